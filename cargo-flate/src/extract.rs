@@ -0,0 +1,203 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cargo flate extract`, which finds the data embedded by `deflate_file!` in a compiled binary.
+//!
+//! The embedded data are byte literals without symbols of their own,
+//! so the binary is scanned for the magic number of the [`Header`] instead.
+//! A match is only accepted if the data after it decompress to the recorded size and CRC-32,
+//! which rules out bytes that happen to look like a header.
+//! Chunked data cannot be decompressed as a whole and are not found.
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use include_flate_compress::{CompressionMethod, Header, HEADER_SIZE};
+
+/// A file found in the binary.
+struct Blob {
+    /// The offset of the header in the binary.
+    offset: usize,
+    header: Header,
+    data: Vec<u8>,
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut binary = None;
+    let mut output = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(crate::value(args, &mut i)?)),
+            arg if binary.is_none() && !arg.starts_with('-') => binary = Some(PathBuf::from(arg)),
+            arg => return Err(format!("unexpected argument {:?}", arg)),
+        }
+        i += 1;
+    }
+
+    let binary = binary.ok_or("the path of the binary is required")?;
+    let bytes = fs::read(&binary).map_err(|err| format!("cannot read {:?}: {}", binary, err))?;
+    let blobs = scan(&bytes);
+
+    if let Some(output) = &output {
+        fs::create_dir_all(output).map_err(|err| format!("cannot create {:?}: {}", output, err))?;
+    }
+    for blob in &blobs {
+        println!(
+            "{:#010x}  {:<7}  {:>10} bytes  crc32 {:08x}",
+            blob.offset,
+            method_name(blob.header.method),
+            blob.header.len,
+            blob.header.crc32,
+        );
+        if let Some(output) = &output {
+            let path = output.join(format!("{:08x}.bin", blob.offset));
+            fs::write(&path, &blob.data)
+                .map_err(|err| format!("cannot write {:?}: {}", path, err))?;
+        }
+    }
+    eprintln!("found {} embedded files in {:?}", blobs.len(), binary);
+    Ok(())
+}
+
+fn scan(bytes: &[u8]) -> Vec<Blob> {
+    let mut blobs = Vec::new();
+    let mut offset = 0;
+    while offset + HEADER_SIZE <= bytes.len() {
+        let Some((header, data)) = Header::parse(&bytes[offset..]) else {
+            offset += 1;
+            continue;
+        };
+        match decode(&header, data) {
+            Some(output) => {
+                blobs.push(Blob {
+                    offset,
+                    header,
+                    data: output,
+                });
+                // the size of compressed data is not recorded, but stored data can be skipped
+                offset += HEADER_SIZE;
+                if header.method == CompressionMethod::Store {
+                    offset += header.len as usize;
+                }
+            }
+            None => offset += 1,
+        }
+    }
+    blobs
+}
+
+/// Decompresses the data after `header`,
+/// returning `None` unless they match the size and checksum in the header.
+fn decode(header: &Header, data: &[u8]) -> Option<Vec<u8>> {
+    if header.method == CompressionMethod::Store && header.len > data.len() as u64 {
+        return None;
+    }
+
+    let decoder = header.decoder(data).ok()?;
+    let mut output = Vec::new();
+    decoder.take(header.len).read_to_end(&mut output).ok()?;
+    if output.len() as u64 != header.len || header.verify(&output).is_err() {
+        return None;
+    }
+    Some(output)
+}
+
+/// Returns the name of `method` as it would be written after `with`.
+fn method_name(method: CompressionMethod) -> String {
+    match method {
+        CompressionMethod::Store => "none".to_string(),
+        method => format!("{:?}", method).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use include_flate_compress::{compress_bytes, CompressionOptions};
+
+    use super::*;
+
+    /// Returns `len` bytes that do not decompress to anything.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn blob(method: CompressionMethod, data: &[u8]) -> Vec<u8> {
+        let compressed = compress_bytes(
+            data,
+            CompressionOptions {
+                method,
+                ..CompressionOptions::default()
+            },
+        )
+        .unwrap();
+        Header::new(method, data).prepend(&compressed)
+    }
+
+    #[test]
+    fn test_scan() {
+        let stored = b"stored without compression".to_vec();
+        let text = "include-flate ".repeat(100).into_bytes();
+
+        let mut binary = noise(100);
+        let stored_offset = binary.len();
+        binary.extend(blob(CompressionMethod::Store, &stored));
+        binary.extend(noise(37));
+        // a header followed by data that do not match it
+        let mut fake = Header::new(CompressionMethod::DEFAULT, &text).prepend(&noise(64));
+        binary.append(&mut fake);
+        binary.extend(noise(10));
+        let text_offset = binary.len();
+        binary.extend(blob(CompressionMethod::DEFAULT, &text));
+        binary.extend(noise(50));
+        // a truncated stored blob
+        let mut truncated = blob(CompressionMethod::Store, &stored);
+        truncated.truncate(HEADER_SIZE + 4);
+        binary.append(&mut truncated);
+
+        let blobs = scan(&binary);
+        let found: Vec<_> = blobs
+            .iter()
+            .map(|blob| (blob.offset, blob.header.method, blob.data.as_slice()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (stored_offset, CompressionMethod::Store, &stored[..]),
+                (text_offset, CompressionMethod::DEFAULT, &text[..]),
+            ]
+        );
+        assert_eq!(blobs[1].header.len, text.len() as u64);
+    }
+
+    #[test]
+    fn test_method_name() {
+        assert_eq!(method_name(CompressionMethod::Store), "none");
+        #[cfg(feature = "deflate")]
+        assert_eq!(method_name(CompressionMethod::Deflate), "deflate");
+        #[cfg(feature = "zstd")]
+        assert_eq!(method_name(CompressionMethod::Zstd), "zstd");
+    }
+}
@@ -0,0 +1,215 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cargo flate lock`, which checks and regenerates the `assets.lock` read by the macros.
+//!
+//! Lines other than entries are kept as they are,
+//! and updated entries keep the separator and path they were written with.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// The name of the lockfile in the manifest directory, as read by the codegen crate.
+const LOCK_FILE: &str = "assets.lock";
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut update = false;
+    let mut dir = PathBuf::from(".");
+    let mut files = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--update" => update = true,
+            "--manifest-dir" => dir = PathBuf::from(crate::value(args, &mut i)?),
+            arg if !arg.starts_with('-') => files.push(arg.to_string()),
+            arg => return Err(format!("unexpected argument {:?}", arg)),
+        }
+        i += 1;
+    }
+
+    let path = dir.join(LOCK_FILE);
+    let lock = match fs::read_to_string(&path) {
+        Ok(lock) => lock,
+        Err(err) if err.kind() == io::ErrorKind::NotFound && (update || !files.is_empty()) => {
+            String::new()
+        }
+        Err(err) => return Err(format!("cannot read {:?}: {}", path, err)),
+    };
+
+    let (output, stale) = apply(&lock, &dir, &files, update)?;
+    if update || !files.is_empty() {
+        if output != lock {
+            fs::write(&path, &output).map_err(|err| format!("cannot write {:?}: {}", path, err))?;
+        }
+        eprintln!("updated {:?}", path);
+        return Ok(());
+    }
+
+    if !stale.is_empty() {
+        return Err(format!(
+            "{} does not match {}; run `cargo flate lock --update` if the changes are intended",
+            stale.join(", "),
+            LOCK_FILE
+        ));
+    }
+    eprintln!("all files match {:?}", path);
+    Ok(())
+}
+
+/// Returns the lockfile `lock` with the hashes of the listed files refreshed
+/// if `update` is set, and entries for `files` refreshed or added,
+/// along with the paths of the other entries that do not match their files.
+fn apply(
+    lock: &str,
+    dir: &Path,
+    files: &[String],
+    update: bool,
+) -> Result<(String, Vec<String>), String> {
+    let mut output = String::new();
+    let mut stale = Vec::new();
+    let mut listed = Vec::new();
+
+    for (line_no, line) in lock.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        let (expected, entry) = trimmed
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("{}:{}: expected `<sha256> <path>`", LOCK_FILE, line_no + 1))?;
+        // `sha256sum` prefixes paths with `*` in binary mode
+        let path = entry.trim_start().trim_start_matches('*');
+        let actual = hash(dir, path)?;
+
+        let refresh = update
+            || files
+                .iter()
+                .any(|file| components(file) == components(path));
+        if refresh {
+            output.push_str(&actual);
+            output.push_str(&trimmed[expected.len()..]);
+        } else {
+            if !expected.eq_ignore_ascii_case(&actual) {
+                stale.push(path.to_string());
+            }
+            output.push_str(line);
+        }
+        output.push('\n');
+        listed.push(components(path));
+    }
+
+    for file in files {
+        if !listed.contains(&components(file)) {
+            output.push_str(&format!("{}  {}\n", hash(dir, file)?, file));
+            listed.push(components(file));
+        }
+    }
+
+    Ok((output, stale))
+}
+
+/// Splits `path` in the same way as the macros, so that `./a\b` and `a/b` are the same entry.
+fn components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect()
+}
+
+/// Returns the SHA-256 hash of the file at `path` relative to `dir`, in lowercase hex.
+fn hash(dir: &Path, path: &str) -> Result<String, String> {
+    let file: PathBuf = dir.join(components(path).into_iter().collect::<PathBuf>());
+    let data = fs::read(&file).map_err(|err| format!("cannot read {:?}: {}", file, err))?;
+    Ok(Sha256::digest(&data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-flate-lock-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("assets")).unwrap();
+        fs::write(dir.join("assets/empty.txt"), "").unwrap();
+        fs::write(dir.join("assets/abc.txt"), "abc").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = dir("check");
+        let lock = format!(
+            "# generated\n\n{}  assets/empty.txt\n{} *./assets\\abc.txt\n",
+            ABC,
+            ABC.to_uppercase()
+        );
+        let (output, stale) = apply(&lock, &dir, &[], false).unwrap();
+        assert_eq!(output, lock);
+        assert_eq!(stale, ["assets/empty.txt"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_update() {
+        let dir = dir("update");
+        let lock = format!(
+            "# generated\n{}  assets/empty.txt\n{} *assets/abc.txt\n",
+            EMPTY, EMPTY
+        );
+        let (output, stale) = apply(&lock, &dir, &[], true).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "# generated\n{}  assets/empty.txt\n{} *assets/abc.txt\n",
+                EMPTY, ABC
+            )
+        );
+        assert!(stale.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_add() {
+        let dir = dir("add");
+        let lock = format!("{}  assets/empty.txt\n", ABC);
+        let files = [
+            "assets/abc.txt".to_string(),
+            "./assets/empty.txt".to_string(),
+        ];
+        let (output, _) = apply(&lock, &dir, &files, false).unwrap();
+        assert_eq!(
+            output,
+            format!("{}  assets/empty.txt\n{}  assets/abc.txt\n", EMPTY, ABC)
+        );
+
+        let missing = apply("", &dir, &["assets/missing.txt".to_string()], false);
+        assert!(missing.unwrap_err().contains("cannot read"));
+        assert!(apply("invalid\n", &dir, &[], true).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cargo flate`, a cargo subcommand that audits the files embedded with `flate!`.
+
+use std::env;
+use std::process::ExitCode;
+
+mod extract;
+mod lock;
+mod report;
+
+const USAGE: &str = "\
+Audits the files embedded with include-flate
+
+Usage: cargo flate <COMMAND> [OPTIONS]
+
+Commands:
+  report    Prints the size of every embedded file from the reports written by the macros
+  extract   Finds the files embedded in a compiled binary and decompresses them
+  lock      Checks the files listed in assets.lock against their hashes
+
+Options for `report`:
+  --report <PATH>        Reads the report at PATH instead of searching the target directory;
+                         may be repeated
+  --target-dir <DIR>     Searches DIR for reports [default: $CARGO_TARGET_DIR or `target`]
+  --crate <NAME>         Only prints the files embedded by the crate NAME
+
+Usage of `extract`: cargo flate extract <BINARY> [-o <DIR>]
+  -o, --output <DIR>     Writes each decompressed file to DIR, named after its offset
+
+Usage of `lock`: cargo flate lock [--update] [--manifest-dir <DIR>] [FILE]...
+  --update               Replaces the hashes in assets.lock with those of the current files
+  --manifest-dir <DIR>   The directory of Cargo.toml and assets.lock [default: .]
+  FILE                   Adds FILE to assets.lock, or updates its entry
+";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // cargo passes the name of the subcommand as the first argument
+    if args.first().map(String::as_str) == Some("flate") {
+        args.remove(0);
+    }
+
+    let result = match args.first().map(String::as_str) {
+        Some("report") => report::run(&args[1..]),
+        Some("extract") => extract::run(&args[1..]),
+        Some("lock") => lock::run(&args[1..]),
+        None | Some("-h" | "--help" | "help") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command {:?}\n\n{}", command, USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns the value of the option at `args[*i]`, advancing `i` past it.
+fn value(args: &[String], i: &mut usize) -> Result<String, String> {
+    let name = &args[*i];
+    *i += 1;
+    args.get(*i)
+        .cloned()
+        .ok_or_else(|| format!("{} requires a value", name))
+}
@@ -0,0 +1,356 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cargo flate report`, which prints the reports written by `deflate_file!`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::Value;
+
+/// The environment variable that sets the path of the report,
+/// as read by the codegen crate.
+const REPORT_ENV: &str = "INCLUDE_FLATE_REPORT";
+
+/// The name of the report in `OUT_DIR`, as written by the codegen crate.
+const REPORT_FILE: &str = "include-flate-report.jsonl";
+
+/// Directories in the target directory that never contain a report.
+const SKIPPED_DIRS: &[&str] = &["deps", "incremental", ".fingerprint", "examples", "doc"];
+
+/// A file embedded by `deflate_file!`, as recorded in a report.
+struct Record {
+    algorithm: String,
+    original: u64,
+    compressed: u64,
+}
+
+/// Identifies a record, so that only the last record of a recompiled file is kept.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    krate: String,
+    name: String,
+    cfg: Option<String>,
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut reports = Vec::new();
+    let mut target_dir = None;
+    let mut krate = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--report" => reports.push(PathBuf::from(crate::value(args, &mut i)?)),
+            "--target-dir" => target_dir = Some(PathBuf::from(crate::value(args, &mut i)?)),
+            "--crate" => krate = Some(crate::value(args, &mut i)?),
+            arg => return Err(format!("unexpected argument {:?}", arg)),
+        }
+        i += 1;
+    }
+
+    if reports.is_empty() {
+        reports = find(target_dir)?;
+    }
+
+    let mut records = BTreeMap::new();
+    for path in &reports {
+        read(path, &mut records)?;
+    }
+    if let Some(krate) = &krate {
+        records.retain(|key, _| &key.krate == krate);
+    }
+
+    print!("{}", table(&records));
+    Ok(())
+}
+
+/// Returns the report in `INCLUDE_FLATE_REPORT` and those in the target directory,
+/// from the oldest to the newest.
+fn find(target_dir: Option<PathBuf>) -> Result<Vec<PathBuf>, String> {
+    let target_dir = target_dir
+        .or_else(|| std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("target"));
+
+    let mut reports = Vec::new();
+    if let Some(path) = std::env::var_os(REPORT_ENV).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            reports.push(path);
+        }
+    }
+    walk(&target_dir, &mut reports);
+
+    if reports.is_empty() {
+        return Err(format!(
+            "no report found in {:?}; build the crate with a build script, \
+             or set {} to the path of the report before building",
+            target_dir, REPORT_ENV
+        ));
+    }
+
+    // records in newer reports replace those in older ones
+    reports.sort_by_key(|path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    });
+    reports.dedup();
+    Ok(reports)
+}
+
+fn walk(dir: &Path, reports: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS
+                .iter()
+                .any(|skipped| entry.file_name() == *skipped)
+            {
+                walk(&path, reports);
+            }
+        } else if entry.file_name() == REPORT_FILE {
+            reports.push(path);
+        }
+    }
+}
+
+/// Reads the report at `path` into `records`, replacing earlier records of the same file.
+fn read(path: &Path, records: &mut BTreeMap<Key, Record>) -> Result<(), String> {
+    let report =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {:?}: {}", path, err))?;
+
+    for (line_no, line) in report.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, record) = parse(line)
+            .ok_or_else(|| format!("{}:{}: invalid record", path.display(), line_no + 1))?;
+        records.insert(key, record);
+    }
+    Ok(())
+}
+
+fn parse(line: &str) -> Option<(Key, Record)> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let key = Key {
+        krate: value.get("crate")?.as_str()?.to_string(),
+        name: value.get("name")?.as_str()?.to_string(),
+        cfg: value.get("cfg")?.as_str().map(str::to_string),
+    };
+    let record = Record {
+        algorithm: value.get("algorithm")?.as_str()?.to_string(),
+        original: value.get("original_size")?.as_u64()?,
+        compressed: value.get("compressed_size")?.as_u64()?,
+    };
+    Some((key, record))
+}
+
+/// Formats `records` as a table followed by the totals.
+fn table(records: &BTreeMap<Key, Record>) -> String {
+    let header = [
+        "CRATE",
+        "FILE",
+        "ALGORITHM",
+        "ORIGINAL",
+        "COMPRESSED",
+        "RATIO",
+    ];
+    let mut rows: Vec<[String; 6]> = records
+        .iter()
+        .map(|(key, record)| {
+            let name = match &key.cfg {
+                Some(cfg) => format!("{} (cfg({}))", key.name, cfg),
+                None => key.name.clone(),
+            };
+            [
+                key.krate.clone(),
+                name,
+                record.algorithm.clone(),
+                size(record.original),
+                size(record.compressed),
+                ratio(record.original, record.compressed),
+            ]
+        })
+        .collect();
+
+    let original = records.values().map(|record| record.original).sum();
+    let compressed = records.values().map(|record| record.compressed).sum();
+    rows.push([
+        "total".to_string(),
+        format!("{} files", records.len()),
+        String::new(),
+        size(original),
+        size(compressed),
+        ratio(original, compressed),
+    ]);
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |output: &mut String, row: &[String; 6]| {
+        // the text columns are aligned to the left and the numbers to the right
+        writeln!(
+            output,
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:>w4$}  {:>w5$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            row[5],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+            w5 = widths[5],
+        )
+        .expect("writing to a string does not fail");
+    };
+
+    let mut output = String::new();
+    let (total, files) = rows.split_last().expect("the total row is always present");
+    line(&mut output, &header.map(str::to_string));
+    for row in files {
+        line(&mut output, row);
+    }
+    output.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    output.push('\n');
+    line(&mut output, total);
+    output
+}
+
+/// Formats `bytes` with a binary unit.
+fn size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Formats the compressed size as a percentage of the original size.
+fn ratio(original: u64, compressed: u64) -> String {
+    if original == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", compressed as f64 / original as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"{"crate":"app","name":"assets/logo.png","cfg":null,"algorithm":"none","original_size":2048,"compressed_size":2064,"ratio":1.0078}
+{"crate":"app","name":"assets/text.txt","cfg":"unix","algorithm":"zstd","original_size":3145728,"compressed_size":1048576,"ratio":0.3333}
+
+{"crate":"app","name":"assets/text.txt","cfg":"windows","algorithm":"deflate","original_size":3145728,"compressed_size":1572864,"ratio":0.5000}
+{"crate":"lib","name":"assets/\"quoted\".txt","cfg":null,"algorithm":"deflate","original_size":100,"compressed_size":40,"ratio":0.4000}
+{"crate":"app","name":"assets/logo.png","cfg":null,"algorithm":"none","original_size":1000,"compressed_size":1016,"ratio":1.0160}
+"#;
+
+    fn read_report(name: &str, report: &str) -> Result<BTreeMap<Key, Record>, String> {
+        let path = std::env::temp_dir().join(format!(
+            "cargo-flate-report-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, report).unwrap();
+        let mut records = BTreeMap::new();
+        let result = read(&path, &mut records);
+        fs::remove_file(path).unwrap();
+        result.map(|()| records)
+    }
+
+    #[test]
+    fn test_read() {
+        let records = read_report("read", REPORT).unwrap();
+        let keys: Vec<_> = records
+            .keys()
+            .map(|key| (key.krate.as_str(), key.name.as_str(), key.cfg.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("app", "assets/logo.png", None),
+                ("app", "assets/text.txt", Some("unix")),
+                ("app", "assets/text.txt", Some("windows")),
+                ("lib", "assets/\"quoted\".txt", None),
+            ]
+        );
+
+        // the later record of a recompiled file replaces the earlier one
+        let logo = records.values().next().unwrap();
+        assert_eq!(logo.algorithm, "none");
+        assert_eq!((logo.original, logo.compressed), (1000, 1016));
+
+        let err = read_report("invalid", "{\"crate\":\"app\"}\n")
+            .err()
+            .unwrap();
+        assert!(err.ends_with(":1: invalid record"), "{}", err);
+    }
+
+    #[test]
+    fn test_table() {
+        let output = table(&read_report("table", REPORT).unwrap());
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "CRATE  FILE                            ALGORITHM  ORIGINAL  COMPRESSED   RATIO",
+                "app    assets/logo.png                 none         1000 B      1016 B  101.6%",
+                "app    assets/text.txt (cfg(unix))     zstd        3.0 MiB     1.0 MiB   33.3%",
+                "app    assets/text.txt (cfg(windows))  deflate     3.0 MiB     1.5 MiB   50.0%",
+                "lib    assets/\"quoted\".txt             deflate       100 B        40 B   40.0%",
+                "------------------------------------------------------------------------------",
+                "total  4 files                                     6.0 MiB     2.5 MiB   41.7%",
+            ]
+        );
+
+        let empty = table(&BTreeMap::new());
+        assert!(empty.ends_with("total  0 files                  0 B         0 B      -\n"));
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size(0), "0 B");
+        assert_eq!(size(1023), "1023 B");
+        assert_eq!(size(1536), "1.5 KiB");
+        assert_eq!(size(5 << 30), "5.0 GiB");
+        assert_eq!(size(5 << 40), "5120.0 GiB");
+        assert_eq!(ratio(0, 16), "-");
+        assert_eq!(ratio(3, 1), "33.3%");
+    }
+}
@@ -0,0 +1,189 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The conditions after `if` that decide whether the compressed data are kept.
+
+use include_flate_compress::compression_ratio;
+use quote::quote;
+use syn::Error;
+
+/// A condition on the result of compressing a file.
+///
+/// If the condition does not hold, the file is embedded without compression instead.
+///
+/// ```ignore
+/// if saves_at_least(4KiB)
+/// if larger_than(1024)
+/// if less_than_original and compression_ratio_more_than(20%)
+/// if not(larger_than(1024)) or saves_at_least(100)
+/// if cfg(not(debug_assertions))
+/// ```
+///
+/// `and` binds tighter than `or`, and `not(...)` groups a condition.
+///
+/// `cfg(...)` predicates are evaluated by the compiler in the calling crate,
+/// so both the compressed and the uncompressed data are generated if the result depends on them.
+pub(crate) enum ThresholdCondition {
+    /// Compression saves at least this many bytes.
+    SavesAtLeast(u64),
+    /// The file is larger than this many bytes.
+    LargerThan(u64),
+    /// The compressed data are smaller than the file.
+    LessThanOriginal,
+    /// Compression saves more than this percentage of the size of the file.
+    CompressionRatioMoreThan(f64),
+    /// The predicate holds in the configuration that the calling crate is compiled for.
+    Cfg(Box<syn::Meta>),
+    Not(Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+}
+
+mod kw {
+    syn::custom_keyword!(and);
+    syn::custom_keyword!(or);
+}
+
+impl syn::parse::Parse for ThresholdCondition {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut condition = Self::parse_and(input)?;
+        while input.peek(kw::or) {
+            input.parse::<kw::or>()?;
+            condition = Self::Or(Box::new(condition), Box::new(Self::parse_and(input)?));
+        }
+        Ok(condition)
+    }
+}
+
+impl ThresholdCondition {
+    fn parse_and(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut condition = Self::parse_atom(input)?;
+        while input.peek(kw::and) {
+            input.parse::<kw::and>()?;
+            condition = Self::And(Box::new(condition), Box::new(Self::parse_atom(input)?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_atom(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name: syn::Ident = input.parse()?;
+        if name == "less_than_original" {
+            return Ok(Self::LessThanOriginal);
+        }
+
+        let content;
+        syn::parenthesized!(content in input);
+        let condition = match name.to_string().as_str() {
+            "saves_at_least" => Self::SavesAtLeast(crate::parse_size(&content)?),
+            "larger_than" => Self::LargerThan(crate::parse_size(&content)?),
+            "compression_ratio_more_than" => {
+                let percent = if content.peek(syn::LitFloat) {
+                    content.parse::<syn::LitFloat>()?.base10_parse()?
+                } else {
+                    content.parse::<syn::LitInt>()?.base10_parse()?
+                };
+                content.parse::<syn::Token![%]>()?;
+                Self::CompressionRatioMoreThan(percent)
+            }
+            "not" => Self::Not(Box::new(content.parse()?)),
+            "cfg" => Self::Cfg(Box::new(content.parse()?)),
+            _ => {
+                return Err(Error::new(
+                    name.span(),
+                    "expected one of: `saves_at_least`, `larger_than`, `less_than_original`, \
+                     `compression_ratio_more_than`, `cfg`, `not`",
+                ))
+            }
+        };
+        if !content.is_empty() {
+            return Err(content.error("unexpected token"));
+        }
+        Ok(condition)
+    }
+
+    /// Tells whether the data compressed from `original` bytes into `compressed` bytes are kept.
+    ///
+    /// Before compression, `compressed` is `None`.
+    /// `None` is returned if the result depends on the compressed size or on a `cfg` predicate.
+    pub(crate) fn evaluate(&self, original: u64, compressed: Option<u64>) -> Option<bool> {
+        match self {
+            Self::SavesAtLeast(bytes) => {
+                compressed.map(|compressed| original.saturating_sub(compressed) >= *bytes)
+            }
+            Self::LargerThan(bytes) => Some(original > *bytes),
+            Self::LessThanOriginal => compressed.map(|compressed| compressed < original),
+            Self::CompressionRatioMoreThan(percent) => compressed
+                .map(|compressed| 100.0 - compression_ratio(original, compressed) > *percent),
+            Self::Cfg(_) => None,
+            Self::Not(condition) => condition.evaluate(original, compressed).map(|holds| !holds),
+            Self::And(lhs, rhs) => match (
+                lhs.evaluate(original, compressed),
+                rhs.evaluate(original, compressed),
+            ) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Self::Or(lhs, rhs) => match (
+                lhs.evaluate(original, compressed),
+                rhs.evaluate(original, compressed),
+            ) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    /// Resolves the condition for data compressed from `original` bytes into `compressed` bytes,
+    /// leaving only the `cfg` predicates to the compiler.
+    pub(crate) fn resolve(&self, original: u64, compressed: u64) -> Resolved {
+        match self {
+            Self::Cfg(meta) => Resolved::Cfg(quote!(#meta)),
+            Self::Not(condition) => match condition.resolve(original, compressed) {
+                Resolved::Const(holds) => Resolved::Const(!holds),
+                Resolved::Cfg(predicate) => Resolved::Cfg(quote!(not(#predicate))),
+            },
+            Self::And(lhs, rhs) => match (
+                lhs.resolve(original, compressed),
+                rhs.resolve(original, compressed),
+            ) {
+                (Resolved::Const(false), _) | (_, Resolved::Const(false)) => Resolved::Const(false),
+                (Resolved::Const(true), other) | (other, Resolved::Const(true)) => other,
+                (Resolved::Cfg(lhs), Resolved::Cfg(rhs)) => Resolved::Cfg(quote!(all(#lhs, #rhs))),
+            },
+            Self::Or(lhs, rhs) => match (
+                lhs.resolve(original, compressed),
+                rhs.resolve(original, compressed),
+            ) {
+                (Resolved::Const(true), _) | (_, Resolved::Const(true)) => Resolved::Const(true),
+                (Resolved::Const(false), other) | (other, Resolved::Const(false)) => other,
+                (Resolved::Cfg(lhs), Resolved::Cfg(rhs)) => Resolved::Cfg(quote!(any(#lhs, #rhs))),
+            },
+            _ => Resolved::Const(
+                self.evaluate(original, Some(compressed))
+                    .expect("only `cfg` predicates are unknown after compression"),
+            ),
+        }
+    }
+}
+
+/// A condition after compression.
+pub(crate) enum Resolved {
+    /// Whether the compressed data are kept in every configuration.
+    Const(bool),
+    /// The `cfg` predicate under which the compressed data are kept.
+    Cfg(proc_macro2::TokenStream),
+}
@@ -0,0 +1,434 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The defaults in the optional `include-flate.toml` of the crate,
+//! and the environment variables that override them.
+//!
+//! ```toml
+//! algorithm = "zstd"
+//! level = "max"
+//! warning-threshold = 5
+//!
+//! [[override]]
+//! glob = "assets/images/**"
+//! algorithm = "none"
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use include_flate_compress::{CompressionLevel, CompressionMethod};
+use proc_macro2::Span;
+
+/// The name of the configuration file, relative to `CARGO_MANIFEST_DIR`.
+pub const CONFIG_FILE: &str = "include-flate.toml";
+
+/// The environment variable that overrides the `algorithm` in the configuration file.
+pub const ALGORITHM_ENV: &str = "INCLUDE_FLATE_DEFAULT_ALGO";
+
+/// The environment variable that overrides the `warning-threshold` in the configuration file.
+pub const THRESHOLD_ENV: &str = "INCLUDE_FLATE_THRESHOLD";
+
+/// The compression ratio in percent below which a warning is emitted
+/// unless `warning-threshold` is set.
+#[cfg(not(feature = "no-compression-warnings"))]
+pub const DEFAULT_WARNING_THRESHOLD: f64 = 10.0;
+
+/// The settings that apply to a file.
+/// Unset settings fall back to the built-in defaults.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Settings {
+    /// The method used when the macro does not specify one.
+    pub algorithm: Option<CompressionMethod>,
+    pub level: Option<CompressionLevel>,
+    /// The compression ratio in percent below which a warning is emitted.
+    pub warning_threshold: Option<f64>,
+}
+
+impl Settings {
+    /// Overrides the settings that are set in `other`.
+    fn merge(&mut self, other: &Settings) {
+        self.algorithm = other.algorithm.or(self.algorithm);
+        self.level = other.level.or(self.level);
+        self.warning_threshold = other.warning_threshold.or(self.warning_threshold);
+    }
+}
+
+#[derive(Default)]
+pub struct Config {
+    /// The configuration file, if it exists.
+    pub path: Option<PathBuf>,
+    defaults: Settings,
+    /// The `[[override]]` tables in the order they are declared.
+    overrides: Vec<(Pattern, Settings)>,
+}
+
+impl Config {
+    /// Returns the settings that apply to an asset read from `files`.
+    ///
+    /// An override applies if its glob matches every file of the asset.
+    /// Later overrides take precedence over earlier ones.
+    pub fn settings(&self, files: &[String]) -> Settings {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+
+        let mut settings = self.defaults;
+        for (pattern, overrides) in &self.overrides {
+            let matches = files.iter().all(|file| {
+                // match with `/` so that the same globs work on all platforms
+                pattern.matches_with(&crate::components(file).join("/"), options)
+            });
+            if matches {
+                settings.merge(overrides);
+            }
+        }
+        settings
+    }
+}
+
+/// Reads the configuration file in `dir`, then applies the environment variables.
+///
+/// The environment variables override the defaults of the crate,
+/// but not the overrides for specific files.
+/// If neither the file nor the variables exist, every setting is unset.
+pub fn load(dir: &Path) -> Result<Config, String> {
+    let mut config = read(dir)?;
+
+    if let Some(algorithm) = env(ALGORITHM_ENV)? {
+        config.defaults.algorithm =
+            Some(parse_method(&algorithm).map_err(|err| format!("{}: {}", ALGORITHM_ENV, err))?);
+    }
+    if let Some(threshold) = env(THRESHOLD_ENV)? {
+        let threshold = threshold
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|_| format!("{}: {:?} is not a number", THRESHOLD_ENV, threshold))?;
+        config.defaults.warning_threshold = Some(threshold);
+    }
+
+    Ok(config)
+}
+
+/// Returns the value of an environment variable, treating an empty value as unset.
+fn env(name: &str) -> Result<Option<String>, String> {
+    match std::env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(format!("{} must be valid UTF-8", name)),
+    }
+}
+
+fn read(dir: &Path) -> Result<Config, String> {
+    let path = dir.join(CONFIG_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(err) => return Err(format!("cannot read {}: {}", CONFIG_FILE, err)),
+    };
+    let table: toml::Table = text
+        .parse()
+        .map_err(|err| format!("cannot parse {}: {}", CONFIG_FILE, err))?;
+
+    let mut config = Config {
+        path: Some(path),
+        ..Config::default()
+    };
+    for (key, value) in &table {
+        if key != "override" {
+            parse_setting(&mut config.defaults, key, value)?;
+            continue;
+        }
+
+        let overrides = value
+            .as_array()
+            .ok_or_else(|| format!("{}: `override` must be an array of tables", CONFIG_FILE))?;
+        for table in overrides {
+            let table = table
+                .as_table()
+                .ok_or_else(|| format!("{}: `override` must be an array of tables", CONFIG_FILE))?;
+
+            let mut pattern = None;
+            let mut settings = Settings::default();
+            for (key, value) in table {
+                if key == "glob" {
+                    let glob = expect_str(key, value)?;
+                    pattern = Some(Pattern::new(glob).map_err(|err| {
+                        format!("{}: invalid glob {:?}: {}", CONFIG_FILE, glob, err)
+                    })?);
+                } else {
+                    parse_setting(&mut settings, key, value)?;
+                }
+            }
+            let pattern = pattern
+                .ok_or_else(|| format!("{}: every `override` must have a `glob`", CONFIG_FILE))?;
+            config.overrides.push((pattern, settings));
+        }
+    }
+    Ok(config)
+}
+
+fn parse_setting(settings: &mut Settings, key: &str, value: &toml::Value) -> Result<(), String> {
+    match key {
+        "algorithm" => {
+            settings.algorithm = Some(
+                parse_method(expect_str(key, value)?)
+                    .map_err(|err| format!("{}: {}", CONFIG_FILE, err))?,
+            )
+        }
+        "level" => {
+            settings.level = Some(match expect_str(key, value)? {
+                "fast" => CompressionLevel::Fast,
+                "balanced" => CompressionLevel::Balanced,
+                "max" => CompressionLevel::Max,
+                level => {
+                    return Err(format!(
+                        "{}: unknown level {:?}, expected one of \"fast\", \"balanced\", \"max\"",
+                        CONFIG_FILE, level
+                    ))
+                }
+            })
+        }
+        "warning-threshold" => {
+            settings.warning_threshold = Some(match value {
+                toml::Value::Integer(threshold) => *threshold as f64,
+                toml::Value::Float(threshold) => *threshold,
+                _ => return Err(format!("{}: `{}` must be a number", CONFIG_FILE, key)),
+            })
+        }
+        _ => return Err(format!("{}: unknown key `{}`", CONFIG_FILE, key)),
+    }
+    Ok(())
+}
+
+/// Resolves the name of an algorithm as it would be written after `with`.
+///
+/// Only the algorithms that compress into a single stream are accepted,
+/// since the container of the data must be known from the macro call.
+fn parse_method(name: &str) -> Result<CompressionMethod, String> {
+    match name {
+        "zstd" => Ok(CompressionMethod::Zstd),
+        "none" => Ok(CompressionMethod::Store),
+        "deflate" | "gzip" | "zlib" | "lz4" | "xz" | "snappy" => {
+            crate::optional_method(Span::call_site(), name).map_err(|err| err.to_string())
+        }
+        _ => Err(format!("unknown algorithm {:?}", name)),
+    }
+}
+
+fn expect_str<'a>(key: &str, value: &'a toml::Value) -> Result<&'a str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("{}: `{}` must be a string", CONFIG_FILE, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(name: &str, config: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "include-flate-config-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        if let Some(config) = config {
+            fs::write(dir.join(CONFIG_FILE), config).unwrap();
+        }
+        dir
+    }
+
+    fn files(files: &[&str]) -> Vec<String> {
+        files.iter().map(|file| file.to_string()).collect()
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = dir("missing", None);
+        let config = read(&dir).unwrap();
+        assert_eq!(config.path, None);
+        let settings = config.settings(&files(&["assets/a.txt"]));
+        assert_eq!(settings.algorithm, None);
+        assert_eq!(settings.level, None);
+        assert_eq!(settings.warning_threshold, None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_override() {
+        let dir = dir(
+            "override",
+            Some(
+                r#"
+algorithm = "zstd"
+level = "max"
+warning-threshold = 5
+
+[[override]]
+glob = "assets/images/**"
+algorithm = "none"
+"#,
+            ),
+        );
+        let config = read(&dir).unwrap();
+        assert_eq!(config.path, Some(dir.join(CONFIG_FILE)));
+
+        let settings = config.settings(&files(&["assets/a.txt"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Zstd));
+        assert_eq!(settings.level, Some(CompressionLevel::Max));
+        assert_eq!(settings.warning_threshold, Some(5.0));
+
+        // the override replaces the default algorithm and keeps the other defaults
+        let settings = config.settings(&files(&["./assets\\images/logo.png"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Store));
+        assert_eq!(settings.level, Some(CompressionLevel::Max));
+
+        // an override only applies if it matches every file
+        let settings = config.settings(&files(&["assets/images/logo.png", "assets/a.txt"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Zstd));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_glob_precedence() {
+        let dir = dir(
+            "precedence",
+            Some(
+                r#"
+[[override]]
+glob = "assets/**"
+algorithm = "zstd"
+level = "fast"
+
+[[override]]
+glob = "assets/*.png"
+algorithm = "none"
+
+[[override]]
+glob = "*.png"
+level = "max"
+"#,
+            ),
+        );
+        let config = read(&dir).unwrap();
+
+        let settings = config.settings(&files(&["assets/logo.png"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Store));
+        assert_eq!(settings.level, Some(CompressionLevel::Fast));
+
+        // `*` does not match `/`
+        let settings = config.settings(&files(&["assets/images/logo.png"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Zstd));
+        assert_eq!(settings.level, Some(CompressionLevel::Fast));
+
+        let settings = config.settings(&files(&["logo.png"]));
+        assert_eq!(settings.algorithm, None);
+        assert_eq!(settings.level, Some(CompressionLevel::Max));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid() {
+        for (name, config, error) in [
+            (
+                "algorithm",
+                r#"algorithm = "brotli""#,
+                "unknown algorithm \"brotli\"",
+            ),
+            ("level", r#"level = "best""#, "unknown level \"best\""),
+            (
+                "threshold",
+                r#"warning-threshold = "5""#,
+                "must be a number",
+            ),
+            (
+                "key",
+                r#"compression = "zstd""#,
+                "unknown key `compression`",
+            ),
+            (
+                "glob",
+                "[[override]]\nalgorithm = \"none\"",
+                "must have a `glob`",
+            ),
+            ("syntax", "algorithm = ", "cannot parse"),
+            (
+                "override",
+                "[[override]]\nglob = \"*\"\nlevel = 3",
+                "`level` must be a string",
+            ),
+        ] {
+            let dir = dir(&format!("invalid-{}", name), Some(config));
+            let err = read(&dir).err().unwrap();
+            assert!(err.contains(CONFIG_FILE), "{}", err);
+            assert!(err.contains(error), "{}", err);
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    // the variables are only read by this test, so that the other tests are not affected
+    #[test]
+    fn test_env() {
+        let dir = dir(
+            "env",
+            Some(
+                r#"
+algorithm = "zstd"
+warning-threshold = 5
+
+[[override]]
+glob = "assets/images/**"
+algorithm = "zstd"
+"#,
+            ),
+        );
+
+        std::env::set_var(ALGORITHM_ENV, "none");
+        std::env::set_var(THRESHOLD_ENV, " 2.5% ");
+        let config = load(&dir).unwrap();
+        let settings = config.settings(&files(&["assets/a.txt"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Store));
+        assert_eq!(settings.warning_threshold, Some(2.5));
+        // the variables do not override the overrides for specific files
+        let settings = config.settings(&files(&["assets/images/logo.png"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Zstd));
+
+        // empty values are ignored
+        std::env::set_var(ALGORITHM_ENV, "");
+        std::env::set_var(THRESHOLD_ENV, "");
+        let settings = load(&dir).unwrap().settings(&files(&["assets/a.txt"]));
+        assert_eq!(settings.algorithm, Some(CompressionMethod::Zstd));
+        assert_eq!(settings.warning_threshold, Some(5.0));
+
+        std::env::set_var(ALGORITHM_ENV, "brotli");
+        let err = load(&dir).err().unwrap();
+        assert!(err.starts_with(ALGORITHM_ENV), "{}", err);
+        assert!(err.contains("unknown algorithm \"brotli\""), "{}", err);
+
+        std::env::remove_var(ALGORITHM_ENV);
+        std::env::set_var(THRESHOLD_ENV, "five");
+        let err = load(&dir).err().unwrap();
+        assert_eq!(err, format!("{}: \"five\" is not a number", THRESHOLD_ENV));
+
+        std::env::remove_var(THRESHOLD_ENV);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of `#[derive(FlateAssets)]`.
+
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Error, Ident, LitStr};
+
+/// The arguments of a
+/// `#[flate("file" with algo checksum hash signed by "VAR" validate format if condition)]`
+/// field attribute.
+struct FieldArgs {
+    path: LitStr,
+    /// The algorithm, along with its parameters and the algorithms of other targets, if any.
+    algo: Option<proc_macro2::TokenStream>,
+    hash: Option<Ident>,
+    /// `signed`, along with the environment variable of the key, if any.
+    signed: Option<proc_macro2::TokenStream>,
+    format: Option<Ident>,
+    /// The condition, starting with `if`.
+    condition: Option<proc_macro2::TokenStream>,
+}
+
+impl syn::parse::Parse for FieldArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let algo = if input.peek(kw::with) {
+            input.parse::<kw::with>()?;
+            let algo: Ident = input.parse()?;
+            let params = if input.peek(syn::token::Paren) {
+                Some(input.parse::<proc_macro2::Group>()?)
+            } else {
+                None
+            };
+            let dict = if input.peek(kw::dict) {
+                let kw = input.parse::<kw::dict>()?;
+                let dict: proc_macro2::TokenTree = input.parse()?;
+                Some(quote!(#kw #dict))
+            } else {
+                None
+            };
+            // the algorithms of other targets, up to `checksum`, `validate` or `if`
+            let mut targets = proc_macro2::TokenStream::new();
+            if input.peek(kw::on) {
+                while !input.is_empty()
+                    && !input.peek(kw::checksum)
+                    && !input.peek(kw::signed)
+                    && !input.peek(kw::validate)
+                    && !input.peek(syn::Token![if])
+                {
+                    targets.extend([input.parse::<proc_macro2::TokenTree>()?]);
+                }
+            }
+            Some(quote!(#algo #params #dict #targets))
+        } else {
+            None
+        };
+        let hash = if input.peek(kw::checksum) {
+            input.parse::<kw::checksum>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let signed = if input.peek(kw::signed) {
+            let signed = input.parse::<kw::signed>()?;
+            if input.peek(kw::by) {
+                let by = input.parse::<kw::by>()?;
+                let key_env: LitStr = input.parse()?;
+                Some(quote!(#signed #by #key_env))
+            } else {
+                Some(quote!(#signed))
+            }
+        } else {
+            None
+        };
+        let format = if input.peek(kw::validate) {
+            input.parse::<kw::validate>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let condition = if input.peek(syn::Token![if]) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            algo,
+            hash,
+            signed,
+            format,
+            condition,
+        })
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(dict);
+    syn::custom_keyword!(checksum);
+    syn::custom_keyword!(signed);
+    syn::custom_keyword!(by);
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(on);
+}
+
+pub(crate) fn expand(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`FlateAssets` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut inits = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("flate"))
+            .ok_or_else(|| {
+                Error::new(
+                    ident.span(),
+                    "every field must have a `#[flate(\"file\")]` attribute",
+                )
+            })?;
+        let FieldArgs {
+            path,
+            algo,
+            hash,
+            signed,
+            format,
+            condition,
+        } = attr.parse_args()?;
+        let algo = algo.map(|algo| quote!(with #algo));
+        let hash = hash.map(|hash| quote!(checksum #hash));
+        let format = format.map(|format| quote!(validate #format));
+
+        let init = if is_str(&field.ty)? {
+            quote! {
+                static FLATE: ::include_flate::Flate<::std::string::String> =
+                    ::include_flate::__flate_init!(str [String] #ident #path #algo #hash #signed #format #condition);
+            }
+        } else {
+            quote! {
+                static FLATE: ::include_flate::Flate<::std::vec::Vec<u8>> =
+                    ::include_flate::__flate_init!([u8] [Vec] #ident #path #algo #hash #signed #format #condition);
+            }
+        };
+        inits.push(quote!(#ident: { #init &FLATE }));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::include_flate::FlateAssets for #name #ty_generics #where_clause {
+            fn assets() -> Self {
+                Self { #(#inits,)* }
+            }
+        }
+    })
+}
+
+/// Tells whether a field holds a `str` or a `[u8]` asset from its type,
+/// which is either one of the `FlateStr`/`FlateBytes` aliases
+/// or a reference to `Flate<String>`/`Flate<Vec<u8>>`.
+fn is_str(ty: &syn::Type) -> syn::Result<bool> {
+    let error = || {
+        Error::new(
+            ty.span(),
+            "the field must be of type `FlateStr` or `FlateBytes`",
+        )
+    };
+
+    let ty = match ty {
+        syn::Type::Reference(reference) => &*reference.elem,
+        ty => ty,
+    };
+    let syn::Type::Path(path) = ty else {
+        return Err(error());
+    };
+    let segment = path.path.segments.last().ok_or_else(error)?;
+
+    match segment.ident.to_string().as_str() {
+        "FlateStr" => Ok(true),
+        "FlateBytes" => Ok(false),
+        "Flate" => {
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return Err(error());
+            };
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Path(inner))) => {
+                    match inner.path.segments.last() {
+                        Some(inner) if inner.ident == "String" => Ok(true),
+                        Some(inner) if inner.ident == "Vec" => Ok(false),
+                        _ => Err(error()),
+                    }
+                }
+                _ => Err(error()),
+            }
+        }
+        _ => Err(error()),
+    }
+}
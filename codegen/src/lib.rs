@@ -15,34 +15,121 @@
 
 extern crate proc_macro;
 
-use std::fs::{self, File};
-use std::io::{Read, Seek};
-use std::path::PathBuf;
-use std::str::{from_utf8, FromStr};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::from_utf8;
 
-use include_flate_compress::{apply_compression, CompressionMethod};
+#[cfg(not(feature = "no-compression-warnings"))]
+use include_flate_compress::compression_ratio;
+use include_flate_compress::{
+    compress_bytes, compress_chunked, compress_seekable, compress_with_dict, CompressionExtras,
+    CompressionMethod, CompressionOptions, FlateCompressionError, HashAlgorithm, Header,
+    DEFAULT_BLOCK_SIZE, DEFAULT_FRAME_SIZE, ZSTD_DEFAULT_WINDOW_LOG_MAX,
+};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use proc_macro_error::{emit_warning, proc_macro_error};
+use proc_macro_error::emit_warning;
+use proc_macro_error::proc_macro_error;
 use quote::quote;
+use sha2::{Digest, Sha256};
 use syn::{Error, LitByteStr};
 
+mod condition;
+mod config;
+mod derive;
+mod lock;
+mod report;
+mod shared;
+mod signing;
+mod target;
+mod validate;
+
 /// `deflate_file!("file")` is equivalent to `include_bytes!("file.gz")`.
 ///
 /// # Parameters
-/// This macro accepts exactly one literal parameter that refers to a path relative to
-/// `CARGO_MANIFEST_DIR`. Absolute paths are not supported.
+/// This macro accepts a literal parameter that refers to a path relative to
+/// `CARGO_MANIFEST_DIR`, optionally followed by the compression algorithm.
+/// Absolute paths are not supported.
 ///
 /// Note that **this is distinct from the behaviour of the builtin `include_bytes!`/`include_str!` macros** &mdash;
 /// `includle_bytes!`/`include_str!` paths are relative to the current source file, while `deflate_file!` paths are relative to
 /// `CARGO_MANIFEST_DIR`.
 ///
+/// Both `/` and `\` are accepted as path separators on all platforms.
+///
+/// Instead of a single path, a list of paths such as `["head.sql", "body.sql"]` can be passed,
+/// optionally followed by `joined "separator"`.
+/// The files are concatenated with the separator between them before compression.
+///
+/// The arguments may end with `if $condition`, e.g. `if saves_at_least(4096)`,
+/// in which case the file is embedded without compression unless the compressed data
+/// satisfy the condition.
+///
+/// The chunked algorithms split the file into blocks of 64 KiB,
+/// or of another size with `of $size`, e.g. `zstd_chunked of 4KiB`.
+///
+/// The algorithm can be followed by `on "pattern"` and other algorithms,
+/// e.g. `zstd on "x86_64-*", deflate on "wasm32-*", none`,
+/// to select the algorithm by the target of the calling crate.
+/// The file is compressed with each algorithm, and each output is gated behind a `#[cfg]`
+/// predicate derived from the pattern.
+///
+/// # Configuration
+/// If `CARGO_MANIFEST_DIR` contains an `include-flate.toml`, it sets the defaults of the crate:
+///
+/// ```toml
+/// # The algorithm of the files that do not specify one.
+/// algorithm = "zstd"
+/// # The compression level of all files: "fast", "balanced" or "max".
+/// level = "max"
+/// # Warn when a file is compressed by less than 5%, instead of 10%.
+/// warning-threshold = 5
+///
+/// # Settings for the files that match a glob, relative to `CARGO_MANIFEST_DIR`.
+/// # Later overrides take precedence over earlier ones.
+/// [[override]]
+/// glob = "assets/images/**"
+/// algorithm = "none"
+/// ```
+///
+/// The algorithm is one of `deflate`, `zstd`, `gzip`, `zlib`, `none`, `lz4`, `xz` and `snappy`.
+/// An override applies to a list of files only if its glob matches every file.
+///
+/// The `INCLUDE_FLATE_DEFAULT_ALGO` and `INCLUDE_FLATE_THRESHOLD` environment variables
+/// override `algorithm` and `warning-threshold` respectively, except in `[[override]]` tables,
+/// e.g. to build a smaller variant in CI without editing the crate.
+///
+/// # Report
+/// Each compressed file is recorded in a report, one JSON object per line, e.g.
+///
+/// ```json
+/// {"crate":"app","name":"assets/logo.png","cfg":null,"algorithm":"zstd","original_size":4096,"compressed_size":1024,"ratio":0.2500}
+/// ```
+///
+/// where `compressed_size` includes the header, `ratio` is `compressed_size / original_size`,
+/// and `cfg` is the `#[cfg]` predicate of the variant if the file has variants for different targets.
+/// The report is written to the path in the `INCLUDE_FLATE_REPORT` environment variable,
+/// or to `include-flate-report.jsonl` in `OUT_DIR` if the crate has a build script.
+/// Records are appended whenever the crate is rebuilt,
+/// so the last record for each crate and name is the current one.
+/// `cargo flate report`, installed with `cargo install cargo-flate`,
+/// prints the reports in the target directory as a table with totals.
+///
 /// # Returns
-/// This macro expands to a `b"byte string"` literal that contains the deflated form of the file.
+/// This macro expands to a `b"byte string"` literal that contains the deflated form of the file,
+/// wrapped in a block that makes cargo rebuild the calling crate when the file
+/// or `include-flate.toml` changes.
+///
+/// The compressed data are preceded by a 16-byte header,
+/// which records the method that was used, and the size and CRC-32 checksum of the file,
+/// so the output can be decompressed with `include_flate::decode` alone.
 ///
 /// # Compile errors
 /// - If the argument is not a single literal
 /// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+/// - If an `include-flate.toml` exists and is invalid
 #[proc_macro]
 #[proc_macro_error]
 pub fn deflate_file(ts: TokenStream) -> TokenStream {
@@ -54,9 +141,14 @@ pub fn deflate_file(ts: TokenStream) -> TokenStream {
 
 /// This macro is identical to `deflate_file!()`, except it additionally performs UTF-8 validation.
 ///
+/// The path and the algorithm may be followed by `validate json`, `validate toml` or
+/// `validate yaml` to also check the syntax of the file,
+/// which requires the `validate-json`, `validate-toml` or `validate-yaml` feature respectively.
+///
 /// # Compile errors
 /// - The compile errors in `deflate_file!`
 /// - If the file contents are not all valid UTF-8
+/// - If the file is not a valid document of the format passed to `validate`
 #[proc_macro]
 #[proc_macro_error]
 pub fn deflate_utf8_file(ts: TokenStream) -> TokenStream {
@@ -66,114 +158,1330 @@ pub fn deflate_utf8_file(ts: TokenStream) -> TokenStream {
     }
 }
 
+/// `delta_file!("file" "base")` is like `deflate_file!("file" zstd)`,
+/// but compresses the file with the contents of `base` as a zstd dictionary.
+///
+/// This stores `file` as a delta against `base`,
+/// which is much smaller than compressing it on its own if the two files are similar.
+/// The same contents of `base` must be passed to `decompress_with_dict`
+/// to decompress the data after the header.
+///
+/// # Compile errors
+/// - The compile errors in `deflate_file!`, for either file
+#[proc_macro]
+#[proc_macro_error]
+pub fn delta_file(ts: TokenStream) -> TokenStream {
+    match delta_inner(ts, false) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// This macro is identical to `delta_file!()`, except it additionally performs UTF-8 validation.
+///
+/// # Compile errors
+/// - The compile errors in `delta_file!`
+/// - If the file contents are not all valid UTF-8
+#[proc_macro]
+#[proc_macro_error]
+pub fn delta_utf8_file(ts: TokenStream) -> TokenStream {
+    match delta_inner(ts, true) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// `dict_file!("file")` expands to a `b"byte string"` literal of the uncompressed contents
+/// of a dictionary passed to `deflate_file!` with `dict "file"`,
+/// wrapped in a block that makes cargo rebuild the calling crate when the file changes.
+///
+/// # Compile errors
+/// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+#[proc_macro]
+pub fn dict_file(ts: TokenStream) -> TokenStream {
+    match dict_file_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn dict_file_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let lit = syn::parse::<syn::LitStr>(ts)?;
+    let (dict, track) = read_asset(&manifest_dir()?, &lit)?;
+    let bytes = LitByteStr::new(&dict, Span::call_site());
+    Ok(quote!({ #track #bytes }))
+}
+
+/// `shared_dict!()` expands to the contents of the dictionary passed to `deflate_file!`
+/// with `dict shared`, wrapped in a block that makes cargo rebuild the calling crate
+/// when a sample of the dictionary changes.
+///
+/// The dictionary is trained from every file under the `assets` directory of the crate,
+/// or the directory in the `INCLUDE_FLATE_SHARED_DICT_DIR` environment variable,
+/// the first time it is used.
+/// It is cached in `OUT_DIR`, or in the system temporary directory if the crate has no build script,
+/// until the samples change.
+/// Adding a file to the directory does not retrain the dictionary until another sample changes.
+///
+/// # Compile errors
+/// - If the samples cannot be read
+/// - If zstd cannot train a dictionary from the samples, e.g. because there are too few
+#[proc_macro]
+pub fn shared_dict(ts: TokenStream) -> TokenStream {
+    match shared_dict_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn shared_dict_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    syn::parse::<syn::parse::Nothing>(ts)?;
+    let shared = shared::load(&manifest_dir()?).map_err(emap)?;
+    let track = shared.samples.iter().map(|sample| track(sample));
+    let path = shared
+        .path
+        .to_str()
+        .ok_or_else(|| emap("the cache directory of the shared dictionary must be valid UTF-8"))?;
+    Ok(quote!({ #(#track)* ::core::include_bytes!(#path) }))
+}
+
+/// `asset_name!("file")` expands to a string literal that identifies the file in runtime
+/// messages and metadata.
+///
+/// By default, this is the path exactly as passed to the macro,
+/// or the paths joined with `+` if a list of files is passed.
+/// If the `strip-paths` feature is enabled, it is an opaque id derived from the path instead,
+/// so that the layout of the source tree does not leak into the compiled artifact.
+/// The id is stable across machines and builds as long as the path does not change.
+///
+/// # Compile errors
+/// - If the argument is not a string literal or a list of string literals
+#[proc_macro]
+pub fn asset_name(ts: TokenStream) -> TokenStream {
+    match syn::parse::<Source>(ts) {
+        Ok(source) => {
+            let name = if cfg!(feature = "strip-paths") {
+                opaque_id(&source.name())
+            } else {
+                source.name()
+            };
+            let name = syn::LitStr::new(&name, source.span);
+            quote!(#name).into()
+        }
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// `file_len!("file")` expands to a `usize` literal of the size of the file in bytes,
+/// i.e. the size of the data that `deflate_file!("file")` decompresses to.
+/// A list of files is accepted as in `deflate_file!`.
+///
+/// # Compile errors
+/// - If the argument is not a string literal or a list of string literals
+/// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+#[proc_macro]
+pub fn file_len(ts: TokenStream) -> TokenStream {
+    match file_len_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn file_len_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let source = syn::parse::<Source>(ts)?;
+    let (vec, _) = source.read(&manifest_dir()?)?;
+    let len = syn::LitInt::new(&format!("{}usize", vec.len()), source.span);
+    Ok(quote!(#len))
+}
+
+/// `file_digest!("file" sha256)` expands to a `b"byte string"` literal of the digest of the file,
+/// i.e. of the data that `deflate_file!("file")` decompresses to,
+/// wrapped in a block that makes cargo rebuild the calling crate when the file changes.
+/// A list of files is accepted as in `deflate_file!`.
+///
+/// The algorithm is `sha256` or `xxh3`, which require the feature of the same name.
+///
+/// # Compile errors
+/// - If the arguments are not a path followed by an algorithm
+/// - If the algorithm is unknown or its feature is not enabled
+/// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+#[proc_macro]
+pub fn file_digest(ts: TokenStream) -> TokenStream {
+    match file_digest_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn file_digest_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let DigestArgs { source, hash } = syn::parse::<DigestArgs>(ts)?;
+    let algorithm = HashAlgorithm::from_name(&hash.to_string()).ok_or_else(|| {
+        Error::new(
+            hash.span(),
+            format!(
+                "`checksum {0}` requires the `{0}` feature of include-flate",
+                hash
+            ),
+        )
+    })?;
+    let (vec, track) = source.read(&manifest_dir()?)?;
+    let bytes = LitByteStr::new(&algorithm.digest(&vec), Span::call_site());
+    Ok(quote!({ #track #bytes }))
+}
+
+/// `file_signature!("file")` expands to a tuple of two `b"byte string"` literals:
+/// the 32-byte Ed25519 verifying key, and the 64-byte signature of the file,
+/// i.e. of the data that `deflate_file!("file")` decompresses to,
+/// wrapped in a block that makes cargo rebuild the calling crate when the file changes.
+/// A list of files is accepted as in `deflate_file!`.
+///
+/// The file is signed with the hex-encoded secret key in the `INCLUDE_FLATE_SIGNING_KEY`
+/// environment variable, or in the variable passed after the path, e.g.
+/// `file_signature!("file" "MY_SIGNING_KEY")`.
+/// Cargo does not rebuild the calling crate when the key changes.
+///
+/// # Compile errors
+/// - If the `signed` feature of include-flate is not enabled
+/// - If the environment variable is not set to 64 hexadecimal digits
+/// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+#[proc_macro]
+pub fn file_signature(ts: TokenStream) -> TokenStream {
+    match file_signature_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn file_signature_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let SignatureArgs { source, key_env } = syn::parse::<SignatureArgs>(ts)?;
+    let key_env = key_env.map_or_else(|| signing::KEY_ENV.to_string(), |lit| lit.value());
+    let (vec, track) = source.read(&manifest_dir()?)?;
+    let (key, signature) =
+        signing::sign(&key_env, &vec).map_err(|err| Error::new(source.span, err))?;
+    let key = LitByteStr::new(&key, Span::call_site());
+    let signature = LitByteStr::new(&signature, Span::call_site());
+    Ok(quote!({ #track (#key, #signature) }))
+}
+
+/// `uncompressed_file!("file")` is like `deflate_file!("file" none)`,
+/// except that `include-flate.toml` is not read.
+/// A list of files is accepted as in `deflate_file!`.
+///
+/// This is used in place of `deflate_file!` in debug builds with the `debug-passthrough` feature,
+/// so the file is only read, and not compressed.
+///
+/// # Compile errors
+/// - If the argument is not a string literal or a list of string literals
+/// - If the referenced file does not exist or is not readable
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+#[doc(hidden)]
+#[proc_macro]
+pub fn uncompressed_file(ts: TokenStream) -> TokenStream {
+    match uncompressed_file_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn uncompressed_file_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let source = syn::parse::<Source>(ts)?;
+    let (vec, track) = source.read(&manifest_dir()?)?;
+    let vec = with_header(CompressionMethod::Store, &vec, &vec);
+    let bytes = LitByteStr::new(&vec, Span::call_site());
+    Ok(quote!({ #track #bytes }))
+}
+
+/// `#[derive(FlateAssets)]` implements `include_flate::FlateAssets` for a struct
+/// whose fields are assets.
+///
+/// Each field must be annotated with `#[flate("file")]`,
+/// optionally followed by `with $algo`, `checksum $hash`, `signed` and,
+/// for `FlateStr` fields, `validate $format`
+/// inside the parentheses, which have the same meaning as in `flate!`,
+/// and must be of type `FlateStr` or `FlateBytes`.
+/// A hidden static is generated for each field,
+/// so the contents are decompressed on first access and shared by all values of the struct.
+///
+/// The generated code refers to the runtime crate as `::include_flate`.
+///
+/// # Compile errors
+/// - If the input is not a struct with named fields
+/// - If a field does not have a `#[flate]` attribute or is not of a supported type
+/// - If a file cannot be compressed, as in `deflate_file!`
+#[proc_macro_derive(FlateAssets, attributes(flate))]
+pub fn flate_assets(ts: TokenStream) -> TokenStream {
+    match syn::parse::<syn::DeriveInput>(ts).and_then(derive::expand) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// `source_path!("file")` expands to an `Option<&'static str>` expression
+/// containing the absolute path of the file in debug builds.
+///
+/// This always expands to `None` unless the `debug-from-disk` feature is enabled.
+/// With the feature enabled, it expands to `Some` with the path under `CARGO_MANIFEST_DIR`
+/// if `debug_assertions` are enabled in the calling crate, and `None` otherwise.
+/// It always expands to `None` for a list of files,
+/// since the concatenated contents do not exist as a single file on disk.
+/// The absolute path is embedded in debug builds even if the `strip-paths` feature is enabled,
+/// but never in release builds.
+///
+/// # Compile errors
+/// - If the argument is not a string literal or a list of string literals
+/// - If a path is absolute
+#[proc_macro]
+pub fn source_path(ts: TokenStream) -> TokenStream {
+    match source_path_inner(ts) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn source_path_inner(ts: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let source = syn::parse::<Source>(ts)?;
+    let mut relatives = source
+        .files
+        .iter()
+        .map(relative_path)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let path = &source.files[0];
+
+    if !cfg!(feature = "debug-from-disk") || source.is_list {
+        return Ok(quote!(::core::option::Option::None::<&'static str>));
+    }
+
+    let target = manifest_dir()?.join(relatives.remove(0));
+    let target = target.to_str().ok_or_else(|| {
+        Error::new(
+            path.span(),
+            "`debug-from-disk` requires the path of the file to be valid UTF-8",
+        )
+    })?;
+    let target = syn::LitStr::new(target, path.span());
+    // `#[cfg]` rather than `cfg!` so that the path is not even compiled into release builds
+    Ok(quote! {{
+        #[cfg(debug_assertions)]
+        let path = ::core::option::Option::Some(#target);
+        #[cfg(not(debug_assertions))]
+        let path = ::core::option::Option::None;
+        path
+    }})
+}
+
+fn opaque_id(path: &str) -> String {
+    // join with `/` so that the id does not depend on the platform of the build
+    let digest = Sha256::digest(components(path).join("/").as_bytes());
+    format!("asset-{}", hex(&digest[..8]))
+}
+
+/// Splits a path passed to the macros into its components.
+///
+/// Both `/` and `\` are accepted as separators on all platforms,
+/// so that the same path can be used regardless of the platform of the build.
+fn components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect()
+}
+
+/// Converts a path passed to the macros into a relative path with native separators.
+fn relative_path(lit: &syn::LitStr) -> syn::Result<PathBuf> {
+    let value = lit.value();
+    let bytes = value.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    if value.starts_with(['/', '\\']) || has_drive || Path::new(&value).is_absolute() {
+        return Err(Error::new(lit.span(), "absolute paths are not supported"));
+    }
+
+    Ok(components(&value).into_iter().collect())
+}
+
+/// Returns `CARGO_MANIFEST_DIR`, which need not be valid UTF-8.
+fn manifest_dir() -> syn::Result<PathBuf> {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| emap("CARGO_MANIFEST_DIR is not set"))
+}
+
+/// Makes cargo rebuild the calling crate when the file at `path` changes.
+///
+/// The file cannot be tracked if its path is not valid UTF-8.
+fn track(path: &Path) -> proc_macro2::TokenStream {
+    match path.to_str() {
+        Some(path) => quote!(
+            const _: &[u8] = ::core::include_bytes!(#path);
+        ),
+        None => quote!(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 /// An arguments expected provided by the proc-macro.
 ///
 /// ```ignore
 /// flate!(pub static DATA: [u8] from "assets/009f.dat"); // default, DEFLATE
 /// flate!(pub static DATA: [u8] from "assets/009f.dat" with zstd); // Use Zstd for this file spcifically
 /// flate!(pub static DATA: [u8] from "assets/009f.dat" with deflate); // Explicitly use DEFLATE.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with zstd_seekable); // Use the zstd seekable format.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with deflate_chunked); // Compress blocks independently.
+/// deflate_file!("assets/009f.dat" zstd_chunked of 4KiB); // Compress blocks of 4 KiB independently.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with zstd(long = 27)); // Tune zstd.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with best); // Use the method with the smallest output.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with fastest_decode); // Favor decompression speed.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with zstd dict "assets/app.dict"); // Use a dictionary.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" with zstd on "x86_64-*", deflate); // Choose by target.
+/// flate!(pub static DATA: [u8] from "assets/009f.dat" if saves_at_least(4096)); // Only compress if it pays off.
 /// ```
 struct FlateArgs {
-    path: syn::LitStr,
+    source: Source,
     algorithm: Option<CompressionMethodTy>,
+    container: Container,
+    /// The size of the blocks that chunked compression splits the file into.
+    block_size: usize,
+    extras: CompressionExtras,
+    dict: Option<Dict>,
+    /// The algorithms selected by target with `on`, in the order they are declared.
+    targets: Vec<target::Target>,
+    format: Option<syn::Ident>,
+    condition: Option<condition::ThresholdCondition>,
+}
+
+/// How the compressed data are laid out.
+enum Container {
+    /// A single compressed stream.
+    Plain,
+    /// The zstd seekable format.
+    Seekable,
+    /// Independently compressed blocks followed by an index.
+    Chunked,
+    /// A single stream compressed with the method selected at compile time.
+    Selected(Selection),
 }
 
 impl syn::parse::Parse for FlateArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let path = input.parse()?;
+        let source = input.parse()?;
 
-        let algorithm = if input.is_empty() {
+        let (algorithm, container) =
+            if input.is_empty() || input.peek(kw::validate) || input.peek(syn::Token![if]) {
+                (None, Container::Plain)
+            } else {
+                parse_algorithm(input)?
+            };
+
+        let mut extras = default_extras(&container);
+        let params_span = input.span();
+        if input.peek(syn::token::Paren) {
+            parse_zstd_params(input, algorithm.as_ref().map(|algo| algo.0), &mut extras)?;
+        }
+
+        let block_size = if matches!(container, Container::Chunked) && input.peek(kw::of) {
+            input.parse::<kw::of>()?;
+            let span = input.span();
+            usize::try_from(parse_size(input)?)
+                .map_err(|_| Error::new(span, "the block size is too large"))?
+        } else {
+            DEFAULT_BLOCK_SIZE
+        };
+
+        let dict = if input.peek(kw::dict) {
+            let kw = input.parse::<kw::dict>()?;
+            let is_zstd = algorithm.as_ref().map(|algo| algo.0) == Some(CompressionMethod::Zstd);
+            if !is_zstd || !matches!(container, Container::Plain) {
+                return Err(Error::new(
+                    kw.span,
+                    "dictionaries are only supported by `with zstd`",
+                ));
+            }
+            if input.peek(kw::shared) {
+                input.parse::<kw::shared>()?;
+                Some(Dict::Shared)
+            } else {
+                Some(Dict::File(input.parse()?))
+            }
+        } else {
             None
+        };
+
+        // only the header of a single stream records the window for the decoder
+        let large_window = extras
+            .zstd_window_log
+            .is_some_and(|log| log > ZSTD_DEFAULT_WINDOW_LOG_MAX);
+        if large_window && (dict.is_some() || !matches!(container, Container::Plain)) {
+            return Err(Error::new(
+                params_span,
+                format!(
+                    "a `window_log` above {} is not supported with seekable, chunked \
+                     or dictionary compression",
+                    ZSTD_DEFAULT_WINDOW_LOG_MAX
+                ),
+            ));
+        }
+
+        let targets = if input.peek(kw::on) {
+            let kw = input.parse::<kw::on>()?;
+            let method = match (&algorithm, &container) {
+                (Some(algo), Container::Plain) if dict.is_none() => algo.0,
+                _ => {
+                    return Err(Error::new(
+                        kw.span,
+                        "`on` is not supported with `best`, `fastest_decode`, \
+                         seekable, chunked or dictionary compression",
+                    ))
+                }
+            };
+            let mut targets = vec![target::Target {
+                pattern: Some(input.parse()?),
+                method,
+                extras,
+            }];
+            while input.peek(syn::Token![,]) {
+                let comma = input.parse::<syn::Token![,]>()?;
+                if targets
+                    .last()
+                    .is_some_and(|target| target.pattern.is_none())
+                {
+                    return Err(Error::new(
+                        comma.span,
+                        "only the last algorithm can be used without `on`",
+                    ));
+                }
+                targets.push(parse_target(input)?);
+            }
+            targets
+        } else {
+            Vec::new()
+        };
+
+        let format = if input.peek(kw::validate) {
+            input.parse::<kw::validate>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let condition = if input.peek(syn::Token![if]) {
+            let token = input.parse::<syn::Token![if]>()?;
+            if dict.is_some() || matches!(container, Container::Seekable | Container::Chunked) {
+                return Err(Error::new(
+                    token.span,
+                    "conditions are not supported with seekable, chunked or dictionary compression",
+                ));
+            }
+            Some(input.parse()?)
         } else {
-            let lookahead = input.lookahead1();
-            if lookahead.peek(kw::deflate) {
-                input.parse::<kw::deflate>()?;
-                Some(CompressionMethodTy(CompressionMethod::Deflate))
-            } else if lookahead.peek(kw::zstd) {
-                input.parse::<kw::zstd>()?;
-                Some(CompressionMethodTy(CompressionMethod::Zstd))
+            None
+        };
+
+        Ok(Self {
+            source,
+            algorithm,
+            container,
+            block_size,
+            extras,
+            dict,
+            targets,
+            format,
+            condition,
+        })
+    }
+}
+
+/// Parses an algorithm after the first one in `with zstd on "x86_64-*", deflate on "wasm32-*"`.
+fn parse_target(input: syn::parse::ParseStream) -> syn::Result<target::Target> {
+    let span = input.span();
+    let method = match parse_algorithm(input)? {
+        (Some(algo), Container::Plain) => algo.0,
+        _ => {
+            return Err(Error::new(
+                span,
+                "`on` is only supported by the algorithms that compress into a single stream",
+            ))
+        }
+    };
+    let mut extras = default_extras(&Container::Plain);
+    if input.peek(syn::token::Paren) {
+        parse_zstd_params(input, Some(method), &mut extras)?;
+    }
+    let pattern = if input.peek(kw::on) {
+        input.parse::<kw::on>()?;
+        Some(input.parse()?)
+    } else {
+        None
+    };
+    Ok(target::Target {
+        pattern,
+        method,
+        extras,
+    })
+}
+
+/// Parses the name of an algorithm, along with the container that it implies.
+fn parse_algorithm(
+    input: syn::parse::ParseStream,
+) -> syn::Result<(Option<CompressionMethodTy>, Container)> {
+    let mut container = Container::Plain;
+    let lookahead = input.lookahead1();
+    let algorithm = if lookahead.peek(kw::deflate) {
+        let kw = input.parse::<kw::deflate>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "deflate")?))
+    } else if lookahead.peek(kw::zstd) {
+        input.parse::<kw::zstd>()?;
+        Some(CompressionMethodTy(CompressionMethod::Zstd))
+    } else if lookahead.peek(kw::zstd_seekable) {
+        input.parse::<kw::zstd_seekable>()?;
+        container = Container::Seekable;
+        Some(CompressionMethodTy(CompressionMethod::Zstd))
+    } else if lookahead.peek(kw::deflate_chunked) {
+        let kw = input.parse::<kw::deflate_chunked>()?;
+        container = Container::Chunked;
+        Some(CompressionMethodTy(optional_method(kw.span, "deflate")?))
+    } else if lookahead.peek(kw::zstd_chunked) {
+        input.parse::<kw::zstd_chunked>()?;
+        container = Container::Chunked;
+        Some(CompressionMethodTy(CompressionMethod::Zstd))
+    } else if lookahead.peek(kw::gzip) {
+        let kw = input.parse::<kw::gzip>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "gzip")?))
+    } else if lookahead.peek(kw::zlib) {
+        let kw = input.parse::<kw::zlib>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "zlib")?))
+    } else if lookahead.peek(kw::none) {
+        input.parse::<kw::none>()?;
+        Some(CompressionMethodTy(CompressionMethod::Store))
+    } else if lookahead.peek(kw::best) {
+        input.parse::<kw::best>()?;
+        container = Container::Selected(Selection::Smallest);
+        None
+    } else if lookahead.peek(kw::fastest_decode) {
+        input.parse::<kw::fastest_decode>()?;
+        container = Container::Selected(Selection::FastestDecode);
+        None
+    } else if lookahead.peek(kw::lz4) {
+        let kw = input.parse::<kw::lz4>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "lz4")?))
+    } else if lookahead.peek(kw::xz) {
+        let kw = input.parse::<kw::xz>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "xz")?))
+    } else if lookahead.peek(kw::snappy) {
+        let kw = input.parse::<kw::snappy>()?;
+        Some(CompressionMethodTy(optional_method(kw.span, "snappy")?))
+    } else {
+        return Err(lookahead.error());
+    };
+    Ok((algorithm, container))
+}
+
+/// The zstd dictionary that a file is compressed with.
+enum Dict {
+    /// The dictionary in a file.
+    File(syn::LitStr),
+    /// The dictionary trained from the assets of the crate.
+    Shared,
+}
+
+/// The window log that `long` enables without a value, as in `zstd --long`.
+const DEFAULT_LONG_WINDOW_LOG: u32 = 27;
+
+/// Parses the parameters in parentheses after a zstd algorithm.
+///
+/// ```ignore
+/// with zstd(long)
+/// with zstd(long = 30, block_size = 4KiB)
+/// with zstd_seekable(window_log = 24)
+/// with zstd(workers = 4)
+/// ```
+fn parse_zstd_params(
+    input: syn::parse::ParseStream,
+    method: Option<CompressionMethod>,
+    extras: &mut CompressionExtras,
+) -> syn::Result<()> {
+    let content;
+    let paren = syn::parenthesized!(content in input);
+    if method != Some(CompressionMethod::Zstd) {
+        return Err(Error::new(
+            paren.span.join(),
+            "parameters are only supported by the zstd algorithms",
+        ));
+    }
+
+    while !content.is_empty() {
+        let key: syn::Ident = content.parse()?;
+        let value = if content.peek(syn::Token![=]) {
+            content.parse::<syn::Token![=]>()?;
+            let value = if key == "block_size" {
+                parse_size(&content)?
             } else {
-                return Err(lookahead.error());
+                content.parse::<syn::LitInt>()?.base10_parse()?
+            };
+            Some(u32::try_from(value).map_err(|_| {
+                Error::new(key.span(), format!("the value of `{}` is too large", key))
+            })?)
+        } else {
+            None
+        };
+
+        match (key.to_string().as_str(), value) {
+            ("long", value) => {
+                extras.zstd_long = true;
+                extras.zstd_window_log = Some(value.unwrap_or(DEFAULT_LONG_WINDOW_LOG));
+            }
+            ("window_log", Some(value)) => extras.zstd_window_log = Some(value),
+            ("block_size", Some(value)) => extras.zstd_target_block_size = Some(value),
+            #[cfg(feature = "zstd-multithread")]
+            ("workers", Some(value)) => extras.zstd_workers = value,
+            #[cfg(not(feature = "zstd-multithread"))]
+            ("workers", Some(_)) => {
+                return Err(Error::new(
+                    key.span(),
+                    "`workers` requires the `zstd-multithread` feature of include-flate",
+                ))
             }
+            ("window_log" | "block_size" | "workers", None) => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("`{}` requires a value", key),
+                ))
+            }
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    "expected one of: `long`, `window_log`, `block_size`, `workers`",
+                ))
+            }
+        }
+
+        if !content.is_empty() {
+            content.parse::<syn::Token![,]>()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a number of bytes, optionally followed by a unit, e.g. `4096`, `4KiB`, `1.5 MB`.
+///
+/// `KB`, `MB` and `GB` are powers of 1000, while `KiB`, `MiB` and `GiB` are powers of 1024.
+fn parse_size(input: syn::parse::ParseStream) -> syn::Result<u64> {
+    let lit: syn::Lit = input.parse()?;
+    let (digits, suffix) = match &lit {
+        syn::Lit::Int(int) => (int.base10_digits(), int.suffix()),
+        syn::Lit::Float(float) => (float.base10_digits(), float.suffix()),
+        _ => {
+            return Err(Error::new(
+                lit.span(),
+                "expected a size in bytes, e.g. `4096` or `4KiB`",
+            ))
+        }
+    };
+    // the unit may also be separated from the number by a space
+    let unit = if suffix.is_empty() && input.peek(syn::Ident) {
+        input.parse::<syn::Ident>()?.to_string()
+    } else {
+        suffix.to_owned()
+    };
+
+    let multiplier: u64 = match unit.as_str() {
+        "" | "B" => 1,
+        "KB" | "kB" => 1000,
+        "MB" => 1000 * 1000,
+        "GB" => 1000 * 1000 * 1000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => {
+            return Err(Error::new(
+                lit.span(),
+                format!(
+                "unknown unit `{}`, expected one of: `B`, `KB`, `MB`, `GB`, `KiB`, `MiB`, `GiB`",
+                unit
+            ),
+            ))
+        }
+    };
+
+    let size = match digits.parse::<u64>() {
+        Ok(count) => count.checked_mul(multiplier),
+        Err(_) => {
+            let size = digits
+                .parse::<f64>()
+                .map_err(|err| Error::new(lit.span(), err))?
+                * multiplier as f64;
+            (size < u64::MAX as f64).then(|| size.round() as u64)
+        }
+    };
+    size.ok_or_else(|| Error::new(lit.span(), "the size is too large"))
+}
+
+/// The extras of a file that does not specify any parameters.
+fn default_extras(container: &Container) -> CompressionExtras {
+    #[allow(unused_mut)]
+    let mut extras = CompressionExtras::default();
+    // seekable frames and chunked blocks are too small to be split between workers
+    #[cfg(feature = "zstd-multithread")]
+    if matches!(container, Container::Plain) {
+        extras.zstd_workers = default_workers();
+    }
+    #[cfg(not(feature = "zstd-multithread"))]
+    let _ = container;
+    extras
+}
+
+/// The number of zstd workers used for a file that does not specify `workers`.
+#[cfg(feature = "zstd-multithread")]
+fn default_workers() -> u32 {
+    std::thread::available_parallelism().map_or(1, |n| n.get() as u32)
+}
+
+/// The file or files that an asset is read from.
+///
+/// ```ignore
+/// "assets/data.txt"
+/// ["assets/head.sql", "assets/body.sql", "assets/tail.sql"] joined "\n"
+/// ```
+struct Source {
+    files: Vec<syn::LitStr>,
+    /// Inserted between the contents of each pair of consecutive files.
+    separator: Option<syn::LitStr>,
+    /// Whether the files are passed as a list, even if there is only one file.
+    is_list: bool,
+    span: Span,
+}
+
+impl syn::parse::Parse for Source {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if !input.peek(syn::token::Bracket) {
+            let file: syn::LitStr = input.parse()?;
+            return Ok(Self {
+                span: file.span(),
+                files: vec![file],
+                separator: None,
+                is_list: false,
+            });
+        }
+
+        let content;
+        let bracket = syn::bracketed!(content in input);
+        let files: Vec<syn::LitStr> =
+            syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+        if files.is_empty() {
+            return Err(Error::new(
+                bracket.span.join(),
+                "expected at least one file",
+            ));
+        }
+
+        let separator = if input.peek(kw::joined) {
+            input.parse::<kw::joined>()?;
+            Some(input.parse()?)
+        } else {
+            None
         };
 
-        Ok(Self { path, algorithm })
+        Ok(Self {
+            files,
+            separator,
+            is_list: true,
+            span: bracket.span.join(),
+        })
+    }
+}
+
+impl Source {
+    /// The paths of the files as passed to the macro.
+    fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|file| file.value()).collect()
+    }
+
+    /// The name of the asset before `strip-paths` is applied.
+    fn name(&self) -> String {
+        self.paths().join("+")
+    }
+
+    /// Reads and concatenates the files relative to `dir`.
+    ///
+    /// Returns the contents, along with the tokens that track changes to the files.
+    fn read(&self, dir: &Path) -> syn::Result<(Vec<u8>, proc_macro2::TokenStream)> {
+        let separator = self.separator.as_ref().map(|separator| separator.value());
+
+        let mut vec = Vec::new();
+        let mut tracks = proc_macro2::TokenStream::new();
+        for (i, file) in self.files.iter().enumerate() {
+            if i > 0 {
+                if let Some(separator) = &separator {
+                    vec.extend_from_slice(separator.as_bytes());
+                }
+            }
+
+            let (contents, track) = read_asset(dir, file)?;
+            vec.extend(contents);
+            tracks.extend(track);
+        }
+        Ok((vec, tracks))
+    }
+}
+
+/// The arguments of `file_digest!`: the file, followed by the name of the hash algorithm.
+struct DigestArgs {
+    source: Source,
+    hash: syn::Ident,
+}
+
+impl syn::parse::Parse for DigestArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            source: input.parse()?,
+            hash: input.parse()?,
+        })
+    }
+}
+
+/// The arguments of `file_signature!`: the file,
+/// optionally followed by the environment variable of the signing key.
+struct SignatureArgs {
+    source: Source,
+    key_env: Option<syn::LitStr>,
+}
+
+impl syn::parse::Parse for SignatureArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            source: input.parse()?,
+            key_env: if input.is_empty() {
+                None
+            } else {
+                Some(input.parse()?)
+            },
+        })
+    }
+}
+
+/// The arguments of `delta_file!` and `delta_utf8_file!`: the path of the file,
+/// followed by the path of the file it is compressed against.
+struct DeltaArgs {
+    path: syn::LitStr,
+    base: syn::LitStr,
+}
+
+impl syn::parse::Parse for DeltaArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            path: input.parse()?,
+            base: input.parse()?,
+        })
     }
 }
 
 mod kw {
     syn::custom_keyword!(deflate);
     syn::custom_keyword!(zstd);
+    syn::custom_keyword!(zstd_seekable);
+    syn::custom_keyword!(deflate_chunked);
+    syn::custom_keyword!(zstd_chunked);
+    syn::custom_keyword!(gzip);
+    syn::custom_keyword!(zlib);
+    syn::custom_keyword!(none);
+    syn::custom_keyword!(best);
+    syn::custom_keyword!(fastest_decode);
+    syn::custom_keyword!(lz4);
+    syn::custom_keyword!(xz);
+    syn::custom_keyword!(snappy);
+    syn::custom_keyword!(dict);
+    syn::custom_keyword!(shared);
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(joined);
+    syn::custom_keyword!(on);
+    syn::custom_keyword!(of);
 }
 
 #[derive(Debug)]
 struct CompressionMethodTy(CompressionMethod);
 
-fn compression_ratio(original_size: u64, compressed_size: u64) -> f64 {
-    (compressed_size as f64 / original_size as f64) * 100.0
+/// The method used when none is specified, unless `include-flate.toml` sets another one.
+///
+/// The runtime crate forwards its `deflate` and `default-zstd` features here,
+/// so that this agrees with `CompressionMethod::DEFAULT` in the runtime crate.
+#[cfg(all(feature = "deflate", not(feature = "default-zstd")))]
+const DEFAULT_METHOD: CompressionMethod = CompressionMethod::Deflate;
+#[cfg(not(all(feature = "deflate", not(feature = "default-zstd"))))]
+const DEFAULT_METHOD: CompressionMethod = CompressionMethod::Zstd;
+
+/// Resolves a method that is only available with the feature of the same name.
+fn optional_method(span: Span, name: &str) -> syn::Result<CompressionMethod> {
+    match name {
+        #[cfg(feature = "deflate")]
+        "deflate" => Ok(CompressionMethod::Deflate),
+        #[cfg(feature = "deflate")]
+        "gzip" => Ok(CompressionMethod::Gzip),
+        #[cfg(feature = "deflate")]
+        "zlib" => Ok(CompressionMethod::Zlib),
+        #[cfg(feature = "lz4")]
+        "lz4" => Ok(CompressionMethod::Lz4),
+        #[cfg(feature = "xz")]
+        "xz" => Ok(CompressionMethod::Xz),
+        #[cfg(feature = "snappy")]
+        "snappy" => Ok(CompressionMethod::Snappy),
+        _ => {
+            // gzip and zlib are variants of DEFLATE
+            let feature = match name {
+                "gzip" | "zlib" => "deflate",
+                name => name,
+            };
+            Err(Error::new(
+                span,
+                format!(
+                    "`with {}` requires the `{}` feature of include-flate",
+                    name, feature
+                ),
+            ))
+        }
+    }
+}
+
+fn emap<E: std::fmt::Display>(error: E) -> Error {
+    Error::new(Span::call_site(), error)
 }
 
 fn inner(ts: TokenStream, utf8: bool) -> syn::Result<impl Into<TokenStream>> {
-    fn emap<E: std::fmt::Display>(error: E) -> Error {
-        Error::new(Span::call_site(), error)
+    let dir = manifest_dir()?;
+
+    let args: FlateArgs = syn::parse2::<FlateArgs>(ts.to_owned().into())?;
+    let (settings, track_config) = load_settings(&dir, args.source.span, &args.source.paths())?;
+    let algo = args.algorithm.unwrap_or(CompressionMethodTy(
+        settings.algorithm.unwrap_or(DEFAULT_METHOD),
+    ));
+
+    let (vec, track) = args.source.read(&dir)?;
+    let name = args.source.name();
+
+    if utf8 {
+        from_utf8(&vec).map_err(emap)?;
     }
 
-    let dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").map_err(emap)?);
+    if let Some(format) = &args.format {
+        validate::validate(format, args.source.span, &name, &vec)?;
+    }
 
-    let args: FlateArgs = syn::parse2::<FlateArgs>(ts.to_owned().into())?;
-    let path = PathBuf::from_str(&args.path.value()).map_err(emap)?;
-    let algo = args
-        .algorithm
-        .unwrap_or(CompressionMethodTy(CompressionMethod::Deflate));
+    let level = settings.level.unwrap_or_default();
+    // each alternative is the `cfg` predicate under which it is used, and how it is compressed
+    let alternatives = if args.targets.is_empty() {
+        let options = CompressionOptions {
+            method: algo.0,
+            level,
+            extras: args.extras,
+        };
+        vec![(quote!(all()), options)]
+    } else {
+        let fallback = target::Target {
+            pattern: None,
+            method: settings.algorithm.unwrap_or(DEFAULT_METHOD),
+            extras: default_extras(&Container::Plain),
+        };
+        let gates = target::gates(&args.targets)?;
+        gates
+            .into_iter()
+            .zip(args.targets.iter().chain([&fallback]))
+            .map(|(gate, target)| {
+                let options = CompressionOptions {
+                    method: target.method,
+                    level,
+                    extras: target.extras,
+                };
+                (gate, options)
+            })
+            .collect()
+    };
+    // a condition that cannot hold whatever the compressed size skips compression entirely
+    let skip = args
+        .condition
+        .as_ref()
+        .is_some_and(|condition| condition.evaluate(vec.len() as u64, None) == Some(false));
+    let mut track_dict = None;
+    let mut variants = Vec::new();
+    for (gate, options) in alternatives {
+        let (method, compressed_buffer) = if skip {
+            Ok((CompressionMethod::Store, vec.clone()))
+        } else {
+            match args.container {
+                Container::Plain => match &args.dict {
+                    Some(Dict::File(path)) => {
+                        let (dict, track) = read_asset(&dir, path)?;
+                        track_dict = Some(track);
+                        compress_with_dict(&vec, &dict, options).map(|data| (options.method, data))
+                    }
+                    Some(Dict::Shared) => {
+                        let shared = shared::load(&dir).map_err(emap)?;
+                        compress_with_dict(&vec, &shared.dict, options)
+                            .map(|data| (options.method, data))
+                    }
+                    None => compress_bytes(&vec, options).map(|data| (options.method, data)),
+                },
+                Container::Seekable => compress_seekable(&vec, options, DEFAULT_FRAME_SIZE)
+                    .map(|data| (options.method, data)),
+                Container::Chunked => compress_chunked(&vec, options, args.block_size)
+                    .map(|data| (options.method, data)),
+                Container::Selected(selection) => compress_selected(&vec, options, selection),
+            }
+        }
+        .map_err(emap)?;
+
+        let resolved = args
+            .condition
+            .as_ref()
+            .map(|condition| condition.resolve(vec.len() as u64, compressed_buffer.len() as u64));
+        let (method, compressed_buffer) = match resolved {
+            Some(condition::Resolved::Const(false)) => (CompressionMethod::Store, vec.clone()),
+            _ => (method, compressed_buffer),
+        };
 
-    if path.is_absolute() {
-        Err(emap("absolute paths are not supported"))?;
+        #[cfg(not(feature = "no-compression-warnings"))]
+        warn_low_ratio(
+            args.source.span,
+            &name,
+            method,
+            &vec,
+            &compressed_buffer,
+            &settings,
+        );
+        let compressed_buffer = with_header(
+            CompressionOptions { method, ..options },
+            &vec,
+            &compressed_buffer,
+        );
+
+        match resolved {
+            Some(condition::Resolved::Cfg(predicate)) => {
+                let stored = with_header(CompressionMethod::Store, &vec, &vec);
+                variants.push((quote!(all(#gate, #predicate)), method, compressed_buffer));
+                variants.push((
+                    quote!(all(#gate, not(#predicate))),
+                    CompressionMethod::Store,
+                    stored,
+                ));
+            }
+            _ => variants.push((gate, method, compressed_buffer)),
+        }
     }
 
-    let target = dir.join(&path);
+    if let Some(path) = report::path() {
+        let records: Vec<_> = variants
+            .iter()
+            .map(|(gate, method, data)| report::Record {
+                name: &name,
+                cfg: (variants.len() > 1).then(|| gate.to_string()),
+                method: *method,
+                original: vec.len(),
+                compressed: data.len(),
+            })
+            .collect();
+        if let Err(err) = report::append(&path, &records) {
+            emit_warning!(
+                args.source.span,
+                "Cannot write the report of {:?} to {:?}: {}",
+                name,
+                path,
+                err
+            );
+        }
+    }
 
-    let mut file = File::open(&target).map_err(emap)?;
+    let bytes = if let [(_, _, data)] = variants.as_slice() {
+        let bytes = LitByteStr::new(data, Span::call_site());
+        quote!(#bytes)
+    } else {
+        // the compiler picks the data for the configuration of the calling crate
+        let items = variants.iter().map(|(gate, _, data)| {
+            let bytes = LitByteStr::new(data, Span::call_site());
+            quote! {
+                #[cfg(#gate)]
+                const BYTES: &[u8] = #bytes;
+            }
+        });
+        quote!(#(#items)* BYTES)
+    };
+    Ok(quote!({ #track #track_dict #track_config #bytes }))
+}
+
+/// How the method of `best` and `fastest_decode` is selected.
+#[derive(Clone, Copy)]
+enum Selection {
+    /// The method that gives the smallest output.
+    Smallest,
+    /// The fastest method to decompress whose output is close to the smallest.
+    FastestDecode,
+}
+
+/// How much larger than the smallest output, in percent,
+/// the output of a faster method may be to be selected by `fastest_decode`.
+const FASTEST_DECODE_TOLERANCE: usize = 10;
+
+/// Compresses `data` with every enabled method and keeps the output chosen by `selection`.
+fn compress_selected(
+    data: &[u8],
+    options: CompressionOptions,
+    selection: Selection,
+) -> Result<(CompressionMethod, Vec<u8>), FlateCompressionError> {
+    // Ordered from the fastest to the slowest to decompress.
+    // gzip and zlib are omitted since they only add a header to DEFLATE.
+    let mut methods = vec![CompressionMethod::Store];
+    #[cfg(feature = "lz4")]
+    methods.push(CompressionMethod::Lz4);
+    #[cfg(feature = "snappy")]
+    methods.push(CompressionMethod::Snappy);
+    methods.push(CompressionMethod::Zstd);
+    #[cfg(feature = "deflate")]
+    methods.push(CompressionMethod::Deflate);
+    #[cfg(feature = "xz")]
+    methods.push(CompressionMethod::Xz);
+
+    let outputs = methods
+        .into_iter()
+        .map(|method| {
+            compress_bytes(data, CompressionOptions { method, ..options })
+                .map(|compressed| (method, compressed))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let smallest = outputs
+        .iter()
+        .map(|(_, compressed)| compressed.len())
+        .min()
+        .expect("at least one method is enabled");
+
+    // the first method wins a tie, so data are stored as is unless compression helps
+    let (method, compressed) = match selection {
+        Selection::Smallest => outputs
+            .into_iter()
+            .find(|(_, compressed)| compressed.len() == smallest),
+        Selection::FastestDecode => outputs.into_iter().find(|(_, compressed)| {
+            compressed.len() * 100 <= smallest * (100 + FASTEST_DECODE_TOLERANCE)
+        }),
+    }
+    .expect("the smallest output is selectable");
+    Ok((method, compressed))
+}
+
+/// Prefixes `compressed` with the header that describes how it was compressed from `original`.
+fn with_header(
+    options: impl Into<CompressionOptions>,
+    original: &[u8],
+    compressed: &[u8],
+) -> Vec<u8> {
+    Header::with_options(&options.into(), original).prepend(compressed)
+}
+
+fn delta_inner(ts: TokenStream, utf8: bool) -> syn::Result<impl Into<TokenStream>> {
+    let dir = manifest_dir()?;
+
+    let args: DeltaArgs = syn::parse2::<DeltaArgs>(ts.to_owned().into())?;
+    let (vec, track) = read_asset(&dir, &args.path)?;
+    let (base, track_base) = read_asset(&dir, &args.base)?;
+    let (settings, track_config) = load_settings(&dir, args.path.span(), &[args.path.value()])?;
 
-    let mut vec = Vec::<u8>::new();
     if utf8 {
-        std::io::copy(&mut file, &mut vec).map_err(emap)?;
         from_utf8(&vec).map_err(emap)?;
     }
 
-    let mut compressed_buffer = Vec::<u8>::new();
+    let options = CompressionOptions {
+        method: CompressionMethod::Zstd,
+        level: settings.level.unwrap_or_default(),
+        ..CompressionOptions::default()
+    };
+    let compressed_buffer = compress_with_dict(&vec, &base, options).map_err(emap)?;
 
-    {
-        let mut compressed_cursor = std::io::Cursor::new(&mut compressed_buffer);
-        let mut source: Box<dyn Read> = if utf8 {
-            Box::new(std::io::Cursor::new(vec))
-        } else {
-            file.seek(std::io::SeekFrom::Start(0)).map_err(emap)?;
-            Box::new(&file)
-        };
+    #[cfg(not(feature = "no-compression-warnings"))]
+    warn_low_ratio(
+        args.path.span(),
+        &args.path.value(),
+        CompressionMethod::Zstd,
+        &vec,
+        &compressed_buffer,
+        &settings,
+    );
+
+    let compressed_buffer = with_header(CompressionMethod::Zstd, &vec, &compressed_buffer);
+    let bytes = LitByteStr::new(&compressed_buffer, Span::call_site());
+    Ok(quote!({ #track #track_base #track_config #bytes }))
+}
+
+/// Reads the file at `lit` relative to `dir` and verifies it against the lockfile.
+///
+/// Returns the contents of the file, along with the tokens that track changes to it
+/// and to the lockfile.
+fn read_asset(dir: &Path, lit: &syn::LitStr) -> syn::Result<(Vec<u8>, proc_macro2::TokenStream)> {
+    let target = dir.join(relative_path(lit)?);
 
-        apply_compression(&mut source, &mut compressed_cursor, algo.0).map_err(emap)?;
+    let mut vec = Vec::<u8>::new();
+    File::open(&target)
+        .and_then(|mut file| file.read_to_end(&mut vec))
+        .map_err(emap)?;
+
+    lock::verify(dir, &lit.value(), &vec).map_err(|err| Error::new(lit.span(), err))?;
+
+    let mut tokens = track(&target);
+    // the file is verified again when the lockfile changes
+    let lock = dir.join(lock::LOCK_FILE);
+    if lock.is_file() {
+        tokens.extend(track(&lock));
     }
+    Ok((vec, tokens))
+}
 
-    let bytes = LitByteStr::new(&compressed_buffer, Span::call_site());
-    let result = quote!(#bytes);
+/// Loads the settings in `include-flate.toml` and the environment that apply to an asset
+/// read from `files`.
+///
+/// Returns the settings, along with the tokens that track changes to the configuration file
+/// and the environment variables.
+fn load_settings(
+    dir: &Path,
+    span: Span,
+    files: &[String],
+) -> syn::Result<(config::Settings, proc_macro2::TokenStream)> {
+    let config = config::load(dir).map_err(|err| Error::new(span, err))?;
+    let mut track = config.path.as_deref().map(track).unwrap_or_default();
+    // rustc records the variables read by `option_env!` so that cargo rebuilds when they change
+    for var in [config::ALGORITHM_ENV, config::THRESHOLD_ENV] {
+        track.extend(quote!(
+            const _: ::core::option::Option<&str> = ::core::option_env!(#var);
+        ));
+    }
+    Ok((config.settings(files), track))
+}
 
-    #[cfg(not(feature = "no-compression-warnings"))]
-    {
-        let compression_ratio = compression_ratio(
-            fs::metadata(&target).map_err(emap)?.len(),
-            compressed_buffer.len() as u64,
-        );
+#[cfg(not(feature = "no-compression-warnings"))]
+fn warn_low_ratio(
+    span: Span,
+    name: &str,
+    method: CompressionMethod,
+    original: &[u8],
+    compressed: &[u8],
+    settings: &config::Settings,
+) {
+    if method == CompressionMethod::Store {
+        return;
+    }
 
-        if compression_ratio < 10.0f64 {
-            emit_warning!(
-            &args.path,
+    let compression_ratio = compression_ratio(original.len() as u64, compressed.len() as u64);
+
+    let threshold = settings
+        .warning_threshold
+        .unwrap_or(config::DEFAULT_WARNING_THRESHOLD);
+    if compression_ratio < threshold {
+        emit_warning!(
+            span,
             "Detected low compression ratio ({:.2}%) for file {:?} with `{:?}`. Consider using other compression methods.",
             compression_ratio,
-            path.display(),
-            algo.0,
+            name,
+            method,
         );
-        }
     }
-
-    Ok(result)
 }
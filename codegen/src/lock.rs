@@ -0,0 +1,157 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// The name of the lockfile, relative to `CARGO_MANIFEST_DIR`.
+pub const LOCK_FILE: &str = "assets.lock";
+
+/// Verifies `data` against the entry for `path` in `assets.lock`.
+///
+/// The lockfile uses the same format as the output of `sha256sum`,
+/// i.e. one `<hex digest>  <path>` entry per line,
+/// so it can be created with `sha256sum assets/* > assets.lock`
+/// and regenerated with `cargo flate lock --update`.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// If the lockfile does not exist, all assets are accepted.
+/// If the lockfile exists, every embedded asset must be listed in it.
+pub fn verify(dir: &Path, path: &str, data: &[u8]) -> Result<(), String> {
+    let lock_path = dir.join(LOCK_FILE);
+    let lock = match fs::read_to_string(&lock_path) {
+        Ok(lock) => lock,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(format!("cannot read {}: {}", LOCK_FILE, err)),
+    };
+
+    let components = crate::components(path);
+    let mut expected = None;
+    for (line_no, line) in lock.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (hash, entry) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("{}:{}: expected `<sha256> <path>`", LOCK_FILE, line_no + 1))?;
+        // `sha256sum` prefixes paths with `*` in binary mode
+        let entry = entry.trim_start().trim_start_matches('*');
+        if crate::components(entry) == components {
+            expected = Some(hash.to_ascii_lowercase());
+        }
+    }
+
+    let expected = expected.ok_or_else(|| {
+        format!(
+            "{:?} is not listed in {}; add its SHA-256 hash to the lockfile",
+            path, LOCK_FILE
+        )
+    })?;
+
+    let actual = crate::hex(&Sha256::digest(data));
+    if expected != actual {
+        return Err(format!(
+            "{:?} does not match {} (expected SHA-256 {}, got {})",
+            path, LOCK_FILE, expected, actual
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn dir(name: &str, lock: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "include-flate-lock-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        if let Some(lock) = lock {
+            fs::write(dir.join(LOCK_FILE), lock).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_no_lock() {
+        let dir = dir("none", None);
+        assert_eq!(verify(&dir, "assets/abc.txt", b"abc"), Ok(()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_match() {
+        let dir = dir("match", Some(&format!("{}  ./assets\\abc.txt\n", ABC)));
+        assert_eq!(verify(&dir, "assets/abc.txt", b"abc"), Ok(()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_entry() {
+        let dir = dir("missing", Some(&format!("{}  assets/other.txt\n", ABC)));
+        let err = verify(&dir, "assets/abc.txt", b"abc").unwrap_err();
+        assert!(err.contains("is not listed in assets.lock"), "{}", err);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let dir = dir("mismatch", Some(&format!("{}  assets/abc.txt\n", ABC)));
+        let err = verify(&dir, "assets/abc.txt", b"abd").unwrap_err();
+        assert!(err.contains("does not match assets.lock"), "{}", err);
+        assert!(err.contains(ABC), "{}", err);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_binary_mode() {
+        let dir = dir(
+            "binary",
+            Some(&format!("{} *assets/abc.txt\n", ABC.to_uppercase())),
+        );
+        assert_eq!(verify(&dir, "assets/abc.txt", b"abc"), Ok(()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_comments() {
+        let lock = format!(
+            "# regenerate with cargo flate lock --update\n\n   \n{}  assets/abc.txt\n  # {}  assets/abc.txt\n",
+            ABC,
+            "0".repeat(64)
+        );
+        let comments = dir("comments", Some(&lock));
+        assert_eq!(verify(&comments, "assets/abc.txt", b"abc"), Ok(()));
+        fs::remove_dir_all(comments).unwrap();
+
+        let invalid = dir("invalid", Some("invalid\n"));
+        let err = verify(&invalid, "assets/abc.txt", b"abc").unwrap_err();
+        assert!(err.contains("assets.lock:1"), "{}", err);
+        fs::remove_dir_all(invalid).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use include_flate_compress::CompressionMethod;
+
+/// The environment variable that sets the path of the report.
+pub const REPORT_ENV: &str = "INCLUDE_FLATE_REPORT";
+
+/// The name of the report in `OUT_DIR`.
+pub const REPORT_FILE: &str = "include-flate-report.jsonl";
+
+/// An asset embedded by `deflate_file!`.
+pub struct Record<'a> {
+    pub name: &'a str,
+    /// The `cfg` predicate under which this variant of the asset is embedded,
+    /// if the asset has variants for different targets or conditions.
+    pub cfg: Option<String>,
+    pub method: CompressionMethod,
+    pub original: usize,
+    /// The length of the embedded bytes, including the header.
+    pub compressed: usize,
+}
+
+/// Returns the path of the report:
+/// the `INCLUDE_FLATE_REPORT` environment variable if set,
+/// or `include-flate-report.jsonl` in `OUT_DIR` if the crate has a build script.
+pub fn path() -> Option<PathBuf> {
+    match std::env::var_os(REPORT_ENV) {
+        Some(path) if !path.is_empty() => Some(path.into()),
+        _ => std::env::var_os("OUT_DIR").map(|dir| PathBuf::from(dir).join(REPORT_FILE)),
+    }
+}
+
+/// Appends `records` to the report at `path`, one JSON object per line.
+///
+/// Each line is written at once, so crates that are compiled in parallel
+/// can share a report.
+/// A record is appended whenever the asset is compressed again,
+/// so readers should keep the last record for each crate and name.
+pub fn append(path: &PathBuf, records: &[Record]) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let krate = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+    for record in records {
+        let ratio = if record.original == 0 {
+            1.0
+        } else {
+            record.compressed as f64 / record.original as f64
+        };
+        let cfg = match &record.cfg {
+            Some(cfg) => string(cfg),
+            None => "null".to_string(),
+        };
+        let line = format!(
+            "{{\"crate\":{},\"name\":{},\"cfg\":{},\"algorithm\":{},\"original_size\":{},\"compressed_size\":{},\"ratio\":{:.4}}}\n",
+            string(&krate),
+            string(record.name),
+            cfg,
+            string(&algorithm(record.method)),
+            record.original,
+            record.compressed,
+            ratio,
+        );
+        file.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Returns the name of `method` as passed to `deflate_file!`.
+fn algorithm(method: CompressionMethod) -> String {
+    match method {
+        CompressionMethod::Store => "none".to_string(),
+        method => format!("{:?}", method).to_lowercase(),
+    }
+}
+
+/// Formats `value` as a JSON string.
+fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_and_append() {
+        let path =
+            std::env::temp_dir().join(format!("include-flate-report-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        std::env::set_var(REPORT_ENV, &path);
+        assert_eq!(super::path(), Some(path.clone()));
+        std::env::set_var(REPORT_ENV, "");
+        std::env::set_var("OUT_DIR", "out");
+        assert_eq!(super::path(), Some(PathBuf::from("out").join(REPORT_FILE)));
+
+        let records = [
+            Record {
+                name: "assets/a.txt",
+                cfg: None,
+                method: CompressionMethod::Store,
+                original: 100,
+                compressed: 116,
+            },
+            Record {
+                name: "assets/\"b\".txt",
+                cfg: Some("all(unix)".to_string()),
+                method: CompressionMethod::DEFAULT,
+                original: 200,
+                compressed: 50,
+            },
+        ];
+        append(&path, &records[..1]).unwrap();
+        append(&path, &records[1..]).unwrap();
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""name":"assets/a.txt","cfg":null,"algorithm":"none""#));
+        assert!(lines[0].contains(r#""original_size":100,"compressed_size":116,"ratio":1.1600"#));
+        assert!(lines[1].contains(&format!(
+            r#""name":"assets/\"b\".txt","cfg":"all(unix)","algorithm":"{}""#,
+            algorithm(CompressionMethod::DEFAULT)
+        )));
+        assert!(lines[1].contains(r#""original_size":200,"compressed_size":50,"ratio":0.2500"#));
+    }
+}
@@ -0,0 +1,140 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The dictionary shared by the statics declared `with zstd dict shared`.
+//!
+//! The dictionary is trained from the files in a directory of the crate
+//! the first time it is needed, and cached in a file named after the hash of the files,
+//! so it is only trained again when the files change.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use include_flate_compress::train_dictionary;
+use sha2::{Digest, Sha256};
+
+/// The environment variable that overrides the directory of the samples,
+/// relative to `CARGO_MANIFEST_DIR`.
+pub const DIR_ENV: &str = "INCLUDE_FLATE_SHARED_DICT_DIR";
+
+/// The directory of the samples unless overridden by [`DIR_ENV`].
+const DEFAULT_DIR: &str = "assets";
+
+/// The maximum size of the dictionary, which is the default of the zstd CLI.
+const MAX_SIZE: usize = 110 * 1024;
+
+/// The dictionary of each crate that was loaded by this process, by `CARGO_MANIFEST_DIR`.
+static LOADED: Mutex<Option<HashMap<PathBuf, Shared>>> = Mutex::new(None);
+
+#[derive(Clone)]
+pub struct Shared {
+    /// The contents of the dictionary.
+    pub dict: Vec<u8>,
+    /// The file that the dictionary is cached in.
+    pub path: PathBuf,
+    /// The sample files that the dictionary was trained from.
+    pub samples: Vec<PathBuf>,
+}
+
+/// Loads the shared dictionary of the crate in `dir`, training it if it is not cached.
+pub fn load(dir: &Path) -> Result<Shared, String> {
+    let mut loaded = LOADED.lock().unwrap_or_else(|err| err.into_inner());
+    let loaded = loaded.get_or_insert_with(HashMap::new);
+    if let Some(shared) = loaded.get(dir) {
+        return Ok(shared.clone());
+    }
+
+    let samples_dir = dir.join(std::env::var_os(DIR_ENV).unwrap_or_else(|| DEFAULT_DIR.into()));
+    let mut samples = Vec::new();
+    collect(&samples_dir, &mut samples)
+        .map_err(|err| format!("cannot read the samples in {:?}: {}", samples_dir, err))?;
+    samples.sort();
+
+    let mut hasher = Sha256::new();
+    let mut contents = Vec::with_capacity(samples.len());
+    for sample in &samples {
+        let data = fs::read(sample).map_err(|err| format!("cannot read {:?}: {}", sample, err))?;
+        let name = sample.strip_prefix(&samples_dir).unwrap_or(sample);
+        hasher.update(name.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+        contents.push(data);
+    }
+
+    let path = cache_dir().join(format!(
+        "include-flate-shared-{}.dict",
+        crate::hex(&hasher.finalize())
+    ));
+    let dict = match fs::read(&path) {
+        Ok(dict) => dict,
+        Err(_) => {
+            let dict = train_dictionary(&contents, MAX_SIZE).map_err(|err| {
+                format!(
+                    "cannot train the shared dictionary from {:?}: {}",
+                    samples_dir, err
+                )
+            })?;
+            write_atomic(&path, &dict)
+                .map_err(|err| format!("cannot write {:?}: {}", path, err))?;
+            dict
+        }
+    };
+
+    let shared = Shared {
+        dict,
+        path,
+        samples,
+    };
+    loaded.insert(dir.to_path_buf(), shared.clone());
+    Ok(shared)
+}
+
+/// `OUT_DIR` if the crate has a build script, or a directory in the system temporary directory.
+///
+/// Cached dictionaries are named after the hash of their samples,
+/// so crates never read the dictionary of another crate from a shared directory.
+fn cache_dir() -> PathBuf {
+    match std::env::var_os("OUT_DIR") {
+        Some(dir) => dir.into(),
+        None => std::env::temp_dir().join("include-flate"),
+    }
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Writes the file through a temporary file,
+/// since other crates may be compiled in parallel with the same cache directory.
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&temp, data)?;
+    fs::rename(&temp, path)
+}
@@ -0,0 +1,64 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing of `signed` assets with a key from the environment.
+//!
+//! The key is the 32-byte Ed25519 secret key, encoded in hex,
+//! e.g. as generated by `openssl rand -hex 32`.
+//! It is deliberately not tracked with `option_env!` like the other variables,
+//! so that it is never evaluated in the calling crate.
+
+/// The environment variable that holds the signing key,
+/// unless the asset names another one with `signed by "VAR"`.
+pub const KEY_ENV: &str = "INCLUDE_FLATE_SIGNING_KEY";
+
+/// Signs `data` with the key in the environment variable `name`.
+///
+/// Returns the verifying key along with the signature.
+#[cfg(feature = "signed")]
+pub fn sign(name: &str, data: &[u8]) -> Result<([u8; 32], [u8; 64]), String> {
+    let secret = load_key(name)?;
+    Ok(include_flate_compress::sign(&secret, data))
+}
+
+#[cfg(not(feature = "signed"))]
+pub fn sign(_name: &str, _data: &[u8]) -> Result<([u8; 32], [u8; 64]), String> {
+    Err("`signed` requires the `signed` feature of include-flate".into())
+}
+
+#[cfg(feature = "signed")]
+fn load_key(name: &str) -> Result<[u8; 32], String> {
+    let value = match std::env::var(name) {
+        Ok(value) if !value.is_empty() => value,
+        _ => {
+            return Err(format!(
+                "{} must be set to the hex-encoded Ed25519 key to sign assets with",
+                name
+            ))
+        }
+    };
+
+    let invalid = || format!("{} must be 64 hexadecimal digits", name);
+    let value = value.trim();
+    if value.len() != 64 || !value.is_ascii() {
+        return Err(invalid());
+    }
+    let mut key = [0; 32];
+    for (byte, digits) in key.iter_mut().zip(value.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
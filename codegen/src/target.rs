@@ -0,0 +1,138 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The algorithms selected by the target of the calling crate.
+//!
+//! ```ignore
+//! with zstd on "x86_64-*", deflate on "wasm32-*"
+//! with zstd(long) on "*-linux-*", deflate
+//! ```
+//!
+//! Since procedural macros are not told which target they expand for,
+//! the file is compressed with every algorithm,
+//! and each output is gated behind the `cfg` predicate that the pattern translates to.
+
+use include_flate_compress::{CompressionExtras, CompressionMethod};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Error;
+
+/// An algorithm along with the targets it is used for.
+pub(crate) struct Target {
+    /// The pattern after `on`, or `None` for the algorithm of all other targets.
+    pub pattern: Option<syn::LitStr>,
+    pub method: CompressionMethod,
+    pub extras: CompressionExtras,
+}
+
+/// Returns the `cfg` predicate under which each target is selected,
+/// followed by the predicate of the targets that no pattern matches, if any.
+///
+/// The first target whose pattern matches wins, so the predicates are mutually exclusive.
+pub(crate) fn gates(targets: &[Target]) -> syn::Result<Vec<TokenStream>> {
+    let mut gates = Vec::with_capacity(targets.len() + 1);
+    let mut previous = Vec::new();
+    for target in targets {
+        let Some(pattern) = &target.pattern else {
+            gates.push(quote!(not(any(#(#previous),*))));
+            return Ok(gates);
+        };
+        let predicate = predicate(pattern)?;
+        gates.push(quote!(all(#predicate, not(any(#(#previous),*)))));
+        previous.push(predicate);
+    }
+    gates.push(quote!(not(any(#(#previous),*))));
+    Ok(gates)
+}
+
+/// The values of `target_vendor` known to rustc.
+const VENDORS: &[&str] = &[
+    "amd",
+    "apple",
+    "espressif",
+    "fortanix",
+    "ibm",
+    "kmc",
+    "mti",
+    "nintendo",
+    "nvidia",
+    "openwrt",
+    "pc",
+    "risc0",
+    "sony",
+    "sun",
+    "unikraft",
+    "unknown",
+    "uwp",
+    "vex",
+    "win7",
+    "wrs",
+];
+
+/// The values of `target_env` known to rustc.
+const ENVS: &[&str] = &[
+    "gnu",
+    "macabi",
+    "mlibc",
+    "msvc",
+    "musl",
+    "newlib",
+    "nto70",
+    "nto71",
+    "nto71_iosock",
+    "nto80",
+    "ohos",
+    "p1",
+    "p2",
+    "p3",
+    "relibc",
+    "sgx",
+    "sim",
+    "uclibc",
+    "v5",
+];
+
+/// Translates a target pattern such as `"x86_64-*"` into a `cfg` predicate.
+///
+/// The first component is compared with `target_arch`.
+/// Each of the others is compared with `target_vendor` or `target_env` if it is a known value
+/// of either, or with `target_os` otherwise, so that misspelled components are reported
+/// by the `unexpected_cfgs` lint of the calling crate.
+/// A `*` component matches anything.
+fn predicate(pattern: &syn::LitStr) -> syn::Result<TokenStream> {
+    let mut predicates = Vec::new();
+    for (i, component) in pattern.value().split('-').enumerate() {
+        if component == "*" {
+            continue;
+        }
+        if component.is_empty() || component.contains('*') {
+            return Err(Error::new(
+                pattern.span(),
+                "expected a target pattern such as \"x86_64-*\", \
+                 where `*` stands for whole components",
+            ));
+        }
+        predicates.push(if i == 0 {
+            quote!(target_arch = #component)
+        } else if VENDORS.contains(&component) {
+            quote!(target_vendor = #component)
+        } else if ENVS.contains(&component) {
+            quote!(target_env = #component)
+        } else {
+            quote!(target_os = #component)
+        });
+    }
+    Ok(quote!(all(#(#predicates),*)))
+}
@@ -0,0 +1,104 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Syntax validation of text assets with `validate $format`.
+
+use std::str::from_utf8;
+
+use proc_macro2::Span;
+use syn::Error;
+
+/// Checks that `data`, read from the asset `name`, is a syntactically valid document of `format`.
+pub(crate) fn validate(
+    format: &syn::Ident,
+    span: Span,
+    name: &str,
+    data: &[u8],
+) -> syn::Result<()> {
+    let text = from_utf8(data).map_err(|err| Error::new(span, err))?;
+
+    let result = match format.to_string().as_str() {
+        "json" => json(text),
+        "toml" => toml(text),
+        "yaml" => yaml(text),
+        _ => {
+            return Err(Error::new(
+                format.span(),
+                "expected one of `json`, `toml` or `yaml`",
+            ))
+        }
+    };
+
+    match result {
+        Some(Ok(())) => Ok(()),
+        Some(Err(err)) => Err(Error::new(
+            span,
+            format!("{:?} is not valid {}: {}", name, format, err),
+        )),
+        None => Err(Error::new(
+            format.span(),
+            format!(
+                "`validate {0}` requires the `validate-{0}` feature of include-flate",
+                format
+            ),
+        )),
+    }
+}
+
+// Each function returns `None` if the feature for the format is not enabled.
+
+#[cfg(feature = "validate-json")]
+fn json(text: &str) -> Option<Result<(), String>> {
+    Some(
+        serde_json::from_str::<serde_json::Value>(text)
+            .map(drop)
+            .map_err(|err| err.to_string()),
+    )
+}
+
+#[cfg(not(feature = "validate-json"))]
+fn json(_: &str) -> Option<Result<(), String>> {
+    None
+}
+
+#[cfg(feature = "validate-toml")]
+fn toml(text: &str) -> Option<Result<(), String>> {
+    Some(
+        text.parse::<toml::Table>()
+            .map(drop)
+            .map_err(|err| err.to_string()),
+    )
+}
+
+#[cfg(not(feature = "validate-toml"))]
+fn toml(_: &str) -> Option<Result<(), String>> {
+    None
+}
+
+#[cfg(feature = "validate-yaml")]
+fn yaml(text: &str) -> Option<Result<(), String>> {
+    // a stream may contain several documents, each of which must be valid
+    for document in serde_yaml::Deserializer::from_str(text) {
+        if let Err(err) = <serde::de::IgnoredAny as serde::Deserialize>::deserialize(document) {
+            return Some(Err(err.to_string()));
+        }
+    }
+    Some(Ok(()))
+}
+
+#[cfg(not(feature = "validate-yaml"))]
+fn yaml(_: &str) -> Option<Result<(), String>> {
+    None
+}
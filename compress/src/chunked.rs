@@ -0,0 +1,270 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container of independently compressed fixed-size blocks, which allows decompressing
+//! individual blocks with any compression method.
+//!
+//! The compressed blocks are stored back to back, followed by the index:
+//! the compressed size of each block as a little-endian `u32`,
+//! then the block size (`u32`), the decompressed size (`u64`), the number of blocks (`u32`)
+//! and the magic number `IFCK`.
+
+use std::io::{self, Read};
+use std::ops::Range;
+
+use crate::{
+    compress_bytes, decompress_bytes, read_bounded, CompressionMethod, CompressionOptions,
+    FlateCompressionError, FlateDecoder,
+};
+
+/// The number of decompressed bytes in each block, unless specified otherwise.
+pub const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+const MAGIC: &[u8; 4] = b"IFCK";
+const FOOTER_SIZE: usize = 20;
+
+/// Compresses `data` into independently compressed blocks of `block_size` bytes each.
+pub fn compress_chunked(
+    data: &[u8],
+    options: impl Into<CompressionOptions>,
+    block_size: usize,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let options = options.into();
+    let block_size = u32::try_from(block_size)
+        .ok()
+        .filter(|&size| size > 0)
+        .ok_or_else(|| invalid_input("block size must be between 1 and u32::MAX"))?;
+
+    let mut output = Vec::new();
+    let mut sizes = Vec::new();
+    for block in data.chunks(block_size as usize) {
+        let compressed = compress_bytes(block, options)?;
+        sizes.push(
+            u32::try_from(compressed.len())
+                .map_err(|_| invalid_input("compressed block is larger than u32::MAX"))?,
+        );
+        output.extend_from_slice(&compressed);
+    }
+
+    let block_count =
+        u32::try_from(sizes.len()).map_err(|_| invalid_input("too many blocks for the index"))?;
+    for size in sizes {
+        output.extend_from_slice(&size.to_le_bytes());
+    }
+    output.extend_from_slice(&block_size.to_le_bytes());
+    output.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    output.extend_from_slice(&block_count.to_le_bytes());
+    output.extend_from_slice(MAGIC);
+
+    Ok(output)
+}
+
+/// The index of data compressed with [`compress_chunked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkIndex {
+    /// The compressed range of each block.
+    blocks: Vec<Range<usize>>,
+    block_size: usize,
+    len: usize,
+}
+
+impl ChunkIndex {
+    /// Reads the index at the end of `data`.
+    ///
+    /// Returns `None` if `data` were not compressed with [`compress_chunked`].
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let footer = &data[data.len().checked_sub(FOOTER_SIZE)?..];
+        if &footer[16..] != MAGIC {
+            return None;
+        }
+
+        let block_size = read_u32(footer, 0) as usize;
+        let len = usize::try_from(u64::from_le_bytes(footer[4..12].try_into().ok()?)).ok()?;
+        let block_count = read_u32(footer, 12) as usize;
+        if block_size == 0 || len.div_ceil(block_size) != block_count {
+            return None;
+        }
+
+        let index_start = (data.len() - FOOTER_SIZE).checked_sub(block_count.checked_mul(4)?)?;
+        let mut blocks = Vec::with_capacity(block_count);
+        let mut offset = 0usize;
+        for size in data[index_start..data.len() - FOOTER_SIZE].chunks_exact(4) {
+            let end = offset.checked_add(read_u32(size, 0) as usize)?;
+            blocks.push(offset..end);
+            offset = end;
+        }
+
+        (blocks.len() == block_count && offset == index_start).then_some(Self {
+            blocks,
+            block_size,
+            len,
+        })
+    }
+
+    /// The number of blocks.
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// The number of decompressed bytes in each block except the last one.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// The total size of the decompressed data.
+    pub fn decompressed_len(&self) -> usize {
+        self.len
+    }
+
+    /// The range of the decompressed data in block `n`.
+    ///
+    /// # Panics
+    /// If `n` is not less than the number of blocks.
+    pub fn block_range(&self, n: usize) -> Range<usize> {
+        assert!(n < self.block_count(), "block {} is out of bounds", n);
+        n * self.block_size..((n + 1) * self.block_size).min(self.len)
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        method: CompressionMethod,
+        n: usize,
+    ) -> Result<Vec<u8>, FlateCompressionError> {
+        let block = decompress_bytes(&data[self.blocks[n].clone()], method)?;
+        if block.len() != self.block_range(n).len() {
+            return Err(invalid_data("block size does not match the index"));
+        }
+        Ok(block)
+    }
+}
+
+/// Decompresses block `n` of the data compressed with [`compress_chunked`].
+pub fn decompress_block(
+    data: &[u8],
+    method: CompressionMethod,
+    n: usize,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let index = parse(data)?;
+    if n >= index.block_count() {
+        return Err(invalid_input("block is out of bounds"));
+    }
+    index.decompress(data, method, n)
+}
+
+/// Decompresses `range` of the data compressed with [`compress_chunked`],
+/// only decompressing the blocks that overlap with the range.
+pub fn decompress_chunked_range(
+    data: &[u8],
+    method: CompressionMethod,
+    range: Range<usize>,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let index = parse(data)?;
+    if range.start > range.end || range.end > index.decompressed_len() {
+        return Err(invalid_input("range is out of bounds"));
+    }
+
+    let mut output = Vec::with_capacity(range.len());
+    for n in range.start / index.block_size..index.block_count() {
+        let block_range = index.block_range(n);
+        if block_range.start >= range.end {
+            break;
+        }
+
+        let block = index.decompress(data, method, n)?;
+        let start = range.start.saturating_sub(block_range.start);
+        let end = range.end.min(block_range.end) - block_range.start;
+        output.extend_from_slice(&block[start..end]);
+    }
+
+    Ok(output)
+}
+
+/// Decompresses all blocks of the data compressed with [`compress_chunked`].
+///
+/// Fails with [`FlateCompressionError::TooLarge`] as soon as the output exceeds
+/// the size recorded in the index.
+pub fn decompress_chunked(
+    data: &[u8],
+    method: CompressionMethod,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let decoder = ChunkedDecoder::new(data, method)?;
+    let limit = decoder.index.decompressed_len() as u64;
+    read_bounded(decoder, limit)
+}
+
+/// A stream that decompresses the blocks of data compressed with [`compress_chunked`]
+/// one after another.
+pub struct ChunkedDecoder<'a> {
+    data: &'a [u8],
+    method: CompressionMethod,
+    index: ChunkIndex,
+    next: usize,
+    current: Option<FlateDecoder<&'a [u8]>>,
+}
+
+impl<'a> ChunkedDecoder<'a> {
+    pub fn new(data: &'a [u8], method: CompressionMethod) -> Result<Self, FlateCompressionError> {
+        Ok(Self {
+            data,
+            method,
+            index: parse(data)?,
+            next: 0,
+            current: None,
+        })
+    }
+}
+
+impl Read for ChunkedDecoder<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(decoder) = &mut self.current {
+                let read = decoder.read(buf)?;
+                if read > 0 || buf.is_empty() {
+                    return Ok(read);
+                }
+            }
+
+            if self.next == self.index.block_count() {
+                return Ok(0);
+            }
+            let block = &self.data[self.index.blocks[self.next].clone()];
+            self.current = Some(
+                self.method
+                    .decoder(block)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?,
+            );
+            self.next += 1;
+        }
+    }
+}
+
+fn parse(data: &[u8]) -> Result<ChunkIndex, FlateCompressionError> {
+    ChunkIndex::parse(data).ok_or_else(|| invalid_data("data are not chunked"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn invalid_input(message: &str) -> FlateCompressionError {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string()).into()
+}
+
+fn invalid_data(message: &str) -> FlateCompressionError {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string()).into()
+}
@@ -0,0 +1,112 @@
+// include-flate
+// Copyright (C) SOFe, Kento Oki
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared dictionaries for compressing many small related payloads.
+//!
+//! Dictionaries are only supported by zstd.
+
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use zstd::Decoder as ZstdDecoder;
+use zstd::Encoder as ZstdEncoder;
+
+use crate::{read_bounded, CompressionMethod, CompressionOptions, FlateCompressionError};
+
+/// Trains a zstd dictionary of at most `max_size` bytes from `samples`.
+///
+/// The samples should be representative of the payloads that will be compressed with the
+/// dictionary. zstd requires a reasonable number of samples (typically hundreds) to train from.
+pub fn train_dictionary<S: AsRef<[u8]>>(
+    samples: &[S],
+    max_size: usize,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    zstd::dict::from_samples(samples, max_size).map_err(FlateCompressionError::ZstdError)
+}
+
+/// Trains a zstd dictionary of at most `max_size` bytes from every file under `dir`, recursively.
+///
+/// The output can be written to a file and passed to `flate!` with `with zstd dict "file"`.
+/// Files are read in the order of their paths, so the output only depends on the files.
+pub fn train_dictionary_from_dir(
+    dir: impl AsRef<Path>,
+    max_size: usize,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                collect(&entry.path(), files)?;
+            } else {
+                files.push(entry.path());
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(dir.as_ref(), &mut files)?;
+    files.sort();
+
+    let samples = files
+        .iter()
+        .map(std::fs::read)
+        .collect::<io::Result<Vec<_>>>()?;
+    train_dictionary(&samples, max_size)
+}
+
+/// Compresses `data` in memory with the given dictionary.
+///
+/// The method in `options` must be zstd.
+pub fn compress_with_dict(
+    data: &[u8],
+    dict: &[u8],
+    options: impl Into<CompressionOptions>,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let options = options.into();
+    if options.method != CompressionMethod::Zstd {
+        return Err(FlateCompressionError::DictionaryUnsupported(options.method));
+    }
+
+    #[allow(unused_mut)]
+    let mut encoder = ZstdEncoder::with_dictionary(Vec::new(), options.zstd_level(), dict)
+        .map_err(FlateCompressionError::ZstdError)?;
+    options
+        .configure_zstd(&mut encoder)
+        .map_err(FlateCompressionError::ZstdError)?;
+    #[cfg(feature = "zstd-multithread")]
+    encoder
+        .multithread(options.extras.zstd_workers)
+        .map_err(FlateCompressionError::ZstdError)?;
+    encoder.write_all(data)?;
+    encoder.finish().map_err(FlateCompressionError::ZstdError)
+}
+
+/// Decompresses zstd-compressed `data` in memory with the dictionary it was compressed with.
+pub fn decompress_with_dict(data: &[u8], dict: &[u8]) -> Result<Vec<u8>, FlateCompressionError> {
+    decompress_with_dict_bounded(data, dict, u64::MAX)
+}
+
+/// Like [`decompress_with_dict`], but fails with [`FlateCompressionError::TooLarge`]
+/// as soon as the output exceeds `limit` bytes.
+pub fn decompress_with_dict_bounded(
+    data: &[u8],
+    dict: &[u8],
+    limit: u64,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let decoder = ZstdDecoder::with_dictionary(BufReader::new(data), dict)
+        .map_err(FlateCompressionError::ZstdError)?;
+    read_bounded(decoder, limit)
+}
@@ -0,0 +1,72 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The hash algorithms of `checksum $hash`,
+//! whose digests are embedded next to the compressed data and checked after decompression.
+
+use crate::FlateCompressionError;
+
+/// A hash algorithm that the contents of an asset can be checked with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// SHA-256, with a 32-byte digest.
+    #[cfg(feature = "sha256")]
+    Sha256,
+    /// The 64-bit XXH3, with an 8-byte digest in big-endian order,
+    /// as printed by `xxhsum -H3`.
+    /// It is much faster than SHA-256, but does not resist deliberate tampering.
+    #[cfg(feature = "xxh3")]
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    /// Resolves the algorithm named in `checksum $hash`,
+    /// or returns `None` if it is unknown or not enabled.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            #[cfg(feature = "sha256")]
+            "sha256" => Some(Self::Sha256),
+            #[cfg(feature = "xxh3")]
+            "xxh3" => Some(Self::Xxh3),
+            _ => None,
+        }
+    }
+
+    /// Computes the digest of `data`.
+    // the enum is empty if no algorithm is enabled
+    #[cfg_attr(
+        not(any(feature = "sha256", feature = "xxh3")),
+        allow(unused_variables)
+    )]
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "sha256")]
+            Self::Sha256 => {
+                use sha2::Digest;
+                sha2::Sha256::digest(data).to_vec()
+            }
+            #[cfg(feature = "xxh3")]
+            Self::Xxh3 => twox_hash::XxHash3_64::oneshot(data).to_be_bytes().to_vec(),
+        }
+    }
+
+    /// Checks `data` against the `expected` digest.
+    pub fn verify(self, expected: &[u8], data: &[u8]) -> Result<(), FlateCompressionError> {
+        if self.digest(data) != expected {
+            return Err(FlateCompressionError::DigestMismatch(self));
+        }
+        Ok(())
+    }
+}
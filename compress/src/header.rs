@@ -0,0 +1,174 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The header that precedes every buffer embedded by `deflate_file!`,
+//! so that the data can be decompressed without knowing how the macro was called.
+//!
+//! The header is the magic number `IFL`, the [id](CompressionMethod::id) of the method,
+//! the size of the uncompressed data as a little-endian `u64`,
+//! then the CRC-32 of the uncompressed data as a little-endian `u32`.
+//! The upper 4 bits of the method byte hold how much the zstd window log
+//! exceeds [`ZSTD_DEFAULT_WINDOW_LOG_MAX`], if it does.
+//!
+//! The `include-flate-compress` binary of this crate compresses files into this format
+//! and decompresses them, e.g. to prepare data for a loader outside of the macros.
+
+use std::io::Read;
+
+#[cfg(feature = "zstd")]
+use crate::ZSTD_WINDOW_LOG_LIMIT;
+use crate::{
+    scratch, CompressionMethod, CompressionOptions, FlateCompressionError, FlateDecoder,
+    ZSTD_DEFAULT_WINDOW_LOG_MAX,
+};
+
+const MAGIC: &[u8; 3] = b"IFL";
+
+/// The size of the header in bytes.
+pub const HEADER_SIZE: usize = 16;
+
+/// Describes the data that follow the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// The method that the data were compressed with.
+    ///
+    /// For chunked data, this is the method of each block.
+    pub method: CompressionMethod,
+    /// The size of the uncompressed data.
+    pub len: u64,
+    /// The CRC-32 of the uncompressed data.
+    pub crc32: u32,
+    /// The base-2 logarithm of the zstd window that the data were compressed with,
+    /// if it is larger than [`ZSTD_DEFAULT_WINDOW_LOG_MAX`].
+    ///
+    /// The decoders created from the header accept this window instead of the default limit.
+    pub zstd_window_log: Option<u32>,
+}
+
+impl Header {
+    /// Describes the data compressed from `original` with `method`.
+    pub fn new(method: CompressionMethod, original: &[u8]) -> Self {
+        Self {
+            method,
+            len: original.len() as u64,
+            crc32: crc32fast::hash(original),
+            zstd_window_log: None,
+        }
+    }
+
+    /// Describes the data compressed from `original` with `options`,
+    /// recording the zstd window if it is larger than the decoders accept by default.
+    pub fn with_options(options: &CompressionOptions, original: &[u8]) -> Self {
+        #[allow(unused_mut)]
+        let mut header = Self::new(options.method, original);
+        #[cfg(feature = "zstd")]
+        if options.method == CompressionMethod::Zstd {
+            header.zstd_window_log = options
+                .extras
+                .zstd_window_log
+                .filter(|&log| log > ZSTD_DEFAULT_WINDOW_LOG_MAX);
+        }
+        header
+    }
+
+    /// Returns the header followed by `data`.
+    pub fn prepend(&self, data: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(HEADER_SIZE + data.len());
+        output.extend_from_slice(MAGIC);
+        let window = self.zstd_window_log.map_or(0, |log| {
+            log.saturating_sub(ZSTD_DEFAULT_WINDOW_LOG_MAX) as u8
+        });
+        output.push(self.method.id() | window << 4);
+        output.extend_from_slice(&self.len.to_le_bytes());
+        output.extend_from_slice(&self.crc32.to_le_bytes());
+        output.extend_from_slice(data);
+        output
+    }
+
+    /// Reads the header at the start of `data`, returning it along with the data after it.
+    ///
+    /// Returns `None` if `data` do not start with a header,
+    /// if the method is unknown or not enabled,
+    /// or if the zstd window is larger than this target can decompress.
+    pub fn parse(data: &[u8]) -> Option<(Self, &[u8])> {
+        if data.len() < HEADER_SIZE || &data[..3] != MAGIC {
+            return None;
+        }
+
+        let method = CompressionMethod::from_id(data[3] & 0x0f)?;
+        let zstd_window_log = match u32::from(data[3] >> 4) {
+            0 => None,
+            excess => {
+                let log = ZSTD_DEFAULT_WINDOW_LOG_MAX + excess;
+                #[cfg(feature = "zstd")]
+                let valid = method == CompressionMethod::Zstd && log <= ZSTD_WINDOW_LOG_LIMIT;
+                // only zstd records a window
+                #[cfg(not(feature = "zstd"))]
+                let valid = false;
+                if !valid {
+                    return None;
+                }
+                Some(log)
+            }
+        };
+        let len = u64::from_le_bytes(data[4..12].try_into().ok()?);
+        let crc32 = u32::from_le_bytes(data[12..HEADER_SIZE].try_into().ok()?);
+        let header = Self {
+            method,
+            len,
+            crc32,
+            zstd_window_log,
+        };
+        Some((header, &data[HEADER_SIZE..]))
+    }
+
+    /// Returns a decoder of `read`, which holds the data after the header,
+    /// that accepts the zstd window recorded in the header.
+    pub fn decoder<R: Read>(&self, read: R) -> Result<FlateDecoder<R>, FlateCompressionError> {
+        FlateDecoder::with_window_log_max(self.method, read, self.window_log_max())
+    }
+
+    /// Decompresses `data`, which follow the header, in memory,
+    /// accepting the zstd window recorded in the header.
+    ///
+    /// Like [`decompress_bounded`](crate::decompress_bounded) with a limit of [`len`](Self::len),
+    /// this fails with [`FlateCompressionError::TooLarge`]
+    /// as soon as the output exceeds the size in the header.
+    /// The checksum is not verified; see [`verify`](Self::verify).
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, FlateCompressionError> {
+        scratch::with_thread_scratch(|scratch| {
+            scratch::decompress(data, self.method, scratch, self.len, self.window_log_max())
+        })
+    }
+
+    fn window_log_max(&self) -> u32 {
+        self.zstd_window_log
+            .map_or(ZSTD_DEFAULT_WINDOW_LOG_MAX, |log| {
+                log.max(ZSTD_DEFAULT_WINDOW_LOG_MAX)
+            })
+    }
+
+    /// Checks the decompressed `data` against the checksum.
+    pub fn verify(&self, data: &[u8]) -> Result<(), FlateCompressionError> {
+        let actual = crc32fast::hash(data);
+        if actual != self.crc32 {
+            return Err(FlateCompressionError::ChecksumMismatch {
+                expected: self.crc32,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
@@ -18,25 +18,101 @@ compile_error!("You must enable either the `deflate` or `zstd` feature.");
 
 use std::{
     fmt,
-    io::{self, BufRead, BufReader, Read, Seek, Write},
+    io::{self, BufRead, BufReader, Read, Write},
 };
 
 #[cfg(feature = "deflate")]
 use libflate::deflate::Decoder as DeflateDecoder;
 #[cfg(feature = "deflate")]
+use libflate::deflate::EncodeOptions as DeflateEncodeOptions;
+#[cfg(feature = "deflate")]
 use libflate::deflate::Encoder as DeflateEncoder;
+#[cfg(feature = "deflate")]
+use libflate::lz77::DefaultLz77Encoder;
+#[cfg(feature = "deflate")]
+use libflate::{gzip, zlib};
+#[cfg(feature = "lz4")]
+use lz4_flex::frame::FrameDecoder as Lz4Decoder;
+#[cfg(feature = "lz4")]
+use lz4_flex::frame::FrameEncoder as Lz4Encoder;
+#[cfg(feature = "snappy")]
+use snap::read::FrameDecoder as SnappyDecoder;
+#[cfg(feature = "snappy")]
+use snap::write::FrameEncoder as SnappyEncoder;
+#[cfg(feature = "xz")]
+use xz2::read::XzDecoder;
+#[cfg(feature = "xz")]
+use xz2::write::XzEncoder;
 #[cfg(feature = "zstd")]
 use zstd::Decoder as ZstdDecoder;
 #[cfg(feature = "zstd")]
 use zstd::Encoder as ZstdEncoder;
 
+pub use chunked::{
+    compress_chunked, decompress_block, decompress_chunked, decompress_chunked_range, ChunkIndex,
+    ChunkedDecoder, DEFAULT_BLOCK_SIZE,
+};
+#[cfg(feature = "zstd")]
+pub use dict::{
+    compress_with_dict, decompress_with_dict, decompress_with_dict_bounded, train_dictionary,
+    train_dictionary_from_dir,
+};
+pub use hash::HashAlgorithm;
+pub use header::{Header, HEADER_SIZE};
+pub use scratch::{decompress_bounded_with, decompress_bytes_with, DecompressScratch};
+#[cfg(feature = "zstd")]
+pub use seekable::{compress_seekable, decompress_seekable_range, SeekTable, DEFAULT_FRAME_SIZE};
+#[cfg(feature = "signed")]
+pub use signature::{sign, verify_signature, KEY_SIZE, SIGNATURE_SIZE};
+pub use stats::{apply_compression_with_stats, apply_decompression_with_stats, CompressionStats};
+
+mod chunked;
+#[cfg(feature = "zstd")]
+mod dict;
+mod hash;
+mod header;
+mod scratch;
+#[cfg(feature = "zstd")]
+mod seekable;
+#[cfg(feature = "signed")]
+mod signature;
+mod stats;
+
 #[derive(Debug)]
 pub enum FlateCompressionError {
     #[cfg(feature = "deflate")]
     DeflateError(io::Error),
     #[cfg(feature = "zstd")]
     ZstdError(io::Error),
+    #[cfg(feature = "lz4")]
+    Lz4Error(io::Error),
+    #[cfg(feature = "xz")]
+    XzError(io::Error),
+    #[cfg(feature = "snappy")]
+    SnappyError(io::Error),
     IoError(io::Error),
+    /// The method does not support compressing with a dictionary.
+    DictionaryUnsupported(CompressionMethod),
+    /// The method does not support the seekable format.
+    SeekableUnsupported(CompressionMethod),
+    /// The CRC-32 of the decompressed data does not match the one recorded at compile time.
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    /// The digest of the decompressed data does not match the one embedded with `checksum`.
+    DigestMismatch(HashAlgorithm),
+    /// The decompressed data are longer than the size they were expected to have.
+    TooLarge {
+        limit: u64,
+    },
+    /// The data do not start with a valid [`Header`].
+    InvalidHeader,
+    /// The decompressed data are not valid UTF-8.
+    InvalidUtf8(std::str::Utf8Error),
+    /// The signature of the decompressed data does not match the embedded verifying key.
+    #[cfg(feature = "signed")]
+    InvalidSignature,
 }
 
 impl From<io::Error> for FlateCompressionError {
@@ -52,45 +128,268 @@ impl fmt::Display for FlateCompressionError {
             FlateCompressionError::DeflateError(err) => write!(f, "Deflate error: {}", err),
             #[cfg(feature = "zstd")]
             FlateCompressionError::ZstdError(err) => write!(f, "Zstd error: {}", err),
+            #[cfg(feature = "lz4")]
+            FlateCompressionError::Lz4Error(err) => write!(f, "LZ4 error: {}", err),
+            #[cfg(feature = "xz")]
+            FlateCompressionError::XzError(err) => write!(f, "XZ error: {}", err),
+            #[cfg(feature = "snappy")]
+            FlateCompressionError::SnappyError(err) => write!(f, "Snappy error: {}", err),
             FlateCompressionError::IoError(err) => write!(f, "I/O error: {}", err),
+            FlateCompressionError::DictionaryUnsupported(method) => {
+                write!(f, "{:?} does not support dictionaries", method)
+            }
+            FlateCompressionError::SeekableUnsupported(method) => {
+                write!(f, "{:?} does not support the seekable format", method)
+            }
+            FlateCompressionError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected CRC-32 {:08x}, got {:08x}",
+                expected, actual
+            ),
+            FlateCompressionError::DigestMismatch(algorithm) => {
+                write!(f, "{:?} digest mismatch", algorithm)
+            }
+            FlateCompressionError::TooLarge { limit } => write!(
+                f,
+                "the decompressed data exceed the expected size of {} bytes",
+                limit
+            ),
+            FlateCompressionError::InvalidHeader => write!(f, "invalid header"),
+            FlateCompressionError::InvalidUtf8(err) => write!(f, "invalid UTF-8: {}", err),
+            #[cfg(feature = "signed")]
+            FlateCompressionError::InvalidSignature => write!(f, "invalid signature"),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CompressionMethod {
     #[cfg(feature = "deflate")]
     Deflate,
     #[cfg(feature = "zstd")]
     Zstd,
+    /// DEFLATE in a gzip container, which can be served as is with `Content-Encoding: gzip`.
+    #[cfg(feature = "deflate")]
+    Gzip,
+    /// DEFLATE in a zlib container, which can be served as is with `Content-Encoding: deflate`.
+    #[cfg(feature = "deflate")]
+    Zlib,
+    /// The LZ4 frame format, which decompresses much faster than the other methods
+    /// at the cost of a lower compression ratio.
+    /// [`CompressionLevel`] has no effect on LZ4.
+    #[cfg(feature = "lz4")]
+    Lz4,
+    /// The XZ format (LZMA2), which usually compresses better than the other methods
+    /// but decompresses more slowly.
+    /// It suits large assets that are only read once.
+    #[cfg(feature = "xz")]
+    Xz,
+    /// The Snappy frame format, which sits between LZ4 and DEFLATE in speed and ratio.
+    /// [`CompressionLevel`] has no effect on Snappy.
+    #[cfg(feature = "snappy")]
+    Snappy,
+    /// Stores the data without compression,
+    /// for files that are already compressed but should be accessed like the others.
+    Store,
+}
+
+impl Default for CompressionMethod {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 impl CompressionMethod {
-    pub fn encoder<'a, W: BufRead + Write + Seek + 'a>(
-        &'a self,
-        write: W,
-    ) -> Result<FlateEncoder<W>, FlateCompressionError> {
+    /// The method used when none is specified, usable in const contexts.
+    ///
+    /// This is DEFLATE, unless the `default-zstd` feature is enabled
+    /// or the `deflate` feature is disabled.
+    #[cfg(all(feature = "deflate", not(feature = "default-zstd")))]
+    pub const DEFAULT: Self = Self::Deflate;
+    /// The method used when none is specified, usable in const contexts.
+    ///
+    /// This is DEFLATE, unless the `default-zstd` feature is enabled
+    /// or the `deflate` feature is disabled.
+    #[cfg(not(all(feature = "deflate", not(feature = "default-zstd"))))]
+    pub const DEFAULT: Self = Self::Zstd;
+
+    pub fn encoder<W: Write>(&self, write: W) -> Result<FlateEncoder<W>, FlateCompressionError> {
         FlateEncoder::new(*self, write)
     }
 
-    pub fn decoder<'a, R: ReadSeek + 'a>(
-        &'a self,
-        read: R,
-    ) -> Result<FlateDecoder<'a>, FlateCompressionError> {
-        FlateDecoder::new(*self, Box::new(read))
+    pub fn decoder<R: Read>(&self, read: R) -> Result<FlateDecoder<R>, FlateCompressionError> {
+        FlateDecoder::new(*self, read)
+    }
+
+    /// A number that identifies the method in compressed data.
+    ///
+    /// The number of each method is stable across versions and features.
+    pub fn id(self) -> u8 {
+        match self {
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Deflate => 0,
+            #[cfg(feature = "zstd")]
+            CompressionMethod::Zstd => 1,
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Gzip => 2,
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Zlib => 3,
+            #[cfg(feature = "lz4")]
+            CompressionMethod::Lz4 => 4,
+            #[cfg(feature = "xz")]
+            CompressionMethod::Xz => 5,
+            #[cfg(feature = "snappy")]
+            CompressionMethod::Snappy => 6,
+            CompressionMethod::Store => 7,
+        }
+    }
+
+    /// Returns the method identified by `id`, or `None` if it is unknown or not enabled.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            #[cfg(feature = "deflate")]
+            0 => Some(CompressionMethod::Deflate),
+            #[cfg(feature = "zstd")]
+            1 => Some(CompressionMethod::Zstd),
+            #[cfg(feature = "deflate")]
+            2 => Some(CompressionMethod::Gzip),
+            #[cfg(feature = "deflate")]
+            3 => Some(CompressionMethod::Zlib),
+            #[cfg(feature = "lz4")]
+            4 => Some(CompressionMethod::Lz4),
+            #[cfg(feature = "xz")]
+            5 => Some(CompressionMethod::Xz),
+            #[cfg(feature = "snappy")]
+            6 => Some(CompressionMethod::Snappy),
+            7 => Some(CompressionMethod::Store),
+            _ => None,
+        }
     }
 }
 
-#[cfg(any(feature = "deflate", feature = "zstd"))]
-impl Default for CompressionMethod {
-    fn default() -> Self {
-        #[cfg(feature = "deflate")]
-        {
-            Self::Deflate
+/// A preset that trades compression speed for compressed size.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CompressionLevel {
+    /// Compress quickly at the cost of a larger output.
+    Fast,
+    /// The default tradeoff of each method.
+    #[default]
+    Balanced,
+    /// Produce the smallest output the method supports, however slow.
+    Max,
+}
+
+/// Method-specific tuning that is not covered by [`CompressionLevel`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompressionExtras {
+    /// Overrides the zstd compression level selected by the preset.
+    #[cfg(feature = "zstd")]
+    pub zstd_level: Option<i32>,
+    /// The number of zstd worker threads, or 0 to compress on the calling thread.
+    #[cfg(feature = "zstd-multithread")]
+    pub zstd_workers: u32,
+    /// Enables zstd long-distance matching, which finds repetitions far apart in large inputs.
+    #[cfg(feature = "zstd")]
+    pub zstd_long: bool,
+    /// The base-2 logarithm of the zstd window size, i.e. the longest distance of a match.
+    ///
+    /// Decompressing data compressed with a window larger than 2<sup>27</sup> bytes
+    /// needs a buffer as large as the window.
+    #[cfg(feature = "zstd")]
+    pub zstd_window_log: Option<u32>,
+    /// The size in bytes that zstd tries to keep each compressed block under.
+    #[cfg(feature = "zstd")]
+    pub zstd_target_block_size: Option<u32>,
+}
+
+/// The largest zstd window log that the decoders accept by default,
+/// which is the default limit of zstd.
+///
+/// Larger windows need as much memory to decompress,
+/// so data from untrusted sources must not be able to raise the limit.
+/// Only a [`Header`] records a larger window, for data compressed with one on purpose.
+pub const ZSTD_DEFAULT_WINDOW_LOG_MAX: u32 = 27;
+
+/// The largest zstd window log that the encoder can produce on this target.
+#[cfg(feature = "zstd")]
+pub(crate) const ZSTD_WINDOW_LOG_LIMIT: u32 = if cfg!(target_pointer_width = "64") {
+    31
+} else {
+    30
+};
+
+/// All parameters that affect compression.
+///
+/// A `CompressionMethod` converts into the options with the default level and extras,
+/// so it can be passed wherever options are accepted.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CompressionOptions {
+    pub method: CompressionMethod,
+    pub level: CompressionLevel,
+    pub extras: CompressionExtras,
+}
+
+impl CompressionOptions {
+    pub fn encoder<W: Write>(&self, write: W) -> Result<FlateEncoder<W>, FlateCompressionError> {
+        FlateEncoder::with_options(*self, write)
+    }
+
+    #[cfg(feature = "deflate")]
+    fn deflate_lz77(&self) -> DefaultLz77Encoder {
+        match self.level {
+            // A smaller window finds fewer matches, but searches them faster.
+            CompressionLevel::Fast => DefaultLz77Encoder::with_window_size(4096),
+            // libflate always uses the largest window by default.
+            CompressionLevel::Balanced | CompressionLevel::Max => DefaultLz77Encoder::new(),
         }
-        #[cfg(all(not(feature = "deflate"), feature = "zstd"))]
-        {
-            Self::Zstd
+    }
+
+    #[cfg(feature = "deflate")]
+    fn deflate_options(&self) -> DeflateEncodeOptions<DefaultLz77Encoder> {
+        DeflateEncodeOptions::with_lz77(self.deflate_lz77())
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd_level(&self) -> i32 {
+        self.extras.zstd_level.unwrap_or(match self.level {
+            CompressionLevel::Fast => 1,
+            // 0 selects the default level of the zstd library.
+            CompressionLevel::Balanced => 0,
+            CompressionLevel::Max => 19,
+        })
+    }
+
+    /// Applies the advanced zstd parameters in the extras to `encoder`.
+    #[cfg(feature = "zstd")]
+    fn configure_zstd<W: Write>(&self, encoder: &mut ZstdEncoder<'_, W>) -> io::Result<()> {
+        if self.extras.zstd_long {
+            encoder.long_distance_matching(true)?;
+        }
+        if let Some(log) = self.extras.zstd_window_log {
+            encoder.window_log(log)?;
+        }
+        if let Some(size) = self.extras.zstd_target_block_size {
+            encoder.set_parameter(zstd::stream::raw::CParameter::TargetCBlockSize(size))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "xz")]
+    fn xz_preset(&self) -> u32 {
+        match self.level {
+            CompressionLevel::Fast => 1,
+            CompressionLevel::Balanced => 6,
+            CompressionLevel::Max => 9,
+        }
+    }
+}
+
+impl From<CompressionMethod> for CompressionOptions {
+    fn from(method: CompressionMethod) -> Self {
+        Self {
+            method,
+            ..Self::default()
         }
     }
 }
@@ -100,31 +399,122 @@ pub enum FlateEncoder<W: Write> {
     Deflate(DeflateEncoder<W>),
     #[cfg(feature = "zstd")]
     Zstd(ZstdEncoder<'static, W>),
+    #[cfg(feature = "deflate")]
+    Gzip(gzip::Encoder<W>),
+    #[cfg(feature = "deflate")]
+    Zlib(zlib::Encoder<W>),
+    #[cfg(feature = "lz4")]
+    Lz4(Lz4Encoder<W>),
+    #[cfg(feature = "xz")]
+    Xz(XzEncoder<W>),
+    #[cfg(feature = "snappy")]
+    Snappy(Box<SnappyEncoder<W>>),
+    Store(W),
 }
 
-impl<'a, W: BufRead + Write + Seek + 'a> FlateEncoder<W> {
+impl<W: Write> FlateEncoder<W> {
     pub fn new(
         method: CompressionMethod,
         write: W,
     ) -> Result<FlateEncoder<W>, FlateCompressionError> {
-        match method {
+        Self::with_options(method.into(), write)
+    }
+
+    pub fn with_options(
+        options: CompressionOptions,
+        write: W,
+    ) -> Result<FlateEncoder<W>, FlateCompressionError> {
+        match options.method {
             #[cfg(feature = "deflate")]
-            CompressionMethod::Deflate => Ok(FlateEncoder::Deflate(DeflateEncoder::new(write))),
+            CompressionMethod::Deflate => Ok(FlateEncoder::Deflate(DeflateEncoder::with_options(
+                write,
+                options.deflate_options(),
+            ))),
             #[cfg(feature = "zstd")]
-            CompressionMethod::Zstd => ZstdEncoder::new(write, 0)
-                .map(FlateEncoder::Zstd)
-                .map_err(|e| FlateCompressionError::ZstdError(e)),
+            CompressionMethod::Zstd => {
+                #[allow(unused_mut)]
+                let mut encoder = ZstdEncoder::new(write, options.zstd_level())
+                    .and_then(|mut encoder| {
+                        options.configure_zstd(&mut encoder)?;
+                        Ok(encoder)
+                    })
+                    .map(FlateEncoder::Zstd)
+                    .map_err(FlateCompressionError::ZstdError)?;
+                #[cfg(feature = "zstd-multithread")]
+                encoder.multithread(options.extras.zstd_workers)?;
+                Ok(encoder)
+            }
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Gzip => gzip::Encoder::with_options(
+                write,
+                gzip::EncodeOptions::with_lz77(options.deflate_lz77()),
+            )
+            .map(FlateEncoder::Gzip)
+            .map_err(FlateCompressionError::DeflateError),
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Zlib => zlib::Encoder::with_options(
+                write,
+                zlib::EncodeOptions::with_lz77(options.deflate_lz77()),
+            )
+            .map(FlateEncoder::Zlib)
+            .map_err(FlateCompressionError::DeflateError),
+            #[cfg(feature = "lz4")]
+            CompressionMethod::Lz4 => Ok(FlateEncoder::Lz4(Lz4Encoder::new(write))),
+            #[cfg(feature = "xz")]
+            CompressionMethod::Xz => {
+                Ok(FlateEncoder::Xz(XzEncoder::new(write, options.xz_preset())))
+            }
+            #[cfg(feature = "snappy")]
+            CompressionMethod::Snappy => {
+                Ok(FlateEncoder::Snappy(Box::new(SnappyEncoder::new(write))))
+            }
+            CompressionMethod::Store => Ok(FlateEncoder::Store(write)),
+        }
+    }
+
+    /// Sets the number of worker threads used for compression.
+    ///
+    /// This only affects zstd; other methods always compress on the calling thread.
+    /// Setting 0 workers compresses on the calling thread.
+    #[cfg(feature = "zstd-multithread")]
+    pub fn multithread(&mut self, n_workers: u32) -> Result<(), FlateCompressionError> {
+        match self {
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Deflate(_) => Ok(()),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Gzip(_) | FlateEncoder::Zlib(_) => Ok(()),
+            FlateEncoder::Zstd(encoder) => encoder
+                .multithread(n_workers)
+                .map_err(FlateCompressionError::ZstdError),
+            #[cfg(feature = "lz4")]
+            FlateEncoder::Lz4(_) => Ok(()),
+            #[cfg(feature = "xz")]
+            FlateEncoder::Xz(_) => Ok(()),
+            #[cfg(feature = "snappy")]
+            FlateEncoder::Snappy(_) => Ok(()),
+            FlateEncoder::Store(_) => Ok(()),
         }
     }
 }
 
-impl<'a, W: Write + 'a> Write for FlateEncoder<W> {
+impl<W: Write> Write for FlateEncoder<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self {
             #[cfg(feature = "deflate")]
             FlateEncoder::Deflate(encoder) => encoder.write(buf),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Zlib(encoder) => encoder.write(buf),
             #[cfg(feature = "zstd")]
             FlateEncoder::Zstd(encoder) => encoder.write(buf),
+            #[cfg(feature = "lz4")]
+            FlateEncoder::Lz4(encoder) => encoder.write(buf),
+            #[cfg(feature = "xz")]
+            FlateEncoder::Xz(encoder) => encoder.write(buf),
+            #[cfg(feature = "snappy")]
+            FlateEncoder::Snappy(encoder) => encoder.write(buf),
+            FlateEncoder::Store(write) => write.write(buf),
         }
     }
 
@@ -132,82 +522,213 @@ impl<'a, W: Write + 'a> Write for FlateEncoder<W> {
         match self {
             #[cfg(feature = "deflate")]
             FlateEncoder::Deflate(encoder) => encoder.flush(),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Zlib(encoder) => encoder.flush(),
             #[cfg(feature = "zstd")]
             FlateEncoder::Zstd(encoder) => encoder.flush(),
+            #[cfg(feature = "lz4")]
+            FlateEncoder::Lz4(encoder) => encoder.flush(),
+            #[cfg(feature = "xz")]
+            FlateEncoder::Xz(encoder) => encoder.flush(),
+            #[cfg(feature = "snappy")]
+            FlateEncoder::Snappy(encoder) => encoder.flush(),
+            FlateEncoder::Store(write) => write.flush(),
         }
     }
 }
 
-impl<'a, W: Write + 'a> FlateEncoder<W> {
-    fn finish_encode(self) -> Result<W, FlateCompressionError> {
+impl<W: Write> FlateEncoder<W> {
+    /// Writes the remaining compressed data and returns the underlying writer.
+    ///
+    /// The compressed stream is incomplete unless this method is called.
+    pub fn finish_encode(self) -> Result<W, FlateCompressionError> {
         match self {
             #[cfg(feature = "deflate")]
             FlateEncoder::Deflate(encoder) => encoder
                 .finish()
                 .into_result()
-                .map_err(|e| FlateCompressionError::DeflateError(e)),
+                .map_err(FlateCompressionError::DeflateError),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Gzip(encoder) => encoder
+                .finish()
+                .into_result()
+                .map_err(FlateCompressionError::DeflateError),
+            #[cfg(feature = "deflate")]
+            FlateEncoder::Zlib(encoder) => encoder
+                .finish()
+                .into_result()
+                .map_err(FlateCompressionError::DeflateError),
             #[cfg(feature = "zstd")]
-            FlateEncoder::Zstd(encoder) => encoder
+            FlateEncoder::Zstd(encoder) => {
+                encoder.finish().map_err(FlateCompressionError::ZstdError)
+            }
+            #[cfg(feature = "lz4")]
+            FlateEncoder::Lz4(encoder) => encoder
                 .finish()
-                .map_err(|e| FlateCompressionError::ZstdError(e)),
+                .map_err(|err| FlateCompressionError::Lz4Error(err.into())),
+            #[cfg(feature = "xz")]
+            FlateEncoder::Xz(encoder) => encoder.finish().map_err(FlateCompressionError::XzError),
+            #[cfg(feature = "snappy")]
+            FlateEncoder::Snappy(encoder) => encoder
+                .into_inner()
+                .map_err(|err| FlateCompressionError::SnappyError(err.into_error())),
+            FlateEncoder::Store(write) => Ok(write),
         }
     }
 }
 
-pub trait ReadSeek: BufRead + Seek {}
-
-impl<T: BufRead + Seek> ReadSeek for T {}
+/// A stream that decompresses the data read from `R`.
+///
+/// The decompressed output is buffered, so `BufRead` adapters such as `lines()`
+/// can be used directly on the decoder.
+///
+/// If the `simd-inflate` feature is enabled, DEFLATE data are decompressed with
+/// the SIMD-accelerated inflate implementation from `zlib-rs`
+/// when the CPU supports it (AVX2 on x86, NEON on aarch64),
+/// falling back to `libflate` otherwise.
+/// The compressed format is the same either way.
+pub struct FlateDecoder<R: Read> {
+    inner: BufReader<DecoderKind<R>>,
+}
 
-pub enum FlateDecoder<'a> {
+enum DecoderKind<R: Read> {
+    #[cfg(feature = "deflate")]
+    Deflate(DeflateDecoder<R>),
+    #[cfg(feature = "simd-inflate")]
+    SimdDeflate(flate2::read::DeflateDecoder<R>),
     #[cfg(feature = "deflate")]
-    Deflate(DeflateDecoder<Box<dyn BufRead + 'a>>),
+    Gzip(gzip::Decoder<R>),
+    #[cfg(feature = "deflate")]
+    Zlib(zlib::Decoder<R>),
     #[cfg(feature = "zstd")]
-    Zstd(ZstdDecoder<'a, BufReader<Box<dyn BufRead + 'a>>>),
+    Zstd(ZstdDecoder<'static, BufReader<R>>),
+    #[cfg(feature = "lz4")]
+    Lz4(Lz4Decoder<R>),
+    #[cfg(feature = "xz")]
+    Xz(XzDecoder<R>),
+    #[cfg(feature = "snappy")]
+    Snappy(SnappyDecoder<R>),
+    Store(R),
+}
+
+/// Whether the CPU supports the vector instructions used by the SIMD inflate path.
+#[cfg(feature = "simd-inflate")]
+fn simd_inflate_supported() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    return std::is_x86_feature_detected!("avx2");
+    #[cfg(target_arch = "aarch64")]
+    return std::arch::is_aarch64_feature_detected!("neon");
+    #[allow(unreachable_code)]
+    false
 }
 
-impl<'a> FlateDecoder<'a> {
+impl<R: Read> FlateDecoder<R> {
+    /// Creates a decoder that accepts zstd windows up to [`ZSTD_DEFAULT_WINDOW_LOG_MAX`].
     pub fn new(
         method: CompressionMethod,
-        read: Box<dyn BufRead + 'a>,
-    ) -> Result<FlateDecoder<'a>, FlateCompressionError> {
-        match method {
+        read: R,
+    ) -> Result<FlateDecoder<R>, FlateCompressionError> {
+        Self::with_window_log_max(method, read, ZSTD_DEFAULT_WINDOW_LOG_MAX)
+    }
+
+    /// Creates a decoder that accepts zstd windows up to 2<sup>`window_log_max`</sup> bytes.
+    pub(crate) fn with_window_log_max(
+        method: CompressionMethod,
+        read: R,
+        #[allow(unused_variables)] window_log_max: u32,
+    ) -> Result<FlateDecoder<R>, FlateCompressionError> {
+        let kind = match method {
+            #[cfg(feature = "simd-inflate")]
+            CompressionMethod::Deflate if simd_inflate_supported() => {
+                DecoderKind::SimdDeflate(flate2::read::DeflateDecoder::new(read))
+            }
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Deflate => DecoderKind::Deflate(DeflateDecoder::new(read)),
             #[cfg(feature = "deflate")]
-            CompressionMethod::Deflate => Ok(FlateDecoder::Deflate(DeflateDecoder::new(read))),
+            CompressionMethod::Gzip => DecoderKind::Gzip(
+                gzip::Decoder::new(read).map_err(FlateCompressionError::DeflateError)?,
+            ),
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Zlib => DecoderKind::Zlib(
+                zlib::Decoder::new(read).map_err(FlateCompressionError::DeflateError)?,
+            ),
             #[cfg(feature = "zstd")]
             CompressionMethod::Zstd => {
-                let decoder = ZstdDecoder::new(read)?;
-                Ok(FlateDecoder::Zstd(decoder))
+                let mut decoder = ZstdDecoder::new(read)?;
+                decoder.window_log_max(window_log_max)?;
+                DecoderKind::Zstd(decoder)
             }
-        }
+            #[cfg(feature = "lz4")]
+            CompressionMethod::Lz4 => DecoderKind::Lz4(Lz4Decoder::new(read)),
+            #[cfg(feature = "xz")]
+            CompressionMethod::Xz => DecoderKind::Xz(XzDecoder::new(read)),
+            #[cfg(feature = "snappy")]
+            CompressionMethod::Snappy => DecoderKind::Snappy(SnappyDecoder::new(read)),
+            CompressionMethod::Store => DecoderKind::Store(read),
+        };
+        Ok(FlateDecoder {
+            inner: BufReader::new(kind),
+        })
     }
 }
 
-impl<'a> Read for FlateDecoder<'a> {
+impl<R: Read> Read for DecoderKind<R> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match self {
             #[cfg(feature = "deflate")]
-            FlateDecoder::Deflate(decoder) => decoder.read(buf),
+            DecoderKind::Deflate(decoder) => decoder.read(buf),
+            #[cfg(feature = "simd-inflate")]
+            DecoderKind::SimdDeflate(decoder) => decoder.read(buf),
+            #[cfg(feature = "deflate")]
+            DecoderKind::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "deflate")]
+            DecoderKind::Zlib(decoder) => decoder.read(buf),
             #[cfg(feature = "zstd")]
-            FlateDecoder::Zstd(decoder) => decoder.read(buf),
+            DecoderKind::Zstd(decoder) => decoder.read(buf),
+            #[cfg(feature = "lz4")]
+            DecoderKind::Lz4(decoder) => decoder.read(buf),
+            #[cfg(feature = "xz")]
+            DecoderKind::Xz(decoder) => decoder.read(buf),
+            #[cfg(feature = "snappy")]
+            DecoderKind::Snappy(decoder) => decoder.read(buf),
+            DecoderKind::Store(read) => read.read(buf),
         }
     }
 }
 
-pub fn apply_compression<R: Sized, W: Sized + BufRead + Seek>(
+impl<R: Read> Read for FlateDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Read> BufRead for FlateDecoder<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+pub fn apply_compression<R, W>(
     reader: &mut R,
     writer: &mut W,
-    method: CompressionMethod,
+    options: impl Into<CompressionOptions>,
 ) -> Result<(), FlateCompressionError>
 where
     R: Read,
     W: Write,
 {
-    let mut encoder = method.encoder(writer)?;
+    let mut encoder = options.into().encoder(writer)?;
     io::copy(reader, &mut encoder)?;
     encoder.finish_encode().map(|_| ())
 }
 
-pub fn apply_decompression<R: Sized + BufRead + Seek, W: Sized>(
+pub fn apply_decompression<R, W>(
     reader: &mut R,
     writer: &mut W,
     method: CompressionMethod,
@@ -220,3 +741,125 @@ where
     io::copy(&mut decoder, writer)?;
     Ok(())
 }
+
+/// Compresses `data` in memory with the given method or options.
+pub fn compress_bytes(
+    data: &[u8],
+    options: impl Into<CompressionOptions>,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let mut source = data;
+    let mut compressed = Vec::new();
+    apply_compression(&mut source, &mut compressed, options)?;
+    Ok(compressed)
+}
+
+/// Decompresses `data` in memory with `method`.
+///
+/// This reuses a [`DecompressScratch`] cached for the current thread.
+pub fn decompress_bytes(
+    data: &[u8],
+    method: CompressionMethod,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    scratch::with_thread_scratch(|scratch| decompress_bytes_with(data, method, scratch))
+}
+
+/// Decompresses `data` in memory with `method`,
+/// failing with [`FlateCompressionError::TooLarge`] as soon as the output exceeds `limit` bytes.
+///
+/// This guards against data that were altered to decompress to far more than expected,
+/// since at most `limit + 1` bytes are ever decompressed.
+/// The output is allocated with a capacity of `limit` up front instead of growing repeatedly,
+/// so `limit` should be the expected size, such as [`Header::len`],
+/// rather than a loose upper bound.
+/// This reuses a [`DecompressScratch`] cached for the current thread.
+pub fn decompress_bounded(
+    data: &[u8],
+    method: CompressionMethod,
+    limit: u64,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    scratch::with_thread_scratch(|scratch| decompress_bounded_with(data, method, scratch, limit))
+}
+
+/// Reads `reader` to the end, failing if it yields more than `limit` bytes.
+///
+/// Unless `limit` is `u64::MAX`, it is the expected size,
+/// which the output is allocated with up front.
+pub(crate) fn read_bounded(
+    reader: impl Read,
+    limit: u64,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let mut output = Vec::new();
+    if limit != u64::MAX {
+        // `read_to_end` probes for more data before growing a full buffer,
+        // so the output is never reallocated if the size is right
+        let reserved =
+            usize::try_from(limit).is_ok_and(|capacity| output.try_reserve_exact(capacity).is_ok());
+        if !reserved {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                format!("cannot allocate {} bytes for the decompressed data", limit),
+            )
+            .into());
+        }
+    }
+    // read one more byte than allowed to tell whether there are more
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut output)?;
+    if output.len() as u64 > limit {
+        return Err(FlateCompressionError::TooLarge { limit });
+    }
+    Ok(output)
+}
+
+/// Returns the compressed size as a percentage of the original size.
+///
+/// Smaller is better; a ratio above 100 means compression made the data larger.
+/// The result is not finite if `original_size` is 0.
+pub fn compression_ratio(original_size: u64, compressed_size: u64) -> f64 {
+    (compressed_size as f64 / original_size as f64) * 100.0
+}
+
+/// Compresses the data from `reader` and returns the compressed size without storing the output.
+///
+/// If `limit` is set and the compressed output exceeds it,
+/// compression is aborted early and `None` is returned.
+/// This is useful for deciding whether compressing is worthwhile,
+/// e.g. by passing the original size as the limit.
+pub fn estimate_compressed_size<R: Read>(
+    reader: &mut R,
+    options: impl Into<CompressionOptions>,
+    limit: Option<u64>,
+) -> Result<Option<u64>, FlateCompressionError> {
+    let mut counter = CountingWriter {
+        written: 0,
+        limit,
+        exceeded: false,
+    };
+    match apply_compression(reader, &mut counter, options) {
+        Ok(()) => Ok(Some(counter.written)),
+        Err(_) if counter.exceeded => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+struct CountingWriter {
+    written: u64,
+    limit: Option<u64>,
+    exceeded: bool,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += buf.len() as u64;
+        if self.limit.is_some_and(|limit| self.written > limit) {
+            self.exceeded = true;
+            return Err(io::Error::other("compressed size limit exceeded"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
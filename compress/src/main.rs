@@ -0,0 +1,350 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compresses and decompresses files in the format that `deflate_file!` embeds,
+//! i.e. a [`Header`] followed by the compressed data,
+//! so that compatible data can be produced and checked outside of the macros.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use include_flate_compress::{
+    compress_bytes, CompressionLevel, CompressionMethod, CompressionOptions, Header, HEADER_SIZE,
+};
+
+const USAGE: &str = "\
+Compresses and decompresses data in the format embedded by include-flate
+
+Usage: include-flate-compress <COMMAND> [OPTIONS] [INPUT]
+
+Commands:
+  compress      Compresses INPUT and prepends the header
+  decompress    Decompresses INPUT and verifies its checksum
+  inspect       Prints the header of INPUT
+
+INPUT is read from standard input if it is omitted or `-`.
+
+Options:
+  -o, --output <PATH>       Writes to PATH instead of standard output
+  -m, --method <METHOD>     The algorithm to compress with, as written after `with`
+                            [default: the default of the `deflate` and `default-zstd` features]
+  -l, --level <LEVEL>       `fast`, `balanced` or `max` [default: balanced]
+";
+
+/// The options shared by all commands.
+struct Options {
+    input: Option<String>,
+    output: Option<String>,
+    method: CompressionMethod,
+    level: CompressionLevel,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let Some(command) = args.first() else {
+        print!("{}", USAGE);
+        return Ok(());
+    };
+    if matches!(command.as_str(), "-h" | "--help" | "help") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    let options = parse(&args[1..])?;
+
+    match command.as_str() {
+        "compress" => {
+            let input = read(&options)?;
+            let compressed = compress_bytes(
+                &input,
+                CompressionOptions {
+                    method: options.method,
+                    level: options.level,
+                    ..CompressionOptions::default()
+                },
+            )
+            .map_err(|err| err.to_string())?;
+            write(
+                &options,
+                &Header::new(options.method, &input).prepend(&compressed),
+            )
+        }
+        "decompress" => {
+            let input = read(&options)?;
+            let (header, data) = parse_header(&input)?;
+            let output = header.decompress(data).map_err(|err| err.to_string())?;
+            header.verify(&output).map_err(|err| err.to_string())?;
+            write(&options, &output)
+        }
+        "inspect" => {
+            let input = read(&options)?;
+            let (header, data) = parse_header(&input)?;
+            let report = format!(
+                "algorithm: {} (id {})\noriginal size: {}\ncompressed size: {}\ncrc32: {:08x}\n",
+                method_name(header.method),
+                header.method.id(),
+                header.len,
+                HEADER_SIZE + data.len(),
+                header.crc32,
+            );
+            write(&options, report.as_bytes())
+        }
+        _ => Err(format!("unknown command {:?}\n\n{}", command, USAGE)),
+    }
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        input: None,
+        output: None,
+        method: CompressionMethod::DEFAULT,
+        level: CompressionLevel::default(),
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", arg))
+        };
+        match arg.as_str() {
+            "-o" | "--output" => options.output = Some(value()?.clone()),
+            "-m" | "--method" => options.method = parse_method(value()?)?,
+            "-l" | "--level" => {
+                options.level = match value()?.as_str() {
+                    "fast" => CompressionLevel::Fast,
+                    "balanced" => CompressionLevel::Balanced,
+                    "max" => CompressionLevel::Max,
+                    level => return Err(format!("unknown level {:?}", level)),
+                }
+            }
+            _ if options.input.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                options.input = Some(arg.clone())
+            }
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// Resolves the name of an algorithm as it would be written after `with`.
+///
+/// The algorithms that are not enabled are rejected,
+/// since this binary is built with the same features as the library.
+fn parse_method(name: &str) -> Result<CompressionMethod, String> {
+    match name {
+        #[cfg(feature = "deflate")]
+        "deflate" => Ok(CompressionMethod::Deflate),
+        #[cfg(feature = "zstd")]
+        "zstd" => Ok(CompressionMethod::Zstd),
+        #[cfg(feature = "deflate")]
+        "gzip" => Ok(CompressionMethod::Gzip),
+        #[cfg(feature = "deflate")]
+        "zlib" => Ok(CompressionMethod::Zlib),
+        #[cfg(feature = "lz4")]
+        "lz4" => Ok(CompressionMethod::Lz4),
+        #[cfg(feature = "xz")]
+        "xz" => Ok(CompressionMethod::Xz),
+        #[cfg(feature = "snappy")]
+        "snappy" => Ok(CompressionMethod::Snappy),
+        "none" => Ok(CompressionMethod::Store),
+        _ => Err(format!(
+            "unknown algorithm {:?}, or its feature is not enabled",
+            name
+        )),
+    }
+}
+
+/// Returns the name of `method` as it would be written after `with`.
+fn method_name(method: CompressionMethod) -> String {
+    match method {
+        CompressionMethod::Store => "none".to_string(),
+        method => format!("{:?}", method).to_lowercase(),
+    }
+}
+
+fn parse_header(input: &[u8]) -> Result<(Header, &[u8]), String> {
+    Header::parse(input).ok_or_else(|| {
+        "the input does not start with a header, or its algorithm is not enabled".to_string()
+    })
+}
+
+fn read(options: &Options) -> Result<Vec<u8>, String> {
+    match options.input.as_deref() {
+        None | Some("-") => {
+            let mut input = Vec::new();
+            io::stdin()
+                .read_to_end(&mut input)
+                .map_err(|err| format!("cannot read standard input: {}", err))?;
+            Ok(input)
+        }
+        Some(path) => fs::read(path).map_err(|err| format!("cannot read {:?}: {}", path, err)),
+    }
+}
+
+fn write(options: &Options, data: &[u8]) -> Result<(), String> {
+    match options.output.as_deref() {
+        None | Some("-") => io::stdout()
+            .write_all(data)
+            .map_err(|err| format!("cannot write standard output: {}", err)),
+        Some(path) => {
+            fs::write(path, data).map_err(|err| format!("cannot write {:?}: {}", path, err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "include-flate-compress-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_parse() {
+        let options = parse(&args(&["input.txt", "-o", "out", "--level", "max"])).unwrap();
+        assert_eq!(options.input.as_deref(), Some("input.txt"));
+        assert_eq!(options.output.as_deref(), Some("out"));
+        assert_eq!(options.method, CompressionMethod::DEFAULT);
+        assert_eq!(options.level, CompressionLevel::Max);
+
+        let options = parse(&args(&["-m", "none", "-"])).unwrap();
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert_eq!(options.output, None);
+        assert_eq!(options.method, CompressionMethod::Store);
+
+        assert_eq!(
+            parse(&args(&["-m", "brotli"])).err().unwrap(),
+            "unknown algorithm \"brotli\", or its feature is not enabled"
+        );
+        assert_eq!(
+            parse(&args(&["-l", "best"])).err().unwrap(),
+            "unknown level \"best\""
+        );
+        assert_eq!(parse(&args(&["-o"])).err().unwrap(), "-o requires a value");
+        assert_eq!(
+            parse(&args(&["a", "b"])).err().unwrap(),
+            "unexpected argument \"b\""
+        );
+        assert_eq!(
+            parse(&args(&["--verbose"])).err().unwrap(),
+            "unexpected argument \"--verbose\""
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        assert!(run(&[]).is_ok());
+        assert!(run(&args(&["--help"])).is_ok());
+        assert!(run(&args(&["extract"]))
+            .err()
+            .unwrap()
+            .starts_with("unknown command \"extract\""));
+
+        let missing = temp("missing");
+        let err = run(&args(&["compress", missing.to_str().unwrap()]))
+            .err()
+            .unwrap();
+        assert!(err.starts_with("cannot read"), "{}", err);
+
+        let input = temp("not-a-header");
+        fs::write(&input, "plain text").unwrap();
+        let err = run(&args(&["inspect", input.to_str().unwrap()]))
+            .err()
+            .unwrap();
+        assert!(err.starts_with("the input does not start with a header"));
+        fs::remove_file(input).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = "include-flate ".repeat(1000);
+        let [input, compressed, inspected, decompressed] =
+            ["input", "compressed", "inspected", "decompressed"].map(temp);
+        fs::write(&input, &original).unwrap();
+        let path = |path: &PathBuf| path.to_str().unwrap().to_string();
+
+        for method in ["none", "zstd"] {
+            if cfg!(not(feature = "zstd")) && method == "zstd" {
+                continue;
+            }
+            run(&[
+                "compress".to_string(),
+                path(&input),
+                "-m".to_string(),
+                method.to_string(),
+                "-o".to_string(),
+                path(&compressed),
+            ])
+            .unwrap();
+            run(&[
+                "inspect".to_string(),
+                path(&compressed),
+                "-o".to_string(),
+                path(&inspected),
+            ])
+            .unwrap();
+            run(&[
+                "decompress".to_string(),
+                path(&compressed),
+                "-o".to_string(),
+                path(&decompressed),
+            ])
+            .unwrap();
+
+            let compressed_len = fs::metadata(&compressed).unwrap().len();
+            let report = fs::read_to_string(&inspected).unwrap();
+            assert!(report.starts_with(&format!("algorithm: {} (id ", method)));
+            assert!(report.contains(&format!("original size: {}\n", original.len())));
+            assert!(report.contains(&format!("compressed size: {}\n", compressed_len)));
+            assert_eq!(fs::read_to_string(&decompressed).unwrap(), original);
+        }
+
+        // a corrupted checksum is rejected
+        let mut data = fs::read(&compressed).unwrap();
+        data[12] ^= 1;
+        fs::write(&compressed, data).unwrap();
+        let err = run(&args(&["decompress", compressed.to_str().unwrap()]))
+            .err()
+            .unwrap();
+        assert!(err.contains("checksum"), "{}", err);
+
+        for path in [input, compressed, inspected, decompressed] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
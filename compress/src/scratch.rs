@@ -0,0 +1,123 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decompression state that is reused across calls instead of being allocated every time.
+
+use std::cell::RefCell;
+use std::io;
+
+use crate::{
+    read_bounded, CompressionMethod, FlateCompressionError, FlateDecoder,
+    ZSTD_DEFAULT_WINDOW_LOG_MAX,
+};
+
+/// Reusable state for [`decompress_bytes_with`].
+///
+/// Creating a zstd decompression context allocates its window and tables,
+/// which dominates the cost of decompressing many small assets in sequence.
+/// Keeping a `DecompressScratch` around lets consecutive calls reuse that context.
+///
+/// DEFLATE decoders do not expose their window, so DEFLATE decompression
+/// does not benefit from the scratch state.
+pub struct DecompressScratch {
+    #[cfg(feature = "zstd")]
+    zstd: zstd::zstd_safe::DCtx<'static>,
+}
+
+impl DecompressScratch {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "zstd")]
+            zstd: zstd::zstd_safe::DCtx::create(),
+        }
+    }
+}
+
+impl Default for DecompressScratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    static SCRATCH: RefCell<DecompressScratch> = RefCell::new(DecompressScratch::new());
+}
+
+/// Decompresses `data` in memory with the given method, reusing the state in `scratch`.
+pub fn decompress_bytes_with(
+    data: &[u8],
+    method: CompressionMethod,
+    scratch: &mut DecompressScratch,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    decompress(data, method, scratch, u64::MAX, ZSTD_DEFAULT_WINDOW_LOG_MAX)
+}
+
+/// Like [`decompress_bytes_with`], but fails with [`FlateCompressionError::TooLarge`]
+/// as soon as the output exceeds `limit` bytes, instead of decompressing the rest.
+pub fn decompress_bounded_with(
+    data: &[u8],
+    method: CompressionMethod,
+    scratch: &mut DecompressScratch,
+    limit: u64,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    decompress(data, method, scratch, limit, ZSTD_DEFAULT_WINDOW_LOG_MAX)
+}
+
+/// Decompresses `data` like [`decompress_bounded_with`],
+/// accepting zstd windows up to 2<sup>`window_log_max`</sup> bytes.
+pub(crate) fn decompress(
+    data: &[u8],
+    method: CompressionMethod,
+    scratch: &mut DecompressScratch,
+    limit: u64,
+    window_log_max: u32,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    match method {
+        #[cfg(feature = "zstd")]
+        CompressionMethod::Zstd => {
+            let zstd_error = |code| {
+                FlateCompressionError::ZstdError(io::Error::other(zstd::zstd_safe::get_error_name(
+                    code,
+                )))
+            };
+            // the previous call may have stopped in the middle of a frame,
+            // or accepted a larger window
+            scratch
+                .zstd
+                .reset(zstd::zstd_safe::ResetDirective::SessionOnly)
+                .map_err(zstd_error)?;
+            scratch
+                .zstd
+                .set_parameter(zstd::zstd_safe::DParameter::WindowLogMax(window_log_max))
+                .map_err(zstd_error)?;
+            let decoder = zstd::stream::read::Decoder::with_context(data, &mut scratch.zstd);
+            read_bounded(decoder, limit)
+        }
+        #[allow(unreachable_patterns)]
+        _ => read_bounded(
+            FlateDecoder::with_window_log_max(method, data, window_log_max)?,
+            limit,
+        ),
+    }
+}
+
+/// Runs `f` with the scratch state of the current thread,
+/// or with a fresh one if the state of the current thread is already in use.
+pub(crate) fn with_thread_scratch<T>(f: impl FnOnce(&mut DecompressScratch) -> T) -> T {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut scratch) => f(&mut scratch),
+        Err(_) => f(&mut DecompressScratch::new()),
+    })
+}
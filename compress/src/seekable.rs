@@ -0,0 +1,192 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The [zstd seekable format][1], which allows decompressing parts of the data.
+//!
+//! The data are split into independently compressed zstd frames,
+//! followed by a seek table in a skippable frame.
+//! Since zstd decoders ignore skippable frames,
+//! the output can still be decompressed as a whole like any other zstd data.
+//!
+//!   [1]: https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md
+
+use std::io;
+use std::ops::Range;
+
+use crate::{compress_bytes, CompressionMethod, CompressionOptions, FlateCompressionError};
+
+/// The number of decompressed bytes in each frame, unless specified otherwise.
+pub const DEFAULT_FRAME_SIZE: usize = 64 * 1024;
+
+const SKIPPABLE_MAGIC: u32 = 0x184D2A5E;
+const SEEKABLE_MAGIC: u32 = 0x8F92EAB1;
+const FOOTER_SIZE: usize = 9;
+const CHECKSUM_FLAG: u8 = 0x80;
+
+/// Compresses `data` in the zstd seekable format,
+/// with each frame holding `frame_size` bytes of decompressed data.
+///
+/// The method in `options` must be zstd.
+pub fn compress_seekable(
+    data: &[u8],
+    options: impl Into<CompressionOptions>,
+    frame_size: usize,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let options = options.into();
+    if options.method != CompressionMethod::Zstd {
+        return Err(FlateCompressionError::SeekableUnsupported(options.method));
+    }
+    if frame_size == 0 || frame_size > u32::MAX as usize {
+        return Err(invalid_input("frame size must be between 1 and u32::MAX"));
+    }
+
+    let mut output = Vec::new();
+    let mut entries = Vec::new();
+    // emit an empty frame for empty data so that the output is still valid zstd
+    let chunks = data
+        .chunks(frame_size)
+        .chain(data.is_empty().then_some(&[][..]));
+    for chunk in chunks {
+        let frame = compress_bytes(chunk, options)?;
+        let compressed_size = u32::try_from(frame.len())
+            .map_err(|_| invalid_input("compressed frame is larger than u32::MAX"))?;
+        entries.push((compressed_size, chunk.len() as u32));
+        output.extend_from_slice(&frame);
+    }
+
+    let frame_count = u32::try_from(entries.len())
+        .map_err(|_| invalid_input("too many frames for the seek table"))?;
+    output.extend_from_slice(&SKIPPABLE_MAGIC.to_le_bytes());
+    output.extend_from_slice(&(frame_count * 8 + FOOTER_SIZE as u32).to_le_bytes());
+    for (compressed_size, decompressed_size) in entries {
+        output.extend_from_slice(&compressed_size.to_le_bytes());
+        output.extend_from_slice(&decompressed_size.to_le_bytes());
+    }
+    output.extend_from_slice(&frame_count.to_le_bytes());
+    output.push(0);
+    output.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+
+    Ok(output)
+}
+
+/// The positions of the frames in data in the zstd seekable format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeekTable {
+    /// The compressed and decompressed ranges of each frame.
+    frames: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl SeekTable {
+    /// Reads the seek table at the end of `data`.
+    ///
+    /// Returns `None` if `data` is not in the seekable format.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let footer = data
+            .len()
+            .checked_sub(FOOTER_SIZE)
+            .map(|start| &data[start..])?;
+        if read_u32(footer, 5) != SEEKABLE_MAGIC || footer[4] & !CHECKSUM_FLAG != 0 {
+            return None;
+        }
+
+        let entry_size = if footer[4] & CHECKSUM_FLAG != 0 {
+            12
+        } else {
+            8
+        };
+        let frame_count = read_u32(footer, 0) as usize;
+        let table_size = frame_count
+            .checked_mul(entry_size)?
+            .checked_add(FOOTER_SIZE)?;
+        let table_start = data.len().checked_sub(table_size + 8)?;
+        if read_u32(data, table_start) != SKIPPABLE_MAGIC
+            || read_u32(data, table_start + 4) as usize != table_size
+        {
+            return None;
+        }
+
+        let mut frames = Vec::with_capacity(frame_count);
+        let (mut compressed, mut decompressed) = (0usize, 0usize);
+        for entry in data[table_start + 8..]
+            .chunks_exact(entry_size)
+            .take(frame_count)
+        {
+            let compressed_end = compressed.checked_add(read_u32(entry, 0) as usize)?;
+            let decompressed_end = decompressed.checked_add(read_u32(entry, 4) as usize)?;
+            frames.push((compressed..compressed_end, decompressed..decompressed_end));
+            compressed = compressed_end;
+            decompressed = decompressed_end;
+        }
+
+        (compressed == table_start).then_some(Self { frames })
+    }
+
+    /// The number of frames.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The total size of the decompressed data.
+    pub fn decompressed_len(&self) -> usize {
+        self.frames
+            .last()
+            .map_or(0, |(_, decompressed)| decompressed.end)
+    }
+}
+
+/// Decompresses `range` of the data compressed with [`compress_seekable`],
+/// only decompressing the frames that overlap with the range.
+pub fn decompress_seekable_range(
+    data: &[u8],
+    range: Range<usize>,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let table = SeekTable::parse(data).ok_or_else(|| {
+        FlateCompressionError::ZstdError(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "data are not in the seekable format",
+        ))
+    })?;
+    if range.start > range.end || range.end > table.decompressed_len() {
+        return Err(invalid_input("range is out of bounds"));
+    }
+
+    let mut decompressor =
+        zstd::bulk::Decompressor::new().map_err(FlateCompressionError::ZstdError)?;
+    let mut output = Vec::with_capacity(range.len());
+    for (compressed, decompressed) in &table.frames {
+        if decompressed.end <= range.start || decompressed.start >= range.end {
+            continue;
+        }
+
+        let frame = decompressor
+            .decompress(&data[compressed.clone()], decompressed.len())
+            .map_err(FlateCompressionError::ZstdError)?;
+        let start = range.start.saturating_sub(decompressed.start);
+        let end = range.end.min(decompressed.end) - decompressed.start;
+        output.extend_from_slice(&frame[start..end]);
+    }
+
+    Ok(output)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn invalid_input(message: &str) -> FlateCompressionError {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string()).into()
+}
@@ -0,0 +1,46 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ed25519 signatures of the contents of `signed` assets,
+//! which are created at compile time and verified after decompression.
+
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::FlateCompressionError;
+
+/// The size of a signing key, and of a verifying key, in bytes.
+pub const KEY_SIZE: usize = 32;
+
+/// The size of a signature in bytes.
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// Signs `data` with the signing key derived from the 32-byte `secret`.
+///
+/// Returns the verifying key along with the signature.
+pub fn sign(secret: &[u8; KEY_SIZE], data: &[u8]) -> ([u8; KEY_SIZE], [u8; SIGNATURE_SIZE]) {
+    let key = SigningKey::from_bytes(secret);
+    (key.verifying_key().to_bytes(), key.sign(data).to_bytes())
+}
+
+/// Checks that `signature` is a signature of `data` by the owner of the verifying `key`.
+pub fn verify_signature(
+    key: &[u8; KEY_SIZE],
+    signature: &[u8; SIGNATURE_SIZE],
+    data: &[u8],
+) -> Result<(), FlateCompressionError> {
+    let key = VerifyingKey::from_bytes(key).map_err(|_| FlateCompressionError::InvalidSignature)?;
+    key.verify(data, &ed25519_dalek::Signature::from_bytes(signature))
+        .map_err(|_| FlateCompressionError::InvalidSignature)
+}
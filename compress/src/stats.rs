@@ -0,0 +1,119 @@
+// include-flate
+// Copyright (C) SOFe, Kento Oki
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+use crate::{
+    apply_compression, apply_decompression, compression_ratio, CompressionMethod,
+    CompressionOptions, FlateCompressionError,
+};
+
+/// Measurements of a single compression or decompression run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// The number of bytes read from the input.
+    pub bytes_in: u64,
+    /// The number of bytes written to the output.
+    pub bytes_out: u64,
+    /// The wall-clock time spent on the run, including I/O on the input and output.
+    pub duration: Duration,
+}
+
+impl CompressionStats {
+    /// The number of input bytes processed per second.
+    pub fn throughput(&self) -> f64 {
+        self.bytes_in as f64 / self.duration.as_secs_f64()
+    }
+
+    /// The size of the compressed side as a percentage of the uncompressed side,
+    /// as in [`compression_ratio`].
+    ///
+    /// This works for both compression and decompression stats.
+    pub fn ratio(&self) -> f64 {
+        let (original, compressed) = if self.bytes_in >= self.bytes_out {
+            (self.bytes_in, self.bytes_out)
+        } else {
+            (self.bytes_out, self.bytes_in)
+        };
+        compression_ratio(original, compressed)
+    }
+}
+
+/// Like [`apply_compression`], but also measures the run.
+pub fn apply_compression_with_stats<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    options: impl Into<CompressionOptions>,
+) -> Result<CompressionStats, FlateCompressionError> {
+    let mut reader = Counted::new(reader);
+    let mut writer = Counted::new(writer);
+    let start = Instant::now();
+    apply_compression(&mut reader, &mut writer, options)?;
+    Ok(CompressionStats {
+        bytes_in: reader.count,
+        bytes_out: writer.count,
+        duration: start.elapsed(),
+    })
+}
+
+/// Like [`apply_decompression`], but also measures the run.
+pub fn apply_decompression_with_stats<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    method: CompressionMethod,
+) -> Result<CompressionStats, FlateCompressionError> {
+    let mut reader = Counted::new(reader);
+    let mut writer = Counted::new(writer);
+    let start = Instant::now();
+    apply_decompression(&mut reader, &mut writer, method)?;
+    Ok(CompressionStats {
+        bytes_in: reader.count,
+        bytes_out: writer.count,
+        duration: start.elapsed(),
+    })
+}
+
+/// Counts the bytes passing through a reader or writer.
+struct Counted<T> {
+    inner: T,
+    count: u64,
+}
+
+impl<T> Counted<T> {
+    fn new(inner: T) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<T: Read> Read for Counted<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+impl<T: Write> Write for Counted<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
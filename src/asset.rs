@@ -0,0 +1,651 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "signed")]
+use include_flate_compress::verify_signature;
+use include_flate_compress::{
+    decompress_chunked, decompress_chunked_range, ChunkIndex, ChunkedDecoder, CompressionMethod,
+    FlateCompressionError, HashAlgorithm, Header,
+};
+#[cfg(feature = "zstd")]
+use include_flate_compress::{decompress_seekable_range, decompress_with_dict_bounded, SeekTable};
+
+/// The prefix of environment variables that override the contents of a `flate!` static.
+#[cfg(feature = "runtime-override")]
+pub const OVERRIDE_ENV_PREFIX: &str = "INCLUDE_FLATE_OVERRIDE_";
+
+/// The environment variable of a directory whose files override the contents of `flate!` statics
+/// with the same paths.
+#[cfg(feature = "runtime-override")]
+pub const OVERRIDE_DIR_ENV: &str = "INCLUDE_FLATE_OVERRIDE_DIR";
+
+/// Describes where the contents of a `flate!` static come from.
+#[derive(Debug)]
+pub struct Asset {
+    /// The identifier of the static, if the asset is declared as a static.
+    pub ident: Option<&'static str>,
+    /// The name of the asset in runtime messages.
+    pub name: &'static str,
+    /// The path of the original file, if it should be read from disk.
+    pub source: Option<&'static str>,
+    /// The path of the file as passed to the macro,
+    /// if the contents can be overridden by a file in the override directory.
+    pub path: Option<&'static str>,
+    /// The algorithm and the digest of the contents, if declared with `checksum`.
+    pub checksum: Option<(HashAlgorithm, &'static [u8])>,
+    /// The verifying key and the signature of the contents, if declared with `signed`.
+    pub signature: Option<(&'static [u8; 32], &'static [u8; 64])>,
+    /// The compressed bytes, preceded by the header that describes them.
+    pub bytes: &'static [u8],
+    /// Whether the compressed bytes are independently compressed blocks followed by an index.
+    pub chunked: bool,
+    /// Returns the contents that `bytes` were compressed against, if any.
+    pub base: Option<fn() -> &'static [u8]>,
+}
+
+impl Asset {
+    /// Loads the contents of the asset.
+    pub fn load_bytes(&self) -> Vec<u8> {
+        match self.try_load_bytes() {
+            Ok(data) => data,
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Loads the contents of the asset as a string.
+    pub fn load_string(&self) -> String {
+        match self.try_load_string() {
+            Ok(string) => string,
+            // We should have checked for utf8 correctness in encode_utf8_file!,
+            // but the contents may come from a file that was not checked.
+            Err(FlateCompressionError::InvalidUtf8(_)) => {
+                panic!("{:?} has malformed UTF-8", self.name)
+            }
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Loads the contents of the asset,
+    /// or returns an error if the file cannot be read or the embedded data are corrupted.
+    pub fn try_load_bytes(&self) -> Result<Vec<u8>, FlateCompressionError> {
+        let start = std::time::Instant::now();
+
+        #[cfg(feature = "tracing")]
+        let span = {
+            let (header, data) = self.try_header()?;
+            tracing::info_span!(
+                "include_flate::load",
+                asset = self.name,
+                method = ?header.method,
+                compressed_size = data.len(),
+                decompressed_size = tracing::field::Empty,
+                duration = tracing::field::Empty,
+            )
+            .entered()
+        };
+
+        let data = match self.file() {
+            Some(path) => {
+                let data = std::fs::read(&path)?;
+                #[cfg(feature = "log")]
+                log::info!(
+                    "Read {} ({:?}) from {:?}: {} bytes",
+                    self.ident.unwrap_or("<inflate!>"),
+                    self.name,
+                    path,
+                    data.len()
+                );
+                data
+            }
+            None => {
+                let data = self.try_decode()?;
+                crate::hook::call(self.name, self.data().len(), data.len(), start.elapsed());
+                #[cfg(feature = "log")]
+                log::debug!(
+                    "Decompressed {} ({:?}): {} -> {} bytes",
+                    self.ident.unwrap_or("<inflate!>"),
+                    self.name,
+                    self.data().len(),
+                    data.len()
+                );
+                data
+            }
+        };
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::histogram!("include_flate_decompress_seconds", "asset" => self.name)
+                .record(start.elapsed());
+            metrics::gauge!("include_flate_decompressed_bytes", "asset" => self.name)
+                .set(data.len() as f64);
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            let duration = start.elapsed();
+            span.record("decompressed_size", data.len());
+            span.record("duration", tracing::field::debug(duration));
+            tracing::info!(
+                decompressed_size = data.len(),
+                ?duration,
+                "Loaded {:?}",
+                self.name
+            );
+        }
+
+        Ok(data)
+    }
+
+    /// Loads the contents of the asset as a string,
+    /// or returns an error if they cannot be loaded or are not valid UTF-8.
+    pub fn try_load_string(&self) -> Result<String, FlateCompressionError> {
+        String::from_utf8(self.try_load_bytes()?)
+            .map_err(|err| FlateCompressionError::InvalidUtf8(err.utf8_error()))
+    }
+
+    /// Loads the contents of the asset into the type that a `[u8]` static stores.
+    pub fn load_bytes_into<T: From<Vec<u8>>>(&self) -> T {
+        // the capacity is exact, so boxing the contents does not reallocate them
+        self.load_bytes().into()
+    }
+
+    /// Loads the contents of the asset into the type that a `str` static stores.
+    pub fn load_string_into<T: From<String>>(&self) -> T {
+        self.load_string().into()
+    }
+
+    /// Borrows the contents of the asset from the binary if they are stored without compression,
+    /// or loads them otherwise.
+    pub fn load_bytes_cow(&self) -> Cow<'static, [u8]> {
+        match self.stored() {
+            Some(data) => Cow::Borrowed(data),
+            None => Cow::Owned(self.load_bytes()),
+        }
+    }
+
+    /// Borrows the contents of the asset as a string from the binary
+    /// if they are stored without compression, or loads them otherwise.
+    pub fn load_string_cow(&self) -> Cow<'static, str> {
+        match self.stored() {
+            Some(data) => match std::str::from_utf8(data) {
+                Ok(string) => Cow::Borrowed(string),
+                Err(_) => panic!("{:?} has malformed UTF-8", self.name),
+            },
+            None => Cow::Owned(self.load_string()),
+        }
+    }
+
+    /// Returns the embedded contents if they are stored as a single stream without compression
+    /// and not read from a file instead.
+    fn stored(&self) -> Option<&'static [u8]> {
+        if self.chunked || self.base.is_some() || self.method() != CompressionMethod::Store {
+            return None;
+        }
+        if self.file().is_some() {
+            return None;
+        }
+
+        let data = self.data();
+        self.verify(data);
+        Some(data)
+    }
+
+    /// Opens a stream over the contents of the asset without loading them into memory.
+    pub(crate) fn open(&self) -> Box<dyn BufRead + Send> {
+        match self.file() {
+            Some(path) => match File::open(&path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(err) => panic!("Cannot read {:?} from {:?}: {}", self.name, path, err),
+            },
+            // zstd cannot stream with a borrowed dictionary through `FlateDecoder`
+            None if self.base.is_some() => Box::new(Cursor::new(self.decode())),
+            None if self.chunked => {
+                let method = self.method();
+                match ChunkedDecoder::new(self.data(), method) {
+                    Ok(decoder) => {
+                        Box::new(Bounded::new(BufReader::new(decoder), self.header().0.len))
+                    }
+                    Err(err) => panic!(
+                        "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                        method, self.name, err
+                    ),
+                }
+            }
+            None => {
+                let (header, data) = self.header();
+                let method = header.method;
+                match header.decoder(data) {
+                    Ok(decoder) => Box::new(Bounded::new(decoder, header.len)),
+                    Err(err) => panic!(
+                        "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                        method, self.name, err
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Writes the contents into `writer` as they are decompressed.
+    ///
+    /// The contents of a file are copied as they are.
+    /// Embedded contents are checked against the size and the checksum in the header,
+    /// or loaded into memory first if a digest or a signature is declared,
+    /// so that the contents are verified before any of them are written.
+    pub(crate) fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<u64> {
+        if self.file().is_some() {
+            return io::copy(&mut self.open(), writer);
+        }
+
+        let declared = self.checksum.is_some() || self.signature.is_some();
+        if declared || self.base.is_some() {
+            let data = self.try_decode().map_err(io_error)?;
+            writer.write_all(&data)?;
+            return Ok(data.len() as u64);
+        }
+
+        let (header, _) = self.try_header().map_err(io_error)?;
+        crate::copy_checked(&header, self.open(), writer).map_err(io_error)?;
+        Ok(header.len)
+    }
+
+    /// Reads `range` of the contents without loading the rest,
+    /// if the asset is read from a file, stored without compression,
+    /// or embedded in the zstd seekable format.
+    pub(crate) fn read_range(&self, range: Range<usize>) -> Option<Vec<u8>> {
+        if let Some(path) = self.file() {
+            return Some(self.read_file_range(&path, range));
+        }
+
+        if let Some(index) = self.chunk_index() {
+            self.check_range(&range, index.decompressed_len());
+            let method = self.method();
+            return match decompress_chunked_range(self.data(), method, range) {
+                Ok(data) => Some(data),
+                Err(err) => panic!(
+                    "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                    method, self.name, err
+                ),
+            };
+        }
+
+        if self.method() == CompressionMethod::Store {
+            self.check_range(&range, self.data().len());
+            return Some(self.data()[range].to_vec());
+        }
+
+        #[cfg(feature = "zstd")]
+        if self.method() == CompressionMethod::Zstd {
+            let table = SeekTable::parse(self.data())?;
+            self.check_range(&range, table.decompressed_len());
+            return match decompress_seekable_range(self.data(), range) {
+                Ok(data) => Some(data),
+                Err(err) => panic!(
+                    "Compiled seekable buffer for {:?} was corrupted: {:?}",
+                    self.name, err
+                ),
+            };
+        }
+
+        None
+    }
+
+    /// Returns the index of the blocks, if the asset is chunked.
+    pub(crate) fn chunk_index(&self) -> Option<ChunkIndex> {
+        if !self.chunked {
+            return None;
+        }
+
+        match ChunkIndex::parse(self.data()) {
+            Some(index) => Some(index),
+            None => panic!("Compiled chunk index for {:?} was corrupted", self.name),
+        }
+    }
+
+    /// Loads the contents into `buf`, which must be empty.
+    pub(crate) fn load_into_vec(&self, buf: &mut Vec<u8>) {
+        if let Some(path) = self.file() {
+            if let Err(err) = File::open(&path).and_then(|mut file| file.read_to_end(buf)) {
+                panic!("Cannot read {:?} from {:?}: {}", self.name, path, err);
+            }
+            return;
+        }
+
+        buf.resize(self.len(), 0);
+        self.decode_into(buf);
+    }
+
+    /// Loads the contents into the start of `buf` and returns their length.
+    pub(crate) fn load_into_slice(&self, buf: &mut [u8]) -> usize {
+        if let Some(path) = self.file() {
+            let result = File::open(&path).and_then(|mut file| {
+                let len = file.metadata()?.len() as usize;
+                self.check_capacity(len, buf.len());
+                file.read_exact(&mut buf[..len])?;
+                Ok(len)
+            });
+            return match result {
+                Ok(len) => len,
+                Err(err) => panic!("Cannot read {:?} from {:?}: {}", self.name, path, err),
+            };
+        }
+
+        let len = self.len();
+        self.check_capacity(len, buf.len());
+        self.decode_into(&mut buf[..len]);
+        len
+    }
+
+    /// Decompresses the embedded data into `buf`, which must be as long as the contents.
+    ///
+    /// This does not read the file that [`file`](Self::file) would return,
+    /// since the file need not have the same length as the embedded data.
+    pub(crate) fn decode_into(&self, buf: &mut [u8]) {
+        fn fill(mut reader: impl Read, buf: &mut [u8]) -> Result<(), FlateCompressionError> {
+            reader.read_exact(buf)?;
+            if reader.read(&mut [0])? != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the contents are longer than expected",
+                )
+                .into());
+            }
+            Ok(())
+        }
+
+        let (header, data) = self.header();
+        let method = header.method;
+        let result = if self.base.is_some() {
+            // zstd cannot stream with a borrowed dictionary through `FlateDecoder`
+            fill(Cursor::new(self.decode()), buf)
+        } else if self.chunked {
+            ChunkedDecoder::new(data, method).and_then(|decoder| fill(decoder, buf))
+        } else {
+            header.decoder(data).and_then(|decoder| fill(decoder, buf))
+        };
+        if let Err(err) = result {
+            panic!(
+                "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                method, self.name, err
+            );
+        }
+        self.verify(buf);
+    }
+
+    /// Decompresses the embedded data.
+    fn decode(&self) -> Vec<u8> {
+        match self.try_decode() {
+            Ok(data) => data,
+            Err(err) => self.corrupted(err),
+        }
+    }
+
+    fn try_decode(&self) -> Result<Vec<u8>, FlateCompressionError> {
+        let data = if let Some(base) = self.base {
+            self.decode_delta(base())?
+        } else if self.chunked {
+            let (header, data) = self.try_header()?;
+            decompress_chunked(data, header.method)?
+        } else {
+            // `try_decode` verifies the checksum in the header of a single stream itself
+            let data = crate::try_decode(self.bytes)?;
+            self.check_declared(&data)?;
+            return Ok(data);
+        };
+        self.check(&data)?;
+        Ok(data)
+    }
+
+    /// Checks the decompressed contents against the digest and the signature declared with
+    /// `checksum` and `signed`, and against the checksum in the header
+    /// with the `verify-checksum` feature.
+    fn verify(&self, data: &[u8]) {
+        if let Err(err) = self.check(data) {
+            self.corrupted(err);
+        }
+    }
+
+    fn check(&self, data: &[u8]) -> Result<(), FlateCompressionError> {
+        self.check_crc(data)?;
+        self.check_declared(data)
+    }
+
+    fn check_declared(&self, data: &[u8]) -> Result<(), FlateCompressionError> {
+        if let Some((algorithm, digest)) = self.checksum {
+            algorithm.verify(digest, data)?;
+        }
+        #[cfg(feature = "signed")]
+        if let Some((key, signature)) = self.signature {
+            verify_signature(key, signature, data)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "verify-checksum")]
+    fn check_crc(&self, data: &[u8]) -> Result<(), FlateCompressionError> {
+        self.try_header()?.0.verify(data)
+    }
+
+    #[cfg(not(feature = "verify-checksum"))]
+    fn check_crc(&self, _data: &[u8]) -> Result<(), FlateCompressionError> {
+        Ok(())
+    }
+
+    /// Panics because the contents could not be loaded,
+    /// naming the file that they were read from, if any.
+    fn fail(&self, err: FlateCompressionError) -> ! {
+        match self.file() {
+            Some(path) => panic!("Cannot read {:?} from {:?}: {}", self.name, path, err),
+            None => self.corrupted(err),
+        }
+    }
+
+    /// Panics because the embedded data are corrupted.
+    fn corrupted(&self, err: FlateCompressionError) -> ! {
+        let method = self.method();
+        #[cfg(feature = "log")]
+        log::error!(
+            "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+            method,
+            self.name,
+            err
+        );
+        panic!(
+            "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+            method, self.name, err
+        )
+    }
+
+    #[cfg(feature = "zstd")]
+    fn decode_delta(&self, base: &[u8]) -> Result<Vec<u8>, FlateCompressionError> {
+        let (header, data) = self.try_header()?;
+        decompress_with_dict_bounded(data, base, header.len)
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    fn decode_delta(&self, _base: &[u8]) -> Result<Vec<u8>, FlateCompressionError> {
+        panic!(
+            "{:?} is compressed against another asset, which requires the `zstd` feature",
+            self.name
+        )
+    }
+
+    fn read_file_range(&self, path: &Path, range: Range<usize>) -> Vec<u8> {
+        let result = File::open(path).and_then(|mut file| {
+            self.check_range(&range, file.metadata()?.len() as usize);
+            file.seek(SeekFrom::Start(range.start as u64))?;
+            let mut data = vec![0; range.len()];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        });
+        match result {
+            Ok(data) => data,
+            Err(err) => panic!("Cannot read {:?} from {:?}: {}", self.name, path, err),
+        }
+    }
+
+    fn check_range(&self, range: &Range<usize>, len: usize) {
+        if range.start > range.end || range.end > len {
+            panic!(
+                "range {:?} is out of bounds for {:?} of length {}",
+                range, self.name, len
+            );
+        }
+    }
+
+    pub(crate) fn check_capacity(&self, len: usize, capacity: usize) {
+        if len > capacity {
+            panic!(
+                "buffer of length {} is too short for {:?} of length {}",
+                capacity, self.name, len
+            );
+        }
+    }
+
+    /// Returns the length of the contents, reading the metadata of the file if it is read instead.
+    pub(crate) fn contents_len(&self) -> usize {
+        match self.file() {
+            Some(path) => match std::fs::metadata(&path) {
+                Ok(metadata) => metadata.len() as usize,
+                Err(err) => panic!("Cannot read {:?} from {:?}: {}", self.name, path, err),
+            },
+            None => self.len(),
+        }
+    }
+
+    /// Returns the length of the contents, as recorded in the header.
+    pub(crate) fn len(&self) -> usize {
+        self.header().0.len as usize
+    }
+
+    pub(crate) fn method(&self) -> CompressionMethod {
+        self.header().0.method
+    }
+
+    /// Returns the compressed data, without the header.
+    pub(crate) fn data(&self) -> &'static [u8] {
+        self.header().1
+    }
+
+    fn header(&self) -> (Header, &'static [u8]) {
+        match self.try_header() {
+            Ok(header) => header,
+            Err(_) => panic!("Compiled buffer for {:?} has an invalid header", self.name),
+        }
+    }
+
+    fn try_header(&self) -> Result<(Header, &'static [u8]), FlateCompressionError> {
+        Header::parse(self.bytes).ok_or(FlateCompressionError::InvalidHeader)
+    }
+
+    /// Returns the file to read instead of the embedded data, if any.
+    ///
+    /// A file set in the override environment variable of the static takes precedence over
+    /// a file in the override directory, then the file read from disk in debug builds,
+    /// then the embedded data.
+    /// Signed assets are never overridden, since other contents would not match the signature.
+    pub(crate) fn file(&self) -> Option<PathBuf> {
+        #[cfg(feature = "runtime-override")]
+        if let (Some(ident), None) = (self.ident, self.signature) {
+            if let Some(path) = std::env::var_os(format!("{}{}", OVERRIDE_ENV_PREFIX, ident)) {
+                return Some(path.into());
+            }
+        }
+
+        #[cfg(feature = "runtime-override")]
+        if let (Some(dir), Some(relative), None) = (
+            std::env::var_os(OVERRIDE_DIR_ENV),
+            self.path,
+            self.signature,
+        ) {
+            // the path is relative to the manifest directory, with either separator
+            let mut path = PathBuf::from(dir);
+            path.extend(
+                relative
+                    .split(['/', '\\'])
+                    .filter(|component| !component.is_empty()),
+            );
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+
+        self.source.map(|source| Path::new(source).to_path_buf())
+    }
+}
+
+/// Converts `err` into an I/O error, keeping the errors of the reader or writer as they are.
+fn io_error(err: FlateCompressionError) -> io::Error {
+    match err {
+        FlateCompressionError::IoError(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+    }
+}
+
+/// A stream over embedded contents that fails if they are longer or shorter than expected,
+/// so that corrupted data cannot yield more than the size recorded in the header.
+struct Bounded<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R> Bounded<R> {
+    fn new(inner: R, len: u64) -> Self {
+        Self {
+            inner,
+            remaining: len,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Bounded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Bounded<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let remaining = self.remaining;
+        let available = self.inner.fill_buf()?;
+        if available.len() as u64 > remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the contents are longer than expected",
+            ));
+        }
+        if available.is_empty() && remaining > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the contents are shorter than expected",
+            ));
+        }
+        Ok(available)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.remaining -= amt as u64;
+    }
+}
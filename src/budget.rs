@@ -0,0 +1,145 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Mutex, MutexGuard};
+
+/// A static whose contents are cached by [`Flate::shared`](crate::Flate::shared)
+/// or [`Flate::load`](crate::Flate::load).
+pub(crate) trait Evict: Sync {
+    /// Drops the cached contents unless a guard uses them, and returns whether they were dropped.
+    fn evict(&'static self) -> bool;
+}
+
+struct Entry {
+    flate: &'static dyn Evict,
+    len: usize,
+    /// The value of the clock when the contents were last used.
+    used: u64,
+}
+
+struct Cache {
+    budget: Option<usize>,
+    /// The sum of the lengths of the entries.
+    total: usize,
+    clock: u64,
+    entries: Vec<Entry>,
+}
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache {
+    budget: None,
+    total: 0,
+    clock: 0,
+    entries: Vec::new(),
+});
+
+fn lock() -> MutexGuard<'static, Cache> {
+    CACHE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn key(flate: &dyn Evict) -> usize {
+    flate as *const dyn Evict as *const () as usize
+}
+
+/// Sets the total number of decompressed bytes that statics may keep cached
+/// for [`Flate::shared`](crate::Flate::shared) and [`Flate::load`](crate::Flate::load),
+/// or removes the limit with `None`, which is the default.
+///
+/// When the total exceeds the budget, the contents of the statics that were used least recently
+/// are purged, except those still used by a [`FlateGuard`](crate::FlateGuard),
+/// and are decompressed again when needed.
+/// Contents borrowed by dereferencing a static are kept for the rest of the program,
+/// so they are not counted.
+pub fn set_cache_budget(budget: Option<usize>) {
+    lock().budget = budget;
+    enforce(None);
+}
+
+/// Returns the budget set with [`set_cache_budget`].
+pub fn cache_budget() -> Option<usize> {
+    lock().budget
+}
+
+/// Returns the total number of decompressed bytes that statics currently keep cached
+/// for [`Flate::shared`](crate::Flate::shared) and [`Flate::load`](crate::Flate::load).
+pub fn cached_bytes() -> usize {
+    lock().total
+}
+
+/// Records that the contents of `flate` were decompressed and cached.
+pub(crate) fn insert(flate: &'static dyn Evict, len: usize) {
+    let mut cache = lock();
+    cache.clock += 1;
+    let used = cache.clock;
+    cache.total += len;
+    cache.entries.push(Entry { flate, len, used });
+}
+
+/// Records that the cached contents of `flate` were used.
+pub(crate) fn touch(flate: &dyn Evict) {
+    let mut cache = lock();
+    cache.clock += 1;
+    let used = cache.clock;
+    if let Some(entry) = cache
+        .entries
+        .iter_mut()
+        .find(|entry| key(entry.flate) == key(flate))
+    {
+        entry.used = used;
+    }
+}
+
+/// Records that the cached contents of `flate` were dropped.
+pub(crate) fn remove(flate: &dyn Evict) {
+    let mut cache = lock();
+    if let Some(index) = cache
+        .entries
+        .iter()
+        .position(|entry| key(entry.flate) == key(flate))
+    {
+        let entry = cache.entries.swap_remove(index);
+        cache.total -= entry.len;
+    }
+}
+
+/// Purges the least recently used contents until the total is within the budget,
+/// except those of `current`, which are being used.
+///
+/// This must not be called while the contents of a static are locked,
+/// since evicting them locks the contents and then the cache.
+pub(crate) fn enforce(current: Option<&dyn Evict>) {
+    let mut victims: Vec<_> = {
+        let cache = lock();
+        match cache.budget {
+            Some(budget) if cache.total > budget => {}
+            _ => return,
+        }
+        cache
+            .entries
+            .iter()
+            .filter(|entry| current.map(key) != Some(key(entry.flate)))
+            .map(|entry| (entry.used, entry.flate))
+            .collect()
+    };
+    victims.sort_by_key(|&(used, _)| used);
+
+    for (_, flate) in victims {
+        let cache = lock();
+        if cache.budget.is_none_or(|budget| cache.total <= budget) {
+            break;
+        }
+        drop(cache);
+        flate.evict();
+    }
+}
@@ -0,0 +1,142 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::sync::Once;
+
+use crate::Asset;
+
+/// The type of statics declared with [`flate_buf!`](crate::flate_buf).
+///
+/// The contents are decompressed on first access into a buffer of `N` bytes
+/// that is reserved in a separate static, so they are never stored on the heap.
+/// This dereferences to `[u8]` for `[u8]` statics and `str` for `str` statics.
+pub struct FlateBuf<T: ?Sized, const N: usize> {
+    asset: Asset,
+    once: Once,
+    buf: &'static __Buffer<N>,
+    _contents: PhantomData<fn() -> *const T>,
+}
+
+/// The buffer of a [`FlateBuf`].
+///
+/// This is a static of its own, since a static that is entirely uninitialized is placed in
+/// `.bss` and takes no space in the binary, unlike a buffer next to the initialized fields.
+#[doc(hidden)]
+pub struct __Buffer<const N: usize>(UnsafeCell<MaybeUninit<[u8; N]>>);
+
+// SAFETY: the buffer is only accessed through the `FlateBuf` that owns it,
+// which writes it once, inside `once`, before any reference to it is handed out.
+unsafe impl<const N: usize> Sync for __Buffer<N> {}
+
+impl<const N: usize> __Buffer<N> {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+}
+
+impl<T: ?Sized, const N: usize> FlateBuf<T, N> {
+    /// `buf` must not be used by any other `FlateBuf`.
+    #[doc(hidden)]
+    pub const fn new(asset: Asset, buf: &'static __Buffer<N>) -> Self {
+        Self {
+            asset,
+            once: Once::new(),
+            buf,
+            _contents: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, const N: usize> FlateBuf<T, N> {
+    /// Returns the decompressed bytes, decompressing them on first access
+    /// and passing them to `check`.
+    fn bytes(&self, check: fn(&Asset, &[u8])) -> &[u8] {
+        self.once.call_once(|| {
+            let ptr = self.buf.0.get().cast::<u8>();
+            // SAFETY: nothing else accesses the buffer until `once` completes.
+            // The buffer is zeroed first, because the decoders require an initialized slice;
+            // zeroing in place avoids creating an array of `N` bytes on the stack.
+            let buf = unsafe {
+                ptr.write_bytes(0, N);
+                std::slice::from_raw_parts_mut(ptr, N)
+            };
+            self.asset.decode_into(buf);
+            check(&self.asset, buf);
+        });
+
+        // SAFETY: the buffer was initialized in `once` and is never written again.
+        unsafe { std::slice::from_raw_parts(self.buf.0.get().cast::<u8>(), N) }
+    }
+}
+
+impl<const N: usize> Deref for FlateBuf<[u8], N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.bytes(|_, _| {})
+    }
+}
+
+impl<const N: usize> Deref for FlateBuf<str, N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        // SAFETY: the contents were checked to be valid UTF-8 when they were decompressed.
+        unsafe { std::str::from_utf8_unchecked(self.bytes(check_utf8)) }
+    }
+}
+
+impl<T: ?Sized, const N: usize> AsRef<[u8]> for FlateBuf<T, N>
+where
+    Self: Deref<Target = T>,
+    T: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        (**self).as_ref()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FlateBuf<str, N> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+/// Prints the length of the contents instead of the contents themselves.
+/// This does not trigger decompression.
+impl<T: ?Sized, const N: usize> fmt::Debug for FlateBuf<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlateBuf")
+            .field("initialized", &self.once.is_completed())
+            .field("len", &N)
+            .finish()
+    }
+}
+
+fn check_utf8(asset: &Asset, bytes: &[u8]) {
+    // We should have checked for utf8 correctness in encode_utf8_file!
+    if std::str::from_utf8(bytes).is_err() {
+        panic!(
+            "{:?} has malformed UTF-8 despite checked at compile time",
+            asset.name
+        );
+    }
+}
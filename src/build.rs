@@ -0,0 +1,62 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compression for build scripts.
+//!
+//! Build scripts that generate artifacts can compress them with [`compress`]
+//! and embed the output with `include_bytes!`,
+//! then decompress it at runtime with [`decode`](crate::decode).
+//! The output of [`compress`] is exactly what `flate!` embeds for a file,
+//! so it is always accepted by [`decode`](crate::decode) of the same major version.
+//!
+//! ```ignore
+//! // build.rs
+//! let compressed = include_flate::build::compress(&generated, CompressionMethod::Zstd)?;
+//! std::fs::write(out_dir.join("generated.bin.zst"), compressed)?;
+//!
+//! // src/lib.rs
+//! static GENERATED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/generated.bin.zst"));
+//! let data = include_flate::decode("generated", GENERATED);
+//! ```
+//!
+//! With the `zstd` feature, [`train_dictionary_from_dir`] trains a dictionary
+//! from a directory of sample files for `flate!(... with zstd dict "file")`.
+//! The dictionary is usually trained once and committed along with the assets,
+//! since `flate!` reads it relative to the crate root like any other asset:
+//!
+//! ```ignore
+//! let dict = include_flate::build::train_dictionary_from_dir("assets/locales", 16 * 1024)?;
+//! std::fs::write("assets/locales.dict", dict)?;
+//! ```
+
+#[cfg(feature = "zstd")]
+pub use include_flate_compress::{train_dictionary, train_dictionary_from_dir};
+pub use include_flate_compress::{
+    CompressionExtras, CompressionLevel, CompressionMethod, CompressionOptions,
+    FlateCompressionError, Header, HEADER_SIZE,
+};
+
+/// Compresses `data` with the given method or options,
+/// in the same format as the data embedded by `flate!`,
+/// including the [`Header`] that records the method and any zstd window
+/// larger than the decoders accept by default.
+pub fn compress(
+    data: &[u8],
+    options: impl Into<CompressionOptions>,
+) -> Result<Vec<u8>, FlateCompressionError> {
+    let options = options.into();
+    let compressed = include_flate_compress::compress_bytes(data, options)?;
+    Ok(Header::with_options(&options, data).prepend(&compressed))
+}
@@ -0,0 +1,120 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use once_cell::sync::OnceCell;
+
+use crate::Asset;
+
+/// The type of statics declared with [`flate_chunks!`](crate::flate_chunks).
+///
+/// The file is split into chunks of a fixed size that are compressed independently.
+/// Each chunk is decompressed on first access and kept for the rest of the program,
+/// so memory usage is proportional to the chunks that are actually used.
+pub struct FlateChunks {
+    asset: Asset,
+    chunk_size: usize,
+    chunks: OnceCell<Chunks>,
+}
+
+struct Chunks {
+    /// The length of the contents.
+    len: usize,
+    /// The contents of each chunk, if decompressed.
+    cells: Box<[OnceCell<Box<[u8]>>]>,
+}
+
+impl FlateChunks {
+    #[doc(hidden)]
+    pub const fn new(asset: Asset, chunk_size: usize) -> Self {
+        Self {
+            asset,
+            chunk_size,
+            chunks: OnceCell::new(),
+        }
+    }
+
+    /// Returns the number of chunks.
+    pub fn len(&self) -> usize {
+        self.chunks().cells.len()
+    }
+
+    /// Tells whether the file is empty, i.e. there are no chunks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the size of each chunk. The last chunk may be shorter.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns chunk `i`, decompressing it on first access.
+    ///
+    /// Only this chunk is decompressed; the other chunks are not affected.
+    ///
+    /// # Panics
+    /// If `i` is not less than [`len`](Self::len).
+    pub fn get_chunk(&self, i: usize) -> &[u8] {
+        let chunks = self.chunks();
+        let Some(chunk) = chunks.cells.get(i) else {
+            panic!(
+                "chunk {} is out of bounds for {:?} with {} chunks",
+                i,
+                self.asset.name,
+                chunks.cells.len()
+            );
+        };
+
+        chunk.get_or_init(|| {
+            let start = i * self.chunk_size;
+            let range = start..(start + self.chunk_size).min(chunks.len);
+            match self.asset.read_range(range.clone()) {
+                Some(data) => data.into_boxed_slice(),
+                None => self.asset.load_bytes()[range].into(),
+            }
+        })
+    }
+
+    fn chunks(&self) -> &Chunks {
+        self.chunks.get_or_init(|| {
+            let len = self.asset.contents_len();
+            let count = len.div_ceil(self.chunk_size);
+            Chunks {
+                len,
+                cells: (0..count).map(|_| OnceCell::new()).collect(),
+            }
+        })
+    }
+}
+
+/// Prints the number of chunks that have been decompressed instead of the contents.
+/// This does not trigger decompression.
+impl fmt::Debug for FlateChunks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let loaded = self.chunks.get().map_or(0, |chunks| {
+            chunks
+                .cells
+                .iter()
+                .filter(|chunk| chunk.get().is_some())
+                .count()
+        });
+        f.debug_struct("FlateChunks")
+            .field("chunk_size", &self.chunk_size)
+            .field("loaded", &loaded)
+            .finish()
+    }
+}
@@ -0,0 +1,51 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Flate;
+
+/// Derives [`FlateAssets`](trait@FlateAssets) for a struct whose fields are assets.
+///
+/// ```ignore
+/// #[derive(FlateAssets)]
+/// struct Ui {
+///     #[flate("assets/index.html")]
+///     index: FlateStr,
+///     #[flate("assets/app.js" with zstd)]
+///     app: FlateStr,
+///     #[flate("assets/logo.png")]
+///     logo: FlateBytes,
+/// }
+///
+/// let ui = Ui::assets();
+/// serve(&ui.index, &ui.app);
+/// ```
+pub use include_flate_codegen::FlateAssets;
+
+/// A `str` asset in a struct deriving [`FlateAssets`](trait@FlateAssets).
+pub type FlateStr = &'static Flate<String>;
+
+/// A `[u8]` asset in a struct deriving [`FlateAssets`](trait@FlateAssets).
+pub type FlateBytes = &'static Flate<Vec<u8>>;
+
+/// A struct whose fields are assets, implemented with `#[derive(FlateAssets)]`.
+///
+/// Each field refers to its own hidden [`flate!`](crate::flate) static,
+/// so values of the struct are cheap to create and copy around,
+/// and the contents of each field are decompressed once on first access
+/// no matter how many values are created.
+pub trait FlateAssets: Sized {
+    /// Returns the assets without decompressing any of them.
+    fn assets() -> Self;
+}
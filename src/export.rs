@@ -0,0 +1,98 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// Metadata and accessor of an asset exported by [`flate_module!`](crate::flate_module).
+pub struct AssetEntry {
+    ident: &'static str,
+    name: &'static str,
+    accessor: Accessor,
+}
+
+enum Accessor {
+    Bytes(fn() -> &'static [u8]),
+    Str(fn() -> &'static str),
+}
+
+impl AssetEntry {
+    #[doc(hidden)]
+    pub const fn bytes_entry(
+        ident: &'static str,
+        name: &'static str,
+        accessor: fn() -> &'static [u8],
+    ) -> Self {
+        Self {
+            ident,
+            name,
+            accessor: Accessor::Bytes(accessor),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn str_entry(
+        ident: &'static str,
+        name: &'static str,
+        accessor: fn() -> &'static str,
+    ) -> Self {
+        Self {
+            ident,
+            name,
+            accessor: Accessor::Str(accessor),
+        }
+    }
+
+    /// The identifier of the static.
+    pub fn ident(&self) -> &'static str {
+        self.ident
+    }
+
+    /// The name of the asset, i.e. its path unless the `strip-paths` feature is enabled.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Whether the asset was declared as `str`.
+    pub fn is_str(&self) -> bool {
+        matches!(self.accessor, Accessor::Str(_))
+    }
+
+    /// Returns the decompressed contents, decompressing them on first access.
+    pub fn bytes(&self) -> &'static [u8] {
+        match self.accessor {
+            Accessor::Bytes(accessor) => accessor(),
+            Accessor::Str(accessor) => accessor().as_bytes(),
+        }
+    }
+
+    /// Returns the decompressed contents if the asset was declared as `str`,
+    /// decompressing them on first access.
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self.accessor {
+            Accessor::Bytes(_) => None,
+            Accessor::Str(accessor) => Some(accessor()),
+        }
+    }
+}
+
+impl fmt::Debug for AssetEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AssetEntry")
+            .field("ident", &self.ident)
+            .field("name", &self.name)
+            .field("is_str", &self.is_str())
+            .finish()
+    }
+}
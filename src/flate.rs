@@ -0,0 +1,767 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::future::Future;
+use std::io::{self, BufRead, Write};
+use std::ops::{Bound, Deref, Index, Range, RangeBounds};
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "hot-reload")]
+use std::time::SystemTime;
+
+use include_flate_compress::CompressionMethod;
+use once_cell::sync::OnceCell;
+
+#[cfg(feature = "cache-budget")]
+use crate::budget;
+use crate::future::Load;
+#[cfg(all(unix, feature = "reclaimable"))]
+use crate::reclaim::Reclaimable;
+use crate::{Asset, FlateGuard, Seekable};
+
+/// The type of statics declared with [`flate!`](crate::flate).
+///
+/// This dereferences to `Vec<u8>` for `[u8]` statics and `String` for `str` statics.
+/// The contents are decompressed on first access.
+///
+/// Besides `Deref`, this implements `AsRef`, `Borrow` and `Index` by forwarding to the contents,
+/// so statics can be passed to generic code directly.
+/// If the `serde` feature is enabled, it also serializes as its contents.
+pub struct Flate<T: 'static> {
+    pub(crate) asset: Asset,
+    load: fn(&Asset) -> T,
+    cell: OnceCell<T>,
+    /// The contents returned by [`shared`](Self::shared) and [`load`](Self::load).
+    shared: Mutex<Shared<T>>,
+    /// The modification time of the file that the contents were last read from,
+    /// along with the contents.
+    #[cfg(feature = "hot-reload")]
+    reloaded: Mutex<Option<(SystemTime, &'static T)>>,
+    #[cfg(all(unix, feature = "reclaimable"))]
+    reclaimable: Reclaimable,
+    /// The counter of dereferences, registered on the first dereference
+    /// so that its labelled key is not built again on every access.
+    #[cfg(feature = "metrics")]
+    accesses: OnceCell<metrics::Counter>,
+}
+
+impl<T> Flate<T> {
+    #[doc(hidden)]
+    pub const fn new(asset: Asset, load: fn(&Asset) -> T) -> Self {
+        Self {
+            asset,
+            load,
+            cell: OnceCell::new(),
+            shared: Mutex::new(Shared {
+                value: None,
+                guards: 0,
+                releases: 0,
+                grace: Duration::ZERO,
+            }),
+            #[cfg(feature = "hot-reload")]
+            reloaded: Mutex::new(None),
+            #[cfg(all(unix, feature = "reclaimable"))]
+            reclaimable: Reclaimable::new(),
+            #[cfg(feature = "metrics")]
+            accesses: OnceCell::new(),
+        }
+    }
+}
+
+impl<T: Send + Sync> Flate<T> {
+    /// Tells whether the contents have already been decompressed,
+    /// i.e. whether dereferencing the static would not decompress them.
+    pub fn is_initialized(&self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Returns the contents if they have already been decompressed.
+    pub(crate) fn get(&self) -> Option<&T> {
+        #[cfg(feature = "test-override")]
+        if let Some(value) = test_override::get(self) {
+            return Some(value);
+        }
+
+        #[cfg(feature = "hot-reload")]
+        if let Some((_, value)) = *self.reloaded.lock().unwrap_or_else(|err| err.into_inner()) {
+            return Some(value);
+        }
+
+        self.cell.get()
+    }
+}
+
+impl<T: Send + Sync> Deref for Flate<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        #[cfg(feature = "metrics")]
+        self.accesses
+            .get_or_init(
+                || metrics::counter!("include_flate_accesses_total", "asset" => self.asset.name),
+            )
+            .increment(1);
+
+        #[cfg(feature = "test-override")]
+        if let Some(value) = test_override::get(self) {
+            return value;
+        }
+
+        #[cfg(feature = "hot-reload")]
+        if let Some(value) = self.reload() {
+            return value;
+        }
+
+        match self.cell.get() {
+            Some(value) => value,
+            None => self.init(),
+        }
+    }
+}
+
+#[cfg(feature = "hot-reload")]
+impl<T: Send + Sync> Flate<T> {
+    /// Returns the contents of the file that the static is read from at runtime, if any,
+    /// reading it again if it was modified since the last access.
+    ///
+    /// The previous contents are leaked, since they may still be borrowed.
+    fn reload(&self) -> Option<&T> {
+        let path = self.asset.file()?;
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+
+        let mut reloaded = self.reloaded.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some((time, value)) = *reloaded {
+            if time == modified {
+                return Some(value);
+            }
+        }
+
+        let value: &'static T = Box::leak(Box::new((self.load)(&self.asset)));
+        *reloaded = Some((modified, value));
+        Some(value)
+    }
+}
+
+impl<T: Send + Sync> Flate<T> {
+    /// Returns the contents without blocking the async executor,
+    /// decompressing them on another thread on first access.
+    ///
+    /// The returned future resolves immediately if the contents were already decompressed.
+    /// Otherwise, a thread is spawned to decompress them, so a large asset does not stall
+    /// the executor thread that happens to touch it first.
+    /// This does not depend on any particular executor.
+    pub fn get_async(&'static self) -> impl Future<Output = &'static T> + Send {
+        Load::new(self)
+    }
+
+    /// Decompresses the contents now unless they already were,
+    /// e.g. to move the cost of the first access to startup instead of a hot path.
+    pub fn preload(&self) {
+        if self.cell.get().is_none() {
+            self.init();
+        }
+    }
+
+    /// Decompresses the contents from the constructor of an `#[eager]` static.
+    ///
+    /// Panics cannot unwind out of the constructor,
+    /// so a failure is reported along with the asset and the program is aborted.
+    #[doc(hidden)]
+    pub fn __preload_eager(&self) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.preload())) {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(message) => message,
+                None => payload
+                    .downcast_ref::<String>()
+                    .map_or("unknown error", String::as_str),
+            };
+            eprintln!(
+                "include-flate: cannot load {} ({:?}) before main: {}",
+                self.asset.ident.unwrap_or("<inflate!>"),
+                self.asset.name,
+                message
+            );
+            process::abort();
+        }
+    }
+}
+
+impl<T: Send + Sync> Flate<T> {
+    /// Decompresses the contents on first access.
+    ///
+    /// This is kept out of line so that `deref`, which is inlined at every access site,
+    /// only contains the check for the initialized value.
+    #[cold]
+    #[inline(never)]
+    fn init(&self) -> &T {
+        self.cell.get_or_init(|| (self.load)(&self.asset))
+    }
+}
+
+impl<T: Contents> Flate<T> {
+    /// Returns the contents in an `Arc`, decompressing them if they are not cached.
+    ///
+    /// Unlike the contents borrowed by dereferencing the static,
+    /// which are kept for the rest of the program,
+    /// the contents cached here are dropped by [`purge_shared`](Self::purge_shared)
+    /// and decompressed again on the next call.
+    /// The two are cached separately, so this decompresses the data again
+    /// even if the static was already dereferenced.
+    pub fn shared(&'static self) -> Arc<T> {
+        let value = self.lock_shared().get_or_load(self);
+        #[cfg(feature = "cache-budget")]
+        budget::enforce(Some(self));
+        value
+    }
+
+    /// Returns a guard that dereferences to the contents,
+    /// which are decompressed if they are not cached by another guard or by [`shared`](Self::shared).
+    ///
+    /// The contents are shared by all guards, and are purged
+    /// when the last guard is dropped, or after the [grace period](Self::set_grace_period)
+    /// if no guard was created again in the meantime.
+    /// This gives scoped memory usage for assets that are rarely used,
+    /// unlike dereferencing the static, which keeps the contents for the rest of the program.
+    pub fn load(&'static self) -> FlateGuard<T> {
+        let value = {
+            let mut shared = self.lock_shared();
+            shared.guards += 1;
+            shared.get_or_load(self)
+        };
+        #[cfg(feature = "cache-budget")]
+        budget::enforce(Some(self));
+        FlateGuard::new(self, value)
+    }
+}
+
+impl<T: Send + Sync> Flate<T> {
+    /// Drops the contents cached by [`shared`](Self::shared), and returns whether there were any.
+    ///
+    /// The memory is freed once the `Arc`s previously returned by `shared` are dropped as well.
+    /// Only the contents cached by `shared` and [`load`](Self::load) are purged;
+    /// the contents borrowed by dereferencing the static are never freed,
+    /// since references to them may be held for the rest of the program.
+    /// Assets that should be freed after use must therefore only be accessed through
+    /// `shared` or `load`.
+    pub fn purge_shared(&'static self) -> bool {
+        self.lock_shared().take(self).is_some()
+    }
+
+    /// Sets how long the contents are kept after the last [`FlateGuard`] is dropped.
+    ///
+    /// This defaults to zero, i.e. the contents are dropped along with the last guard.
+    pub fn set_grace_period(&self, grace: Duration) {
+        self.lock_shared().grace = grace;
+    }
+
+    fn lock_shared(&self) -> MutexGuard<'_, Shared<T>> {
+        self.shared.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Called when a guard is dropped.
+    pub(crate) fn release(&'static self) {
+        let mut shared = self.lock_shared();
+        shared.guards -= 1;
+        if shared.guards > 0 {
+            return;
+        }
+
+        if shared.grace.is_zero() {
+            shared.take(self);
+            return;
+        }
+
+        shared.releases += 1;
+        let (release, grace) = (shared.releases, shared.grace);
+        drop(shared);
+        thread::spawn(move || {
+            thread::sleep(grace);
+            let mut shared = self.lock_shared();
+            if shared.guards == 0 && shared.releases == release {
+                shared.take(self);
+            }
+        });
+    }
+}
+
+#[cfg(all(unix, feature = "reclaimable"))]
+impl<T> Flate<T> {
+    /// Calls `f` with the contents, which are kept in memory that the OS may reclaim
+    /// under memory pressure while no call is running.
+    ///
+    /// The contents are decompressed into an anonymous mapping on the first call,
+    /// which is marked with `MADV_FREE` after each call,
+    /// or `MADV_DONTNEED` where `MADV_FREE` is not supported.
+    /// If the OS reclaimed any page of the mapping, the next call decompresses the contents again.
+    /// This is cached separately from the contents borrowed by dereferencing the static.
+    pub fn with_reclaimable<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        self.reclaimable.with(&self.asset, f)
+    }
+}
+
+#[cfg(feature = "cache-budget")]
+impl<T: Send + Sync> budget::Evict for Flate<T> {
+    fn evict(&'static self) -> bool {
+        let mut shared = self.lock_shared();
+        shared.guards == 0 && shared.take(self).is_some()
+    }
+}
+
+/// The contents cached by [`Flate::shared`] and [`Flate::load`].
+struct Shared<T> {
+    value: Option<Arc<T>>,
+    /// The number of guards that are alive.
+    guards: usize,
+    /// The number of times that the last guard was dropped,
+    /// so that a delayed purge is skipped if the contents were used again in the meantime.
+    releases: u64,
+    grace: Duration,
+}
+
+impl<T: Contents> Shared<T> {
+    fn get_or_load(&mut self, flate: &'static Flate<T>) -> Arc<T> {
+        if let Some(value) = &self.value {
+            #[cfg(feature = "cache-budget")]
+            budget::touch(flate);
+            return Arc::clone(value);
+        }
+
+        let value = Arc::new((flate.load)(&flate.asset));
+        #[cfg(feature = "cache-budget")]
+        budget::insert(flate, value.as_bytes().len());
+        Arc::clone(self.value.insert(value))
+    }
+}
+
+impl<T: Send + Sync> Shared<T> {
+    fn take(&mut self, flate: &'static Flate<T>) -> Option<Arc<T>> {
+        #[cfg(feature = "cache-budget")]
+        budget::remove(flate);
+        #[cfg(not(feature = "cache-budget"))]
+        let _ = flate;
+        self.value.take()
+    }
+}
+
+impl<T: Deref + Send + Sync> Flate<T> {
+    /// Returns a `'static` reference to the contents, e.g. a `&'static str` for `str` statics,
+    /// decompressing them on first access.
+    ///
+    /// This is for APIs that require `'static` data and do not accept the static itself.
+    /// The contents are decompressed once and kept for the rest of the program,
+    /// so calling this again does not allocate.
+    pub fn leak(&'static self) -> &'static T::Target {
+        self
+    }
+}
+
+impl<T: Contents> AsRef<[u8]> for Flate<T> {
+    fn as_ref(&self) -> &[u8] {
+        (**self).as_bytes()
+    }
+}
+
+impl<T: AsRef<str> + Send + Sync> AsRef<str> for Flate<T> {
+    fn as_ref(&self) -> &str {
+        (**self).as_ref()
+    }
+}
+
+impl<T: Borrow<[u8]> + Send + Sync> Borrow<[u8]> for Flate<T> {
+    fn borrow(&self) -> &[u8] {
+        (**self).borrow()
+    }
+}
+
+impl<T: Borrow<str> + Send + Sync> Borrow<str> for Flate<T> {
+    fn borrow(&self) -> &str {
+        (**self).borrow()
+    }
+}
+
+impl<T: Index<I> + Send + Sync, I> Index<I> for Flate<T> {
+    type Output = T::Output;
+
+    fn index(&self, index: I) -> &T::Output {
+        &(**self)[index]
+    }
+}
+
+/// The types that [`Flate`] can store the contents in.
+///
+/// These are `Vec<u8>` and `String`, or the types selected with `in $storage` in
+/// [`flate!`](crate::flate), such as `Box<[u8]>`, `Arc<str>` and `Cow<'static, [u8]>`.
+pub trait Contents: Send + Sync {
+    /// Returns the contents as bytes.
+    fn as_bytes(&self) -> &[u8];
+}
+
+impl Contents for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Contents for String {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+impl Contents for Box<[u8]> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Contents for Box<str> {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+impl Contents for Arc<[u8]> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Contents for Arc<str> {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+impl Contents for Cow<'static, [u8]> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Contents for Cow<'static, str> {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Contents for bytes::Bytes {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Prints the length of the contents instead of the contents themselves.
+/// This does not trigger decompression.
+impl<T: Contents> fmt::Debug for Flate<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let len = self.get().map(|value| value.as_bytes().len());
+        f.debug_struct("Flate")
+            .field("initialized", &len.is_some())
+            .field("len", &len)
+            .finish()
+    }
+}
+
+impl<T> Flate<T> {
+    /// Returns the compressed bytes embedded in the binary.
+    ///
+    /// For statics declared `with gzip` or `with zlib`, this is a complete gzip or zlib stream.
+    /// The header that records the method and the size of the contents is not included.
+    /// This does not trigger decompression.
+    pub fn compressed(&self) -> &'static [u8] {
+        self.asset.data()
+    }
+
+    /// Returns the length of the compressed bytes embedded in the binary,
+    /// i.e. the length of [`compressed`](Self::compressed).
+    pub fn compressed_len(&self) -> usize {
+        self.asset.data().len()
+    }
+
+    /// Returns the length of the contents as recorded when they were compressed,
+    /// without decompressing them.
+    ///
+    /// This may differ from the length of the contents if they are read from a file at runtime.
+    pub fn decompressed_len(&self) -> usize {
+        self.asset.len()
+    }
+
+    /// Returns the algorithm that the embedded bytes were compressed with.
+    pub fn algorithm(&self) -> CompressionMethod {
+        self.asset.method()
+    }
+
+    /// Returns the Ed25519 key that the contents are verified with, if declared with `signed`.
+    ///
+    /// The key is embedded next to the signature,
+    /// so compare it with the expected key to detect a binary that was re-signed with another key.
+    pub fn verifying_key(&self) -> Option<&'static [u8; 32]> {
+        self.asset.signature.map(|(key, _)| key)
+    }
+}
+
+impl<T: Contents> Flate<T> {
+    /// Returns a copy of `range` of the contents.
+    ///
+    /// If the static is declared `with zstd_seekable` or as chunked and has not been initialized,
+    /// only the frames or blocks overlapping with `range` are decompressed,
+    /// and the static remains uninitialized.
+    /// Likewise, only the range is read if the contents are read from a file at runtime.
+    /// Otherwise, this is equivalent to copying the range from the dereferenced contents.
+    ///
+    /// # Panics
+    /// If the range is out of bounds.
+    pub fn read_range(&self, range: Range<usize>) -> Vec<u8> {
+        if self.get().is_none() {
+            if let Some(data) = self.asset.read_range(range.clone()) {
+                return data;
+            }
+        }
+
+        (**self).as_bytes()[range].to_vec()
+    }
+
+    /// Returns a copy of `range` of the contents, which may be open-ended such as `4096..`.
+    ///
+    /// This is [`read_range`](Self::read_range) for any kind of range;
+    /// an open end is resolved without decompressing the contents.
+    /// For statics declared `in Bytes`, this takes precedence over `Bytes::slice`,
+    /// which can still be called as `Bytes::slice(&NAME, range)` for a shared slice.
+    ///
+    /// # Panics
+    /// If the range is out of bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Vec<u8> {
+        let len = || match self.get() {
+            Some(contents) => contents.as_bytes().len(),
+            None => self.asset.contents_len(),
+        };
+        // a bound of `usize::MAX` cannot be converted to `Range`, and is out of bounds anyway
+        let overflow = || {
+            panic!(
+                "range {:?} is out of bounds for {:?} of length {}",
+                (range.start_bound(), range.end_bound()),
+                self.asset.name,
+                len()
+            )
+        };
+
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).unwrap_or_else(overflow),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).unwrap_or_else(overflow),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len(),
+        };
+        self.read_range(start..end)
+    }
+
+    /// Writes the contents into `buf`, replacing what it held, and returns their length.
+    ///
+    /// This reuses the capacity of `buf`, so that callers can pool buffers
+    /// instead of allocating a new one on every access.
+    /// If the static has not been initialized, the contents are decompressed into `buf` directly
+    /// and the static remains uninitialized; otherwise they are copied from the static.
+    pub fn decode_into(&self, buf: &mut Vec<u8>) -> usize {
+        buf.clear();
+        match self.get() {
+            Some(value) => buf.extend_from_slice(value.as_bytes()),
+            None => self.asset.load_into_vec(buf),
+        }
+        buf.len()
+    }
+
+    /// Writes the contents into the start of `buf` and returns their length,
+    /// like [`decode_into`](Self::decode_into).
+    ///
+    /// # Panics
+    /// If `buf` is shorter than the contents.
+    pub fn decode_into_slice(&self, buf: &mut [u8]) -> usize {
+        match self.get() {
+            Some(value) => {
+                let value = value.as_bytes();
+                self.asset.check_capacity(value.len(), buf.len());
+                buf[..value.len()].copy_from_slice(value);
+                value.len()
+            }
+            None => self.asset.load_into_slice(buf),
+        }
+    }
+
+    /// Writes the contents into `writer` as they are decompressed and returns their length,
+    /// e.g. to stream them to a file or a socket without holding them in memory.
+    ///
+    /// Like [`lines_streaming`](Self::lines_streaming), this decompresses the data again
+    /// on every call unless the static was already initialized,
+    /// and does not initialize it.
+    /// Like [`decode_to_writer`](crate::decode_to_writer), at most the size recorded
+    /// in the header is ever written, and the checksum in the header is verified
+    /// as the contents are written, so `writer` may have received part of them
+    /// when an error is returned.
+    /// If a digest or a signature is declared with `checksum` or `signed`,
+    /// the contents are decompressed and verified in memory before any of them are written.
+    ///
+    /// # Errors
+    /// If writing fails or the embedded data are corrupted.
+    pub fn decode_to_writer<W: Write>(&self, writer: &mut W) -> io::Result<u64> {
+        match self.get() {
+            Some(value) => {
+                writer.write_all(value.as_bytes())?;
+                Ok(value.as_bytes().len() as u64)
+            }
+            None => self.asset.write_to(writer),
+        }
+    }
+
+    /// Returns a reader that decompresses the contents as they are read,
+    /// e.g. to feed a large asset to a parser without ever holding it in memory.
+    ///
+    /// If the static was already initialized, the reader reads the initialized contents.
+    /// Otherwise every reader decompresses the data again, and the static is not initialized.
+    /// Checksums are not verified,
+    /// but reading fails as soon as the contents exceed the size recorded in the header.
+    ///
+    /// Reading fails with an error if the embedded data are corrupted.
+    pub fn reader(&'static self) -> impl BufRead + Send {
+        let reader: Box<dyn BufRead + Send> = match self.get() {
+            Some(value) => Box::new(value.as_bytes()),
+            None => self.asset.open(),
+        };
+        reader
+    }
+
+    /// Returns a reader over the contents that can seek,
+    /// and only decompresses the part of the contents that it reads,
+    /// as with [`read_range`](Self::read_range).
+    pub fn seekable(&'static self) -> Seekable<T> {
+        let len = match self.get() {
+            Some(value) => value.as_bytes().len(),
+            None => self.asset.contents_len(),
+        };
+        let block_size = self.asset.chunk_index().map(|index| index.block_size());
+        Seekable::new(self, len, block_size)
+    }
+
+    /// Returns the number of blocks that can be read with [`block`](Self::block).
+    ///
+    /// Statics declared `with deflate_chunked` or `with zstd_chunked` are split into
+    /// blocks of a fixed size; other statics consist of a single block.
+    pub fn block_count(&self) -> usize {
+        self.asset
+            .chunk_index()
+            .map_or(1, |index| index.block_count())
+    }
+
+    /// Returns a copy of block `n` of the contents.
+    ///
+    /// For chunked statics, only the requested block is decompressed
+    /// and the static is not initialized, like [`read_range`](Self::read_range).
+    ///
+    /// # Panics
+    /// If `n` is not less than [`block_count`](Self::block_count).
+    pub fn block(&self, n: usize) -> Vec<u8> {
+        match self.asset.chunk_index() {
+            Some(index) => self.read_range(index.block_range(n)),
+            None => {
+                assert!(
+                    n == 0,
+                    "block {} is out of bounds for {:?}",
+                    n,
+                    self.asset.name
+                );
+                (**self).as_bytes().to_vec()
+            }
+        }
+    }
+}
+
+impl<T: AsRef<str> + Contents> Flate<T> {
+    /// Returns an iterator over the lines of the contents,
+    /// decompressing them incrementally instead of holding the whole string in memory.
+    ///
+    /// Like [`reader`](Self::reader), this decompresses the data again on every call
+    /// unless the static was already initialized, and does not initialize it.
+    /// Lines are split as in [`BufRead::lines`].
+    ///
+    /// # Panics
+    /// The iterator panics if the contents are not valid UTF-8,
+    /// which can only happen if they are read from a file at runtime.
+    pub fn lines_streaming(&'static self) -> impl Iterator<Item = String> {
+        let name = self.asset.name;
+        self.reader()
+            .lines()
+            .map(move |line| line.unwrap_or_else(|err| panic!("Cannot read {:?}: {}", name, err)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Send + Sync> serde::Serialize for Flate<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "test-override")]
+impl<T: Send + Sync> Flate<T> {
+    /// Replaces the contents of this static for the current thread.
+    ///
+    /// This is intended for tests that need to inject fixtures.
+    /// Since the test harness runs each test on its own thread,
+    /// the replacement is not visible to other tests or to threads spawned by the test.
+    /// Calling this again replaces the previous value; the previous value is leaked.
+    pub fn set_for_test(&'static self, value: impl Into<T>) {
+        test_override::set(self, value.into());
+    }
+}
+
+#[cfg(feature = "test-override")]
+mod test_override {
+    use std::any::Any;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use super::Flate;
+
+    thread_local! {
+        static OVERRIDES: RefCell<HashMap<usize, &'static (dyn Any + Send + Sync)>> =
+            RefCell::new(HashMap::new());
+    }
+
+    fn key<T>(flate: &Flate<T>) -> usize {
+        flate as *const Flate<T> as usize
+    }
+
+    pub(super) fn get<T: Send + Sync>(flate: &Flate<T>) -> Option<&'static T> {
+        OVERRIDES.with(|overrides| {
+            let value = *overrides.borrow().get(&key(flate))?;
+            value.downcast_ref()
+        })
+    }
+
+    pub(super) fn set<T: Send + Sync>(flate: &Flate<T>, value: T) {
+        let value: &'static T = Box::leak(Box::new(value));
+        OVERRIDES.with(|overrides| overrides.borrow_mut().insert(key(flate), value));
+    }
+}
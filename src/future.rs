@@ -0,0 +1,76 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::Flate;
+
+/// A future that decompresses the contents of a static on another thread,
+/// returned by [`Flate::get_async`].
+pub(crate) struct Load<T: 'static> {
+    flate: &'static Flate<T>,
+    state: Option<Arc<Mutex<State>>>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Whether the thread has returned, even if it panicked.
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl<T: Send + Sync> Load<T> {
+    pub(crate) fn new(flate: &'static Flate<T>) -> Self {
+        Self { flate, state: None }
+    }
+}
+
+impl<T: Send + Sync> Future for Load<T> {
+    type Output = &'static T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<&'static T> {
+        let flate = self.flate;
+        if let Some(value) = flate.get() {
+            return Poll::Ready(value);
+        }
+
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(Mutex::new(State::default()));
+            let shared = Arc::clone(&state);
+            thread::spawn(move || {
+                // the panic is raised again by the future when it dereferences the static
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| flate.preload()));
+                let mut state = shared.lock().unwrap_or_else(|err| err.into_inner());
+                state.done = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+
+        let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+        if state.done {
+            return Poll::Ready(&**flate);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
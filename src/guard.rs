@@ -0,0 +1,57 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::{Contents, Flate};
+
+/// A reference to the contents of a [`Flate`] static, returned by [`Flate::load`].
+///
+/// The contents are dropped when the last guard of the static is dropped,
+/// or after the grace period set with [`Flate::set_grace_period`].
+pub struct FlateGuard<T: Send + Sync + 'static> {
+    flate: &'static Flate<T>,
+    value: Arc<T>,
+}
+
+impl<T: Send + Sync> FlateGuard<T> {
+    pub(crate) fn new(flate: &'static Flate<T>, value: Arc<T>) -> Self {
+        Self { flate, value }
+    }
+}
+
+impl<T: Send + Sync> Deref for FlateGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Send + Sync> Drop for FlateGuard<T> {
+    fn drop(&mut self) {
+        self.flate.release();
+    }
+}
+
+impl<T: Contents> fmt::Debug for FlateGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlateGuard")
+            .field("len", &self.value.as_bytes().len())
+            .finish()
+    }
+}
@@ -0,0 +1,40 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// A callback registered with [`set_decode_hook`], which receives the name of the asset,
+/// the compressed size, the decompressed size and the time spent decompressing.
+pub type DecodeHook = fn(name: &str, compressed: usize, decompressed: usize, duration: Duration);
+
+static HOOK: RwLock<Option<DecodeHook>> = RwLock::new(None);
+
+/// Registers a callback that is invoked whenever [`decode`](crate::decode)
+/// or the first access of a static decompresses embedded data,
+/// e.g. to export decompression statistics, or removes it with `None`.
+///
+/// Contents read from a file at runtime are not decompressed, so they are not reported.
+/// The callback replaces the previous one, and may be called from any thread.
+pub fn set_decode_hook(hook: Option<DecodeHook>) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = hook;
+}
+
+pub(crate) fn call(name: &str, compressed: usize, decompressed: usize, duration: Duration) {
+    let hook = *HOOK.read().unwrap_or_else(|err| err.into_inner());
+    if let Some(hook) = hook {
+        hook(name, compressed, decompressed, duration);
+    }
+}
@@ -26,9 +26,15 @@
 //! which might be undesirable if the data are too large.
 //! An actual installer is still required if the binary involves too many resources that do not need to be kept in RAM all time.
 
+use std::io::{self, Read, Write};
+
 /// The low-level macros used by this crate.
+///
+/// These are ordinary function-like procedural macros that expand to byte string literals,
+/// so they can be used in expression position on stable Rust,
+/// e.g. `const DATA: &[u8] = include_flate::codegen::deflate_file!("assets/data.bin");`.
 pub use include_flate_codegen as codegen;
-use include_flate_compress::apply_decompression;
+use include_flate_compress::Header;
 
 #[doc(hidden)]
 pub use include_flate_compress::CompressionMethod;
@@ -36,6 +42,60 @@ pub use include_flate_compress::CompressionMethod;
 #[doc(hidden)]
 pub use once_cell::sync::Lazy;
 
+#[cfg(feature = "bytes")]
+#[doc(hidden)]
+pub use bytes::Bytes;
+
+#[doc(hidden)]
+pub use asset::Asset;
+#[cfg(feature = "runtime-override")]
+pub use asset::{OVERRIDE_DIR_ENV, OVERRIDE_ENV_PREFIX};
+#[cfg(feature = "cache-budget")]
+pub use budget::{cache_budget, cached_bytes, set_cache_budget};
+#[doc(hidden)]
+pub use buf::__Buffer;
+pub use buf::FlateBuf;
+pub use chunks::FlateChunks;
+#[cfg(feature = "ctor")]
+#[doc(hidden)]
+pub use ctor as __ctor;
+pub use derive::{FlateAssets, FlateBytes, FlateStr};
+pub use export::AssetEntry;
+pub use flate::{Contents, Flate};
+pub use guard::FlateGuard;
+pub use hook::{set_decode_hook, DecodeHook};
+pub use include_flate_compress::{FlateCompressionError, HashAlgorithm};
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use inventory as __inventory;
+pub use prefetch::{Prefetch, PrefetchHandle};
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use registry::__Registration;
+#[cfg(feature = "registry")]
+pub use registry::{assets, init_all, AssetInfo};
+pub use seek::Seekable;
+
+mod asset;
+#[cfg(feature = "cache-budget")]
+mod budget;
+mod buf;
+pub mod build;
+mod chunks;
+mod derive;
+mod export;
+mod flate;
+mod future;
+mod guard;
+mod hook;
+mod prefetch;
+#[cfg(all(unix, feature = "reclaimable"))]
+mod reclaim;
+#[cfg(feature = "registry")]
+mod registry;
+mod seek;
+pub mod testing;
+
 /// This macro is like [`include_bytes!`][1] or [`include_str!`][2], but compresses at compile time
 /// and lazily decompresses at runtime.
 ///
@@ -43,30 +103,419 @@ pub use once_cell::sync::Lazy;
 /// The macro can be used like this:
 /// ```ignore
 /// flate!($meta $vis static $name: $type from $file);
+/// flate!($meta $vis const $name: $type from $file);
+/// flate!($meta $vis static $name: $type in $storage from $file);
+/// flate!($meta $vis static $name: $type from $file with $algo validate $format);
+/// flate!($meta $vis static $name: $type from $file with $algo checksum $hash as $digest);
+/// flate!($meta $vis static $name: $type from $file with $algo signed by $key_env);
+/// flate!($meta $vis static $name: $type from $file with $algo if $condition);
+/// flate!($meta $vis static $name: $type from $file with $algo on $target, $algo on $target);
+/// flate!($meta $vis static $name: $type from [$file, $file] joined $separator);
 /// ```
 ///
 /// - `$meta` is zero or more `#[...]` attributes that can be applied on the static parameters of
-/// `lazy_static`. For the actual semantics of the meta attributes, please refer to
-/// [`lazy_static`][3] documentation.
+///   `lazy_static`. For the actual semantics of the meta attributes, please refer to
+///   [`lazy_static`][3] documentation.
 /// - `$vis` is a visibility modifier (e.g. `pub`, `pub(crate)`) or empty.
 /// - `$name` is the name of the static variable..
 /// - `$type` can be either `[u8]` or `str`. However, the actual type created would dereference
-/// into `Vec<u8>` and `String` (although they are `AsRef<[u8]>` and `AsRef<str>`) respectively.
+///   into `Vec<u8>` and `String` (although they are `AsRef<[u8]>` and `AsRef<str>`) respectively.
+/// - `$storage` selects another type to store the contents in instead of `Vec<u8>` or `String`,
+///   which can also be selected explicitly with `in Vec` or `in String`.
+///   `in Box` stores a `Box<[u8]>` or `Box<str>`,
+///   which dereferences to the slice without exposing a growable buffer.
+///   `in Arc` stores an `Arc<[u8]>` or `Arc<str>`, which can be cloned cheaply
+///   to hand the contents to other threads or tasks that require `'static` data.
+///   The contents are copied once into the `Arc` when they are loaded.
+///   `in Bytes` stores a [`bytes::Bytes`][10] for `[u8]` statics,
+///   which can be sliced and shared without copying, e.g. as HTTP bodies in hyper.
+///   This requires the `bytes` feature.
+///   `in Cow` stores a `Cow<'static, [u8]>` or `Cow<'static, str>`,
+///   which borrows the bytes embedded in the binary if the file is stored without compression,
+///   e.g. because the `if` condition does not hold, so that nothing is decompressed or copied;
+///   otherwise it owns the decompressed contents.
+///   `in Result` stores a `Result<Vec<u8>, FlateCompressionError>` or
+///   `Result<String, FlateCompressionError>`, so that corrupted data or a file that cannot be read
+///   surface as an error when the static is dereferenced instead of panicking,
+///   for binaries that must not abort.
+///   The error is kept like the contents, so every access returns the same error.
 /// - `$file` is a path relative to the current [`CARGO_MANIFEST_DIR`][4]. Absolute paths are not supported.
-/// Note that **this is distinct from the behaviour of the builtin `include_bytes!`/`include_str!`
-/// macros** &mdash; `includle_bytes!`/`include_str!` paths are relative to the current source file,
-/// while `flate!` paths are relative to `CARGO_MANIFEST_DIR`.
+///   Note that **this is distinct from the behaviour of the builtin `include_bytes!`/`include_str!`
+///   macros** &mdash; `includle_bytes!`/`include_str!` paths are relative to the current source file,
+///   while `flate!` paths are relative to `CARGO_MANIFEST_DIR`.
+///   Both `/` and `\` are accepted as separators, so the same path works on every platform.
+/// - `$file` can also be a list of paths, e.g. `["head.sql", "body.sql"]`,
+///   optionally followed by `joined $separator` where `$separator` is a string literal.
+///   The files are concatenated, with the separator between each pair of consecutive files,
+///   and compressed as a single asset.
 ///
 /// # Returns
-/// The macro expands to a [`lazy_static`][3] call, which lazily inflates the compressed bytes.
+/// The macro expands to a static of type [`Flate`], which lazily inflates the compressed bytes.
+/// [`Flate::leak`] returns a `'static` reference to the contents, e.g. a `&'static str`,
+/// for APIs that require one.
+/// The contents are kept for the rest of the program once the static is dereferenced;
+/// for assets that are only needed occasionally, [`Flate::shared`] returns them in an `Arc`
+/// that can be dropped with [`Flate::purge_shared`] and is decompressed again when needed.
+/// [`Flate::load`] returns a [`FlateGuard`] that drops the contents along with the last guard.
+/// In async code, [`Flate::get_async`] decompresses the contents on another thread
+/// instead of blocking the executor.
+/// [`Prefetch`] decompresses a list of statics on background threads in order of priority.
+/// [`Flate::compressed_len`], [`Flate::decompressed_len`], [`Flate::algorithm`]
+/// and [`Flate::is_initialized`] describe the static without decompressing it.
+///
+/// With `const` instead of `static`, the macro expands to a constant of type `&'static Flate`
+/// that refers to a hidden static.
+/// This allows declaring assets in positions where statics are not allowed, such as `impl` blocks.
+/// The hidden static is shared by all uses of the constant,
+/// so the data are still only decompressed once.
+///
+/// The compressed data are only referenced by the static itself,
+/// so a static that is never used, e.g. an optional asset exported by a library,
+/// is removed from the final binary along with its data.
+///
+/// # Eager loading
+/// If the `ctor` feature is enabled, a static preceded by `#[eager]`,
+/// after its doc comments and before its other attributes, is decompressed before `main` runs,
+/// so that later accesses do not allocate, e.g. in signal handlers.
+/// The constructor is registered through the [`ctor`](https://docs.rs/ctor) crate,
+/// so `#[eager]` is supported on the targets that it supports and is a compile error on others.
+/// The static is then kept in the binary even if it is not used,
+/// and the program aborts before `main`, after reporting the asset that failed,
+/// if its contents cannot be loaded.
+/// ```ignore
+/// flate!(#[eager] pub static MESSAGES: str from "assets/messages.txt");
+/// ```
+///
+/// # C accessors
+/// A static preceded by `#[ffi]`, after its doc comments and before its other attributes,
+/// is also exported to C through two functions named after it,
+/// which decompress the contents on first use like any other access:
+/// ```ignore
+/// flate!(#[ffi] pub static LOGO: [u8] from "assets/logo.png");
+/// ```
+/// ```c
+/// const uint8_t *LOGO_ptr(void);
+/// size_t LOGO_len(void);
+/// ```
+/// The pointer stays valid for the rest of the program.
+/// `str` contents are not NUL-terminated.
+/// The program aborts if the contents cannot be loaded,
+/// since panics cannot unwind into C.
+/// `#[ffi]` cannot be combined with `#[eager]`.
+///
+/// # Link sections
+/// A static with a `#[link_section = $section]` attribute, anywhere among its attributes,
+/// places its compressed data in the linker section `$section`
+/// instead of the read-only data of the crate, e.g. to group assets away from code:
+/// ```ignore
+/// flate!(#[link_section = ".assets"] pub static LOGO: [u8] from "assets/logo.png");
+/// ```
+/// The static itself, which is written when the contents are decompressed, is not moved.
+/// The compressed data are kept in the binary even if the static is never used,
+/// so that the section is not discarded by the linker.
+/// The section name must follow the format of the target, e.g. `__DATA,__assets` on Apple targets.
+/// With `debug-passthrough` in debug builds, the section holds the uncompressed file instead.
+/// `#[link_section]` is not supported on `const` declarations or together with `#[eager]` or `#[ffi]`.
+///
+/// # Registry
+/// If the `registry` feature is enabled, every static registers itself before `main`
+/// through the [`inventory`](https://docs.rs/inventory) crate, so that [`assets`] lists the statics of the whole program at runtime,
+/// e.g. for memory dashboards, and [`init_all`] decompresses all of them up front.
+/// The statics are then kept in the binary even if they are not used.
 ///
 /// # Compile errors
 /// - If the input format is incorrect
 /// - If the referenced file does not exist or is not readable
 /// - If `$type` is `str` but the file is not fully valid UTF-8
+/// - If `validate $format` is specified but the file is not a valid document of that format
+/// - If an `assets.lock` exists and the file is not listed in it or does not match its hash
+///
+/// # Asset lock
+/// If a file named `assets.lock` exists in `CARGO_MANIFEST_DIR`,
+/// every embedded file must match the SHA-256 hash listed for it.
+/// The lockfile uses the output format of `sha256sum`,
+/// so it can be created with `sha256sum assets/* > assets.lock`.
+/// `cargo flate lock --update`, installed with `cargo install cargo-flate`,
+/// replaces the hashes with those of the current files,
+/// and `cargo flate lock $file` adds a file to the lockfile.
+///
+/// # Checksums
+/// The CRC-32 of the file is embedded along with the compressed data.
+/// If the `verify-checksum` feature is enabled, the decompressed contents are checked against it,
+/// so that data corrupted in the binary, or decompressed with the wrong algorithm,
+/// cause a panic that names the asset instead of silently returning wrong contents.
+/// This costs a pass over the contents whenever they are decompressed in full;
+/// [`Flate::read_range`], [`Flate::block`] and [`Flate::lines_streaming`] are not checked.
+///
+/// A stronger digest can be embedded per asset with `checksum sha256` or `checksum xxh3`
+/// after the algorithm, which requires the `sha256` or `xxh3` feature respectively.
+/// The contents are then always checked against the digest when they are decompressed in full,
+/// regardless of the `verify-checksum` feature.
+/// `checksum sha256 as $digest` also declares a constant `$digest: &[u8]`
+/// holding the raw digest, with the same attributes and visibility as the static,
+/// e.g. to record the SHA-256 of shipped resources:
+///
+/// ```ignore
+/// flate!(pub static MANUAL: str from "assets/manual.md" with zstd checksum sha256 as MANUAL_SHA256);
+/// ```
+///
+/// # Signatures
+/// If the `signed` feature is enabled, `signed` after the algorithm and `checksum`
+/// signs the file with Ed25519 at compile time,
+/// and the contents are verified against the signature whenever they are decompressed in full,
+/// so that a binary whose embedded data were patched panics instead of using them.
+/// The key is read from the `INCLUDE_FLATE_SIGNING_KEY` environment variable,
+/// or from another variable with `signed by "VAR"`,
+/// as the 32-byte secret key in hex, e.g. as generated by `openssl rand -hex 32`.
+/// Cargo does not rebuild the crate when the key changes.
+///
+/// ```ignore
+/// flate!(pub static POLICY: str from "assets/policy.toml" with zstd signed by "POLICY_KEY");
+/// ```
+///
+/// The verifying key is embedded next to the signature,
+/// so this alone does not detect data that were re-signed with another key;
+/// compare [`Flate::verifying_key`] with the expected key for that.
+/// Signed statics ignore the overrides of `runtime-override`,
+/// but contents read from disk with `debug-from-disk` are not verified.
+///
+/// # Asset names
+/// Runtime messages (e.g. panics on corrupted data) identify an asset by its `$file` path.
+/// If the `strip-paths` feature is enabled, an opaque id derived from the path is used instead,
+/// so that the source tree layout is not embedded into the compiled artifact.
+///
+/// # Reading from disk in debug builds
+/// If the `debug-from-disk` feature is enabled and the calling crate is compiled with
+/// `debug_assertions` (e.g. the default `dev` profile),
+/// the static reads the original file from `CARGO_MANIFEST_DIR` on first access
+/// instead of decompressing the embedded data.
+/// This allows editing assets without recompiling the binary during development.
+/// Release builds are unaffected and always use the embedded data.
+/// Since debug builds need the absolute path of the file to read it,
+/// they embed the path even if the `strip-paths` feature is enabled;
+/// release builds do not.
+///
+/// # Skipping compression in debug builds
+/// If the `debug-passthrough` feature is enabled and the calling crate is compiled with
+/// `debug_assertions`, the file is embedded as is, like with `include_bytes!`,
+/// and the macro does no compression work at all, which speeds up clean debug builds.
+/// The algorithm, conditions and validation are ignored in such builds,
+/// so [`Flate::compressed`] returns the contents of the file,
+/// and chunked statics consist of a single block.
+/// This also applies to [`inflate!`], [`flate_delta!`] and [`flate_buf!`].
+/// Release builds are unaffected.
+///
+/// # Overriding at runtime
+/// If the `runtime-override` feature is enabled and the environment variable
+/// `INCLUDE_FLATE_OVERRIDE_$name` is set when the static is first accessed,
+/// the file at the path in the variable is read instead of the embedded data.
+/// This allows patching an embedded asset without shipping a new binary.
+///
+/// Likewise, if the environment variable `INCLUDE_FLATE_OVERRIDE_DIR` is set to a directory,
+/// statics whose `$file` also exists under that directory read it from there instead,
+/// e.g. `$dir/assets/logo.png` for `"assets/logo.png"`,
+/// so that designers can tweak assets without recompiling.
+/// Statics whose file is not in the directory still use the embedded data,
+/// and so do statics declared with a list of files, which have no single path.
+/// The paths are embedded in the binary for this even if the `strip-paths` feature is enabled.
+/// Statics declared with `signed` are never overridden.
+///
+/// # Reloading
+/// If the `hot-reload` feature is enabled, statics that are read from a file at runtime,
+/// i.e. with `debug-from-disk` or an override, read the file again
+/// whenever its modification time changes, so that assets can be edited while the program runs.
+/// Each access then checks the modification time of the file,
+/// and each reload leaks the previous contents since they may still be borrowed,
+/// so this is only intended for development.
+/// Statics that use the embedded data are unaffected.
+///
+/// # Memory budget
+/// If the `cache-budget` feature is enabled, [`set_cache_budget`] limits the total size of
+/// the contents cached by [`Flate::shared`] and [`Flate::load`] across all statics.
+/// When the limit is exceeded, the contents used least recently are purged
+/// and decompressed again on demand.
+///
+/// # Reclaimable memory
+/// If the `reclaimable` feature is enabled, on Unix, [`Flate::with_reclaimable`] provides
+/// the contents from memory that the OS may reclaim under memory pressure between calls,
+/// decompressing them again transparently if it did.
+///
+/// # Overriding in tests
+/// If the `test-override` feature is enabled, tests can replace the contents of a static
+/// with [`Flate::set_for_test`].
+///
+/// # Metrics
+/// If the `metrics` feature is enabled, the following are recorded through the [`metrics`][7]
+/// crate, labelled with the asset name as `asset`:
+///
+/// - `include_flate_decompress_seconds`: a histogram of the time spent loading the contents
+/// - `include_flate_decompressed_bytes`: a gauge of the size of the loaded contents
+/// - `include_flate_accesses_total`: a counter of dereferences of the static,
+///   registered with the recorder that is installed at the first dereference
+///
+/// Regardless of features, [`set_decode_hook`] registers a callback that is invoked
+/// with the asset name, sizes and duration whenever the embedded data are decompressed.
+///
+/// # Tracing
+/// If the `tracing` feature is enabled, loading the contents on first access
+/// is wrapped in an `include_flate::load` span from the [`tracing`][8] crate,
+/// with the asset name, compression method, compressed size, decompressed size
+/// and the time spent loading as fields.
+/// An event with the decompressed size and the time spent is emitted in the span once loaded,
+/// so that a large asset stalling its first access shows up in the logs.
+///
+/// # Logging
+/// If the `log` feature is enabled, records are emitted through the [`log`][9] crate
+/// when the contents are loaded, including the identifier of the static and the asset name:
+///
+/// - `debug`: the contents were decompressed, with the compressed and decompressed sizes
+/// - `info`: the contents were read from a file on disk instead
+/// - `error`: the embedded buffer failed to decompress, right before panicking
 ///
 /// # Algorithm
-/// Compression and decompression use the DEFLATE algorithm from [`libflate`][5].
+/// Compression and decompression use the DEFLATE algorithm from [`libflate`][5] by default.
+/// Append `with zstd` or `with deflate` to select the algorithm explicitly.
+/// Enabling the `default-zstd` feature changes the default to zstd for every `flate!`
+/// that does not specify an algorithm.
+///
+/// The defaults of a crate can also be set in an `include-flate.toml` next to its `Cargo.toml`,
+/// which selects the algorithm of the files that do not specify one,
+/// the compression level, and the threshold of the low compression ratio warning,
+/// optionally per glob of files:
+///
+/// ```toml
+/// algorithm = "zstd"
+/// level = "max"
+/// warning-threshold = 5
+///
+/// [[override]]
+/// glob = "assets/images/**"
+/// algorithm = "none"
+/// ```
+///
+/// The `INCLUDE_FLATE_DEFAULT_ALGO` and `INCLUDE_FLATE_THRESHOLD` environment variables
+/// override `algorithm` and `warning-threshold` at build time, e.g.
+/// `INCLUDE_FLATE_DEFAULT_ALGO=xz cargo build --release` for a size-optimized build.
+/// See [`codegen::deflate_file!`] for the details of the format.
+///
+/// `with gzip` and `with zlib` wrap the DEFLATE stream in a gzip or zlib container,
+/// so that the embedded bytes, returned by [`Flate::compressed`],
+/// can be sent to HTTP clients with `Content-Encoding: gzip` or `deflate` without recompression.
+/// Like `with deflate` and `with deflate_chunked`, they require the `deflate` feature,
+/// which is enabled by default.
+///
+/// `with none` embeds the file without compression,
+/// e.g. for files that are already compressed, while keeping the same API as other statics.
+///
+/// `with lz4` uses the LZ4 frame format, which decompresses much faster at the cost of a lower ratio.
+/// This requires the `lz4` feature.
+///
+/// `with xz` uses the XZ format, which usually gives the best ratio but decompresses slowly.
+/// It suits large assets that are read once. This requires the `xz` feature.
+///
+/// `with snappy` uses the Snappy frame format, a middle ground between `lz4` and `deflate`
+/// in both speed and ratio. This requires the `snappy` feature.
+///
+/// `with best` compresses the file with every enabled algorithm at compile time
+/// and embeds the smallest output, along with the algorithm to decompress it with.
+/// This takes longer to compile, especially with the `xz` feature.
+///
+/// `with fastest_decode` likewise compresses the file with every enabled algorithm,
+/// but favors startup latency over size: it embeds the output of the algorithm
+/// that decompresses fastest among those within 10% of the smallest output.
+/// Algorithms are ranked from the fastest to decompress as
+/// none, `lz4`, `snappy`, `zstd`, `deflate` and `xz`,
+/// so the choice only depends on the file and the enabled features, and builds stay reproducible.
+///
+/// `with zstd_seekable` compresses the file in the zstd seekable format,
+/// which allows [`Flate::read_range`] and [`Flate::slice`]
+/// to decompress only the requested part of the contents.
+///
+/// `with deflate_chunked` and `with zstd_chunked` split the file into blocks of 64 KiB
+/// that are compressed independently, which allows [`Flate::block`] and [`Flate::read_range`]
+/// to decompress only the blocks that are needed.
+/// [`Flate::seekable`] returns a reader that implements `Read` and `Seek` on top of this,
+/// e.g. to read small regions of a large database.
+///
+/// The zstd algorithms accept advanced parameters in parentheses,
+/// e.g. `with zstd(long = 27, block_size = 4096)`:
+///
+/// - `long` or `long = $log` enables long-distance matching with a window of 2<sup>`$log`</sup> bytes
+///   (2<sup>27</sup> by default), which helps for large files with repetitions far apart
+/// - `window_log = $log` sets the window to 2<sup>`$log`</sup> bytes without long-distance matching
+/// - `block_size = $bytes` sets the target size of compressed blocks, e.g. `4096` or `4KiB`
+/// - `workers = $n` compresses on `$n` threads, or on the calling thread if `$n` is 0.
+///   This requires the `zstd-multithread` feature, which also makes `with zstd`
+///   use one thread per CPU by default to speed up builds with large files.
+///
+/// Windows larger than 2<sup>27</sup> bytes need as much memory to decompress.
+/// The header of the embedded data records such a window,
+/// so that it is accepted when the asset is decompressed,
+/// while decompressing other data keeps the default limit of zstd.
+/// They are not supported with seekable, chunked or dictionary compression.
+///
+/// `with zstd dict "assets/app.dict"` compresses the file with a zstd dictionary,
+/// which makes small files that share the same structure, such as JSON documents,
+/// compress several times better.
+/// The dictionary is embedded in the binary to decompress the file;
+/// the compiler merges identical copies, so a dictionary used by many statics is usually stored once.
+/// It can be trained from sample files with [`build::train_dictionary_from_dir`].
+///
+/// `with zstd dict shared` instead compresses the file with a dictionary that is trained
+/// from every file under the `assets` directory of the crate at compile time,
+/// or the directory in the `INCLUDE_FLATE_SHARED_DICT_DIR` environment variable.
+/// The dictionary is cached in `OUT_DIR` until the files change,
+/// and all statics declared `with zstd dict shared` share a single copy of it.
+///
+/// `with zstd on "x86_64-*", deflate on "wasm32-*"` selects the algorithm by the target
+/// that the calling crate is compiled for, e.g. to avoid zstd on targets where it is hard to build.
+/// The first algorithm whose pattern matches the target is used.
+/// The last algorithm can omit `on` to be used for all other targets;
+/// otherwise they use the default algorithm.
+/// The first component of a pattern is compared with `target_arch`,
+/// and each of the others with `target_vendor`, `target_os` or `target_env`,
+/// e.g. `"*-linux-*"` or `"*-windows-msvc"`; `*` matches any component.
+/// Components are the values of these `cfg` options rather than those of the target triple,
+/// e.g. `"x86-*"` rather than `"i686-*"`, or `"*-macos"` rather than `"*-darwin"`.
+/// The file is compressed with every algorithm, and the compiler keeps the output for the target.
+/// The runtime still needs the features of the algorithms used on each target,
+/// which can be enabled per target in `Cargo.toml`.
+///
+/// # Validation
+/// `str` statics can be followed by `validate json`, `validate toml` or `validate yaml`
+/// to check that the file is a syntactically valid document of that format at compile time.
+/// Only the syntax is checked; the document is not deserialized into any particular type.
+/// Each format requires the `validate-json`, `validate-toml` or `validate-yaml` feature respectively.
+///
+/// # Conditions
+/// The declaration can end with `if $condition` to only keep the compressed data
+/// if compression pays off, and embed the file as is otherwise, as with `with none`.
+/// The decision is made at compile time and recorded in the embedded data,
+/// so the static decompresses correctly either way.
+/// Unless another `$storage` is selected, the contents are stored in a `Cow` as with `in Cow`,
+/// so a file that is embedded as is costs nothing at runtime, as with `include_bytes!`:
+/// the static borrows it from the binary instead of copying it.
+///
+/// - `saves_at_least($bytes)` holds if compression saves at least `$bytes` bytes,
+///   which filters out small files whose ratio looks good but whose savings are negligible
+/// - `larger_than($bytes)` holds if the file is larger than `$bytes` bytes.
+///   Smaller files are not compressed at all, which saves build time,
+///   and the overhead of decompressing files that barely shrink
+/// - `less_than_original` holds if the compressed data are smaller than the file
+/// - `compression_ratio_more_than($percent%)` holds if compression saves more than
+///   `$percent` percent of the size of the file
+/// - `cfg($predicate)` holds if the `cfg` predicate holds for the crate calling `flate!`,
+///   e.g. `if cfg(not(debug_assertions))` only compresses in release builds.
+///   Both the compressed and the original data are generated, and the compiler keeps one of them
+///
+/// `$bytes` is a number of bytes, optionally followed by a unit, e.g. `4096`, `4KiB` or `1.5 MB`.
+/// `KB`, `MB` and `GB` are powers of 1000, while `KiB`, `MiB` and `GiB` are powers of 1024.
+///
+/// Conditions can be combined with `and`, `or` and `not(...)`,
+/// e.g. `if larger_than(1024) and not(compression_ratio_more_than(90%) or saves_at_least(4KiB))`.
+/// `and` binds tighter than `or`.
+///
+/// Conditions are not supported with `zstd_seekable`, the chunked algorithms or dictionaries.
 ///
 /// # Examples
 /// Below are some basic examples. For actual compiled examples, see the [`tests`][6] directory.
@@ -81,6 +530,11 @@ pub use once_cell::sync::Lazy;
 /// // Visibility modifiers can be added in the front
 /// flate!(pub static VAR_NAME: str from "public-file.txt");
 ///
+/// // Associated constants can be declared in impl blocks
+/// impl Assets {
+///     flate!(pub const VAR_NAME: [u8] from "impl-file.dat");
+/// }
+///
 /// // Meta attributes can also be added
 /// flate!(#[allow(unused)]
 ///        #[doc = "Example const"]
@@ -93,67 +547,1029 @@ pub use once_cell::sync::Lazy;
 ///   [4]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
 ///   [5]: https://docs.rs/libflate/0.1.26/libflate/
 ///   [6]: https://github.com/SOF3/include-flate/tree/master/tests
+///   [7]: https://docs.rs/metrics
+///   [8]: https://docs.rs/tracing
+///   [9]: https://docs.rs/log
+///   [10]: https://docs.rs/bytes
 #[macro_export]
 macro_rules! flate {
+    ($(#[doc = $doc:literal])* #[eager] $($decl:tt)*) => {
+        $crate::__flate_eager!([$(#[doc = $doc])*] $($decl)*);
+    };
+    ($(#[doc = $doc:literal])* #[ffi] $($decl:tt)*) => {
+        $crate::__flate_ffi!([$(#[doc = $doc])*] $($decl)*);
+    };
+    (# $($decl:tt)*) => {
+        $crate::__flate_section!([] [] # $($decl)*);
+    };
+    (@section [$section:literal] $(#[$meta:meta])* $(pub $(($($vis:tt)+))?)? const $($decl:tt)*) => {
+        compile_error!("`#[link_section]` is only supported on statics");
+    };
+    ($(@section [$($section:literal)?])? $(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: [u8] $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::Flate<$crate::__flate_type!([u8] $($storage)? $(if $($cond)+)?)> =
+            $crate::__flate_init!($($(@section $section)?)? [u8] $([$storage])? $name $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash)? $(signed $(by $key_env)?)? $(if $($cond $(($($arg)*))?)+)?);
+        $(#[$meta])*
+        const _: () = $crate::__flate_register!($name);
+    };
+    ($(@section [$($section:literal)?])? $(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: str $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::Flate<$crate::__flate_type!(str $($storage)? $(if $($cond)+)?)> =
+            $crate::__flate_init!($($(@section $section)?)? str $([$storage])? $name $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash)? $(signed $(by $key_env)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?);
+        $(#[$meta])*
+        const _: () = $crate::__flate_register!($name);
+    };
+    ($(@section [])? $(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? const $name:ident: [u8] $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? const $name: &'static $crate::Flate<$crate::__flate_type!([u8] $($storage)? $(if $($cond)+)?)> = {
+            static FLATE: $crate::Flate<$crate::__flate_type!([u8] $($storage)? $(if $($cond)+)?)> =
+                $crate::__flate_init!([u8] $([$storage])? $name $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash)? $(signed $(by $key_env)?)? $(if $($cond $(($($arg)*))?)+)?);
+            const _: () = $crate::__flate_register!(FLATE);
+            &FLATE
+        };
+    };
+    ($(@section [])? $(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? const $name:ident: str $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? const $name: &'static $crate::Flate<$crate::__flate_type!(str $($storage)? $(if $($cond)+)?)> = {
+            static FLATE: $crate::Flate<$crate::__flate_type!(str $($storage)? $(if $($cond)+)?)> =
+                $crate::__flate_init!(str $([$storage])? $name $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash)? $(signed $(by $key_env)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?);
+            const _: () = $crate::__flate_register!(FLATE);
+            &FLATE
+        };
+    };
+}
+
+// Moves a `#[link_section]` attribute out of the attribute list, wherever it is,
+// since a `$meta` fragment cannot be matched against `link_section` afterwards.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_section {
+    ([$($section:literal)?] [$($attr:tt)*] #[link_section = $new:literal] $($decl:tt)*) => {
+        $crate::__flate_section!([$new] [$($attr)*] $($decl)*);
+    };
+    ([$($section:literal)?] [$($attr:tt)*] # $next:tt $($decl:tt)*) => {
+        $crate::__flate_section!([$($section)?] [$($attr)* # $next] $($decl)*);
+    };
+    ([$($section:literal)?] [$($attr:tt)*] $($decl:tt)*) => {
+        $crate::flate!(@section [$($section)?] $($attr)* $($decl)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_eager {
+    ([$($doc:tt)*] $(#[$meta:meta])* $(pub $(($($vis:tt)+))?)? static $name:ident: $($decl:tt)*) => {
+        $crate::flate!($($doc)* $(#[$meta])* $(pub $(($($vis)+))?)? static $name: $($decl)*);
+        $(#[$meta])*
+        const _: () = {
+            $crate::__flate_ctor!({
+                $crate::Flate::__preload_eager(&$name);
+            });
+        };
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_ffi {
+    ([$($doc:tt)*] $(#[$meta:meta])* $(pub $(($($vis:tt)+))?)? static $name:ident: $($decl:tt)*) => {
+        $crate::flate!($($doc)* $(#[$meta])* $(pub $(($($vis)+))?)? static $name: $($decl)*);
+        $(#[$meta])*
+        const _: () = {
+            #[export_name = ::core::concat!(::core::stringify!($name), "_ptr")]
+            extern "C" fn ptr() -> *const u8 {
+                $name.as_ptr()
+            }
+            #[export_name = ::core::concat!(::core::stringify!($name), "_len")]
+            extern "C" fn len() -> usize {
+                $name.len()
+            }
+        };
+    };
+}
+
+#[cfg(feature = "ctor")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_ctor {
+    ($init:block) => {
+        $crate::__ctor::declarative::ctor! {
+            #[ctor(crate_path = $crate::__ctor)]
+            unsafe fn init() $init
+        }
+    };
+}
+
+#[cfg(not(feature = "ctor"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_ctor {
+    ($init:block) => {
+        ::core::compile_error!("`#[eager]` requires the `ctor` feature of include-flate")
+    };
+}
+
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_register {
+    ($name:ident) => {{
+        $crate::__inventory::submit!($crate::__Registration::__new(&$name));
+    }};
+}
+
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_register {
+    ($name:ident) => {
+        ()
+    };
+}
+
+/// Declares a group of [`flate!`] statics that share the same attributes.
+///
+/// This is mostly useful for gating a set of assets behind a common `#[cfg]` predicate.
+/// Since `#[cfg]` is evaluated before the assets are compressed,
+/// neither the statics nor their embedded data are compiled when the predicate is false.
+///
+/// # Parameters
+/// ```ignore
+/// flate_group!($meta {
+///     $meta $vis static $name: $type from $file;
+///     $meta $vis static $name: $type from $file with $algo;
+/// });
+/// ```
+///
+/// Each declaration has the same syntax as a [`flate!`] invocation, terminated by `;`.
+/// The group attributes are applied before the attributes of each declaration.
+///
+/// # Examples
+/// ```ignore
+/// flate_group!(#[cfg(feature = "premium")] {
+///     pub static PREMIUM_DATA: [u8] from "premium/data.bin";
+///     pub static PREMIUM_TEXT: str from "premium/text.txt" with zstd;
+/// });
+/// ```
+#[macro_export]
+macro_rules! flate_group {
+    ($(#[$($meta:tt)*])* { $($decls:tt)* }) => {
+        $crate::__flate_group!([$(#[$($meta)*])*] $($decls)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_group {
+    ([$($group:tt)*]) => {};
+    ([$($group:tt)*] $(#[$($meta:tt)*])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: $ty:tt $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?;
+        $($rest:tt)*) => {
+        $crate::flate!($($group)* $(#[$($meta)*])*
+            $(pub $(($($vis)+))?)? static $name: $ty $(in $storage)? from $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash $(as $digest)?)? $(signed $(by $key_env)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?);
+        $crate::__flate_group!([$($group)*] $($rest)*);
+    };
+    ([$($group:tt)*] $(#[$($meta:tt)*])*
+        $(pub $(($($vis:tt)+))?)? const $name:ident: $ty:tt $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?;
+        $($rest:tt)*) => {
+        $crate::flate!($($group)* $(#[$($meta)*])*
+            $(pub $(($($vis)+))?)? const $name: $ty $(in $storage)? from $path $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash $(as $digest)?)? $(signed $(by $key_env)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?);
+        $crate::__flate_group!([$($group)*] $($rest)*);
+    };
+}
+
+/// Declares a public module of [`flate!`] statics together with a table of their metadata.
+///
+/// This is intended for library crates that ship embedded assets to downstream crates.
+/// Besides the statics themselves, the module contains:
+///
+/// - `ASSETS`, a `&'static [AssetEntry]` listing every asset in declaration order
+/// - `get(ident)`, which looks up an asset by the identifier of its static
+/// - `preload()`, which decompresses every asset of the module with [`Flate::preload`],
+///   so that the first access in a hot path does not pay for it
+///
+/// Downstream crates can re-export the module with `pub use`,
+/// or iterate over the `ASSETS` of several modules to build their own registry.
+///
+/// # Parameters
+/// ```ignore
+/// flate_module! {
+///     $meta $vis mod $module {
+///         $meta static $name: $type from $file;
+///         $meta static $name: $type from $file with $algo;
+///     }
+/// }
+/// ```
+///
+/// Each declaration has the same syntax as a [`flate!`] invocation without visibility,
+/// terminated by `;`. All statics are declared as `pub` in the module.
+///
+/// # Examples
+/// ```ignore
+/// flate_module! {
+///     pub mod assets {
+///         static LOGO: [u8] from "assets/logo.png";
+///         static LICENSE: str from "LICENSE" with zstd;
+///     }
+/// }
+///
+/// for entry in assets::ASSETS {
+///     println!("{}: {} bytes", entry.name(), entry.bytes().len());
+/// }
+/// ```
+#[macro_export]
+macro_rules! flate_module {
+    ($(#[$meta:meta])* $vis:vis mod $module:ident {
+        $($(#[$item_meta:meta])* static $name:ident: $ty:tt $(in $storage:ident)? from $path:tt $(joined $sep:literal)?
+            $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+                $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+            $(if $($cond:ident $(($($arg:tt)*))?)+)?;)*
+    }) => {
+        $(#[$meta])*
+        $vis mod $module {
+            $(
+                $crate::flate!($(#[$item_meta])* pub static $name: $ty $(in $storage)? from $path
+                    $(joined $sep)? $(with $algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(checksum $hash $(as $digest)?)? $(signed $(by $key_env)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?);
+            )*
+
+            /// All assets in this module, in declaration order.
+            pub static ASSETS: &[$crate::AssetEntry] = &[
+                $($crate::__flate_entry!($ty $name $path),)*
+            ];
+
+            /// Looks up an asset in this module by the identifier of its static.
+            pub fn get(ident: &str) -> ::core::option::Option<&'static $crate::AssetEntry> {
+                ASSETS.iter().find(|entry| entry.ident() == ident)
+            }
+
+            /// Decompresses every asset in this module that was not decompressed yet.
+            pub fn preload() {
+                $($crate::Flate::preload(&$name);)*
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_entry {
+    ([u8] $name:ident $path:tt) => {
+        $crate::AssetEntry::bytes_entry(
+            stringify!($name),
+            $crate::codegen::asset_name!($path),
+            || ::core::convert::AsRef::<[u8]>::as_ref(&$name),
+        )
+    };
+    (str $name:ident $path:tt) => {
+        $crate::AssetEntry::str_entry(
+            stringify!($name),
+            $crate::codegen::asset_name!($path),
+            || ::core::convert::AsRef::<str>::as_ref(&$name),
+        )
+    };
+}
+
+// Each static only carries its data and a pointer to one of the shared loaders,
+// e.g. `Asset::load_bytes_into` or `Asset::load_string_into`, so that no loading logic is generated
+// per asset, but only per type that the contents are stored in.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_init {
+    ($(@section $section:literal)? [u8] $([$storage:ident])? $name:ident $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident)? $(signed $(by $key_env:literal)?)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::Flate::new(
+            $crate::__flate_asset!($path $(joined $sep)? $(@section $section)? {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: $crate::codegen::source_path!($path),
+                checksum: $crate::__flate_checksum!($path $(joined $sep)? $($hash)?),
+                signature: $crate::__flate_signature!($path $(joined $sep)? $(signed $(by $key_env)?)?),
+                bytes: $crate::__flate_bytes!($(@section $section)? $crate::codegen::deflate_file!($path $(joined $sep)? $($algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(if $($cond $(($($arg)*))?)+)?)),
+                chunked: $crate::__flate_chunked!($($algo $(on $target)?)?),
+                base: $crate::__flate_dict!($($($dict)?)?),
+            }),
+            $crate::__flate_load!([u8] $($storage)? $(if $($cond)+)?),
+        )
+    };
+    ($(@section $section:literal)? str $([$storage:ident])? $name:ident $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::Flate::new(
+            $crate::__flate_asset!($path $(joined $sep)? $(@section $section)? {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: $crate::codegen::source_path!($path),
+                checksum: $crate::__flate_checksum!($path $(joined $sep)? $($hash)?),
+                signature: $crate::__flate_signature!($path $(joined $sep)? $(signed $(by $key_env)?)?),
+                bytes: $crate::__flate_bytes!($(@section $section)? $crate::codegen::deflate_utf8_file!($path $(joined $sep)? $($algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?)),
+                chunked: $crate::__flate_chunked!($($algo $(on $target)?)?),
+                base: $crate::__flate_dict!($($($dict)?)?),
+            }),
+            $crate::__flate_load!(str $($storage)? $(if $($cond)+)?),
+        )
+    };
+}
+
+/// This macro is like [`include_bytes!`][1], but compresses at compile time
+/// and decompresses every time the expression is evaluated.
+///
+/// Unlike [`flate!`], no static is declared and the decompressed data are not cached;
+/// each evaluation returns a fresh `Vec<u8>` owned by the caller.
+///
+/// # Parameters
+/// ```ignore
+/// inflate!($file)
+/// inflate!($file with $algo)
+/// inflate!($file with $algo checksum $hash)
+/// ```
+///
+/// `$file`, `$algo` and `$hash` have the same meaning as in [`flate!`].
+///
+/// # Examples
+/// ```ignore
+/// let data: Vec<u8> = inflate!("assets/data.bin" with zstd);
+/// ```
+///
+///   [1]: https://doc.rust-lang.org/std/macro.include_bytes.html
+#[macro_export]
+macro_rules! inflate {
+    ($path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident)? $(signed $(by $key_env:literal)?)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {{
+        $crate::__flate_asset!($path $(joined $sep)? {
+            ident: None,
+            name: $crate::codegen::asset_name!($path),
+            source: $crate::codegen::source_path!($path),
+            checksum: $crate::__flate_checksum!($path $(joined $sep)? $($hash)?),
+            signature: $crate::__flate_signature!($path $(joined $sep)? $(signed $(by $key_env)?)?),
+            bytes: $crate::codegen::deflate_file!($path $(joined $sep)? $($algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(if $($cond $(($($arg)*))?)+)?),
+            chunked: $crate::__flate_chunked!($($algo $(on $target)?)?),
+            base: $crate::__flate_dict!($($($dict)?)?),
+        })
+        .load_bytes()
+    }};
+}
+
+/// Declares a [`flate!`] static that is stored as a delta against another `flate!` static.
+///
+/// The file is compressed with zstd, using the contents of the base file as a dictionary.
+/// This is much smaller than compressing the file on its own if the two files are similar,
+/// such as translations of the same document.
+/// The base static is decompressed when this static is first accessed.
+///
+/// # Parameters
+/// ```ignore
+/// flate_delta!($meta $vis static $name: $type from $file against $base = $base_file);
+/// ```
+///
+/// `$meta`, `$vis`, `$name`, `$type` and `$file` have the same meaning as in [`flate!`].
+/// `$base` is the path to the base static, and `$base_file` is the file it is declared with.
+///
+/// This requires the `zstd` feature.
+///
+/// # Examples
+/// ```ignore
+/// flate!(pub static EN: str from "locales/en.json");
+/// flate_delta!(pub static FR: str from "locales/fr.json" against EN = "locales/en.json");
+/// ```
+#[macro_export]
+macro_rules! flate_delta {
+    ($(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: [u8] from $path:literal
+        against $base:path = $base_path:literal) => {
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::Flate<::std::vec::Vec<u8>> = $crate::Flate::new(
+            $crate::__flate_asset!($path {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: $crate::codegen::source_path!($path),
+                checksum: None,
+                signature: None,
+                bytes: $crate::codegen::delta_file!($path $base_path),
+                chunked: false,
+                base: Some(|| ::core::convert::AsRef::<[u8]>::as_ref(&$base)),
+            }),
+            $crate::Asset::load_bytes,
+        );
+    };
     ($(#[$meta:meta])*
-        $(pub $(($($vis:tt)+))?)? static $name:ident: [u8] from $path:literal $(with $algo:ident)?) => {
-        // HACK: workaround to make cargo auto rebuild on modification of source file
-        const _: &'static [u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path));
+        $(pub $(($($vis:tt)+))?)? static $name:ident: str from $path:literal
+        against $base:path = $base_path:literal) => {
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::Flate<::std::string::String> = $crate::Flate::new(
+            $crate::__flate_asset!($path {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: $crate::codegen::source_path!($path),
+                checksum: None,
+                signature: None,
+                bytes: $crate::codegen::delta_utf8_file!($path $base_path),
+                chunked: false,
+                base: Some(|| ::core::convert::AsRef::<[u8]>::as_ref(&$base)),
+            }),
+            $crate::Asset::load_string,
+        );
+    };
+}
 
+/// Declares another name for a [`flate!`] static.
+///
+/// The alias refers to the same static,
+/// so the compressed data are embedded once and decompressed once no matter which name is used.
+/// This is useful for keeping an old public name during a migration.
+///
+/// # Parameters
+/// ```ignore
+/// flate_alias!($meta $vis static $name = $target);
+/// ```
+///
+/// `$target` is the path to the original static.
+/// The alias is a re-export of the original static,
+/// so `$vis` cannot be wider than the visibility of the original static.
+///
+/// # Examples
+/// ```ignore
+/// mod assets {
+///     flate!(pub static NEW_NAME: str from "assets/data.txt");
+/// }
+///
+/// flate_alias!(pub static LEGACY_NAME = assets::NEW_NAME);
+/// ```
+#[macro_export]
+macro_rules! flate_alias {
+    ($(#[$meta:meta])* $vis:vis static $name:ident = $target:path) => {
         $(#[$meta])*
-        $(pub $(($($vis)+))?)? static $name: $crate::Lazy<::std::vec::Vec<u8>> = $crate::Lazy::new(|| {
-            $crate::decode($crate::codegen::deflate_file!($path), None)
-        });
+        $vis use $target as $name;
+    };
+}
+
+/// Declares a static like [`flate!`], but decompresses into a buffer reserved in the static
+/// instead of the heap.
+///
+/// Since the size of the file is known at compile time,
+/// the static refers to an uninitialized static buffer of exactly that size,
+/// which takes no space in the binary.
+/// The buffer is filled on first access, and the static dereferences to `[u8]` or `str`
+/// for the rest of the program.
+/// The contents are never allocated on the heap,
+/// although the decoder may still allocate its working state while decompressing.
+///
+/// Unlike [`flate!`], the contents are always decompressed from the embedded data,
+/// since a file read at runtime need not have the same size.
+///
+/// # Parameters
+/// ```ignore
+/// flate_buf!($meta $vis static $name: $type from $file);
+/// flate_buf!($meta $vis static $name: $type from $file with $algo);
+/// ```
+///
+/// The parameters have the same meaning as in [`flate!`].
+///
+/// # Examples
+/// ```ignore
+/// flate_buf!(pub static FIRMWARE: [u8] from "assets/firmware.bin" with zstd);
+///
+/// let firmware: &'static [u8] = &FIRMWARE;
+/// ```
+#[macro_export]
+macro_rules! flate_buf {
+    ($(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: [u8] from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::FlateBuf<[u8], { $crate::codegen::file_len!($path $(joined $sep)?) }> = {
+            static BUF: $crate::__Buffer<{ $crate::codegen::file_len!($path $(joined $sep)?) }> = $crate::__Buffer::new();
+            $crate::FlateBuf::new($crate::__flate_asset!($path $(joined $sep)? {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: None,
+                checksum: $crate::__flate_checksum!($path $(joined $sep)? $($hash)?),
+                signature: $crate::__flate_signature!($path $(joined $sep)? $(signed $(by $key_env)?)?),
+                bytes: $crate::codegen::deflate_file!($path $(joined $sep)? $($algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(if $($cond $(($($arg)*))?)+)?),
+                chunked: $crate::__flate_chunked!($($algo $(on $target)?)?),
+                base: $crate::__flate_dict!($($($dict)?)?),
+            }), &BUF)
+        };
     };
     ($(#[$meta:meta])*
-        $(pub $(($($vis:tt)+))?)? static $name:ident: str from $path:literal $(with $algo:ident)?) => {
-        // HACK: workaround to make cargo auto rebuild on modification of source file
-        const _: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path));
+        $(pub $(($($vis:tt)+))?)? static $name:ident: str from $path:tt $(joined $sep:literal)?
+        $(with $algo:ident $(($($param:tt)*))? $(dict $dict:tt)?
+            $(on $target:literal $(, $talgo:ident $(($($tparam:tt)*))? $(on $ttarget:literal)?)*)?)? $(checksum $hash:ident $(as $digest:ident)?)? $(signed $(by $key_env:literal)?)? $(validate $format:ident)?
+        $(if $($cond:ident $(($($arg:tt)*))?)+)?) => {
+        $crate::__flate_digest!([$(#[$meta])*] [$(pub $(($($vis)+))?)?] $path $(joined $sep)? $(checksum $hash $(as $digest)?)?);
+        $(#[$meta])*
+        $(pub $(($($vis)+))?)? static $name: $crate::FlateBuf<str, { $crate::codegen::file_len!($path $(joined $sep)?) }> = {
+            static BUF: $crate::__Buffer<{ $crate::codegen::file_len!($path $(joined $sep)?) }> = $crate::__Buffer::new();
+            $crate::FlateBuf::new($crate::__flate_asset!($path $(joined $sep)? {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: None,
+                checksum: $crate::__flate_checksum!($path $(joined $sep)? $($hash)?),
+                signature: $crate::__flate_signature!($path $(joined $sep)? $(signed $(by $key_env)?)?),
+                bytes: $crate::codegen::deflate_utf8_file!($path $(joined $sep)? $($algo $(($($param)*))? $(dict $dict)? $(on $target $(, $talgo $(($($tparam)*))? $(on $ttarget)?)*)?)? $(validate $format)? $(if $($cond $(($($arg)*))?)+)?),
+                chunked: $crate::__flate_chunked!($($algo $(on $target)?)?),
+                base: $crate::__flate_dict!($($($dict)?)?),
+            }), &BUF)
+        };
+    };
+}
 
+/// Declares a static whose file is split into chunks that are compressed independently,
+/// and decompressed one at a time on first access.
+///
+/// This is intended for large files of which only small parts are used, such as tile atlases.
+/// Each chunk is kept once it is decompressed, so memory usage is proportional to
+/// the chunks that are actually used, rather than to the whole file.
+///
+/// # Parameters
+/// ```ignore
+/// flate_chunks!($meta $vis static $name: [u8] from $file with $algo of $size);
+/// ```
+///
+/// `$meta`, `$vis`, `$name` and `$file` have the same meaning as in [`flate!`].
+/// `$algo` is `deflate_chunked` or `zstd_chunked`,
+/// and `$size` is the size of each chunk in bytes, as an integer literal.
+///
+/// # Returns
+/// The macro expands to a static of type [`FlateChunks`].
+///
+/// # Examples
+/// ```ignore
+/// flate_chunks!(pub static ATLAS: [u8] from "assets/atlas.bin" with zstd_chunked of 16384);
+///
+/// let tile = &ATLAS.get_chunk(42)[..256];
+/// ```
+#[macro_export]
+macro_rules! flate_chunks {
+    ($(#[$meta:meta])*
+        $(pub $(($($vis:tt)+))?)? static $name:ident: [u8] from $path:tt $(joined $sep:literal)?
+        with $algo:ident of $size:literal) => {
         $(#[$meta])*
-        $(pub $(($($vis)+))?)? static $name: $crate::Lazy<::std::string::String> = $crate::Lazy::new(|| {
-            let algo = match stringify!($($algo)?){
-                "deflate" => $crate::CompressionMethod::Deflate,
-                "zstd" => $crate::CompressionMethod::Zstd,
-                _ => $crate::CompressionMethod::default(),
-            };
-            $crate::decode_string($crate::codegen::deflate_utf8_file!($path $($algo)?), Some($crate::CompressionMethodTy(algo)))
-        });
+        $(pub $(($($vis)+))?)? static $name: $crate::FlateChunks = $crate::FlateChunks::new(
+            $crate::__flate_asset!($path $(joined $sep)? {
+                ident: Some(stringify!($name)),
+                name: $crate::codegen::asset_name!($path),
+                source: $crate::codegen::source_path!($path),
+                checksum: None,
+                signature: None,
+                bytes: $crate::codegen::deflate_file!($path $(joined $sep)? $algo of $size),
+                chunked: $crate::__flate_chunked!($algo),
+                base: None,
+            }),
+            $size,
+        );
+    };
+}
+
+#[cfg(not(feature = "debug-passthrough"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_asset {
+    ($path:tt $(joined $sep:literal)? $(@section $section:literal)? { $($field:ident: $value:expr,)* }) => {
+        $crate::Asset {
+            path: $crate::__flate_path!($path $(joined $sep)?),
+            $($field: $value,)*
+        }
     };
 }
 
-#[derive(Debug)]
-pub struct CompressionMethodTy(pub CompressionMethod);
+// Items that are configured out are removed before the macros in them are expanded,
+// so the file is not compressed at all in debug builds.
+#[cfg(feature = "debug-passthrough")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_asset {
+    ($path:tt $(joined $sep:literal)? $(@section $section:literal)? {
+        ident: $ident:expr,
+        name: $name:expr,
+        source: $source:expr,
+        checksum: $checksum:expr,
+        signature: $signature:expr,
+        $($field:ident: $value:expr,)*
+    }) => {{
+        #[cfg(debug_assertions)]
+        const ASSET: $crate::Asset = $crate::Asset {
+            ident: $ident,
+            name: $name,
+            source: $source,
+            path: $crate::__flate_path!($path $(joined $sep)?),
+            checksum: $checksum,
+            signature: $signature,
+            bytes: $crate::__flate_bytes!($(@section $section)? $crate::codegen::uncompressed_file!($path $(joined $sep)?)),
+            chunked: false,
+            base: None,
+        };
+        #[cfg(not(debug_assertions))]
+        const ASSET: $crate::Asset = $crate::Asset {
+            ident: $ident,
+            name: $name,
+            source: $source,
+            path: $crate::__flate_path!($path $(joined $sep)?),
+            checksum: $checksum,
+            signature: $signature,
+            $($field: $value,)*
+        };
+        ASSET
+    }};
+}
 
-impl Into<CompressionMethod> for CompressionMethodTy {
-    fn into(self) -> CompressionMethod {
-        self.0
-    }
+// Only a single file can be found in the override directory,
+// and its path is not embedded at all unless it can be overridden.
+#[cfg(feature = "runtime-override")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_path {
+    ($path:literal) => {
+        ::core::option::Option::Some($path)
+    };
+    ($path:tt $(joined $sep:literal)?) => {
+        ::core::option::Option::None
+    };
 }
 
+#[cfg(not(feature = "runtime-override"))]
 #[doc(hidden)]
-#[allow(private_interfaces)]
-pub fn decode(bytes: &[u8], algo: Option<CompressionMethodTy>) -> Vec<u8> {
-    use std::io::Cursor;
+#[macro_export]
+macro_rules! __flate_path {
+    ($path:tt $(joined $sep:literal)?) => {
+        ::core::option::Option::None
+    };
+}
 
-    let algo: CompressionMethod = algo
-        .unwrap_or(CompressionMethodTy(CompressionMethod::Deflate))
-        .into();
-    let mut source = Cursor::new(bytes);
-    let mut ret = Vec::new();
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_bytes {
+    (@section $section:literal $bytes:expr) => {{
+        const BYTES: &[u8] = $bytes;
+        // a literal cannot be placed in a section, so the bytes are copied into a static,
+        // which is kept even if unused so that the linker does not discard the section
+        #[used]
+        #[link_section = $section]
+        static SECTION: [u8; BYTES.len()] = match BYTES.first_chunk() {
+            Some(bytes) => *bytes,
+            None => unreachable!(),
+        };
+        &SECTION
+    }};
+    ($bytes:expr) => {
+        $bytes
+    };
+}
 
-    match apply_decompression(&mut source, &mut ret, algo) {
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_type {
+    ([u8] if $($cond:ident)+) => {
+        $crate::__flate_type!([u8] Cow)
+    };
+    (str if $($cond:ident)+) => {
+        $crate::__flate_type!(str Cow)
+    };
+    ($ty:tt $storage:ident if $($cond:ident)+) => {
+        $crate::__flate_type!($ty $storage)
+    };
+    ([u8] $(Vec)?) => {
+        ::std::vec::Vec<u8>
+    };
+    (str $(String)?) => {
+        ::std::string::String
+    };
+    ([u8] Box) => {
+        ::std::boxed::Box<[u8]>
+    };
+    (str Box) => {
+        ::std::boxed::Box<str>
+    };
+    ([u8] Arc) => {
+        ::std::sync::Arc<[u8]>
+    };
+    (str Arc) => {
+        ::std::sync::Arc<str>
+    };
+    ([u8] Bytes) => {
+        $crate::Bytes
+    };
+    ([u8] Cow) => {
+        ::std::borrow::Cow<'static, [u8]>
+    };
+    (str Cow) => {
+        ::std::borrow::Cow<'static, str>
+    };
+    ([u8] Result) => {
+        ::core::result::Result<::std::vec::Vec<u8>, $crate::FlateCompressionError>
+    };
+    (str Result) => {
+        ::core::result::Result<::std::string::String, $crate::FlateCompressionError>
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_load {
+    ([u8] if $($cond:ident)+) => {
+        $crate::Asset::load_bytes_cow
+    };
+    (str if $($cond:ident)+) => {
+        $crate::Asset::load_string_cow
+    };
+    ($ty:tt $storage:ident if $($cond:ident)+) => {
+        $crate::__flate_load!($ty $storage)
+    };
+    ([u8] Cow) => {
+        $crate::Asset::load_bytes_cow
+    };
+    (str Cow) => {
+        $crate::Asset::load_string_cow
+    };
+    ([u8] Result) => {
+        $crate::Asset::try_load_bytes
+    };
+    (str Result) => {
+        $crate::Asset::try_load_string
+    };
+    ([u8] $($storage:ident)?) => {
+        $crate::Asset::load_bytes_into
+    };
+    (str $($storage:ident)?) => {
+        $crate::Asset::load_string_into
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_chunked {
+    (deflate_chunked) => {
+        true
+    };
+    (zstd_chunked) => {
+        true
+    };
+    ($($algo:ident $(on $target:literal)?)?) => {
+        false
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_dict {
+    () => {
+        None
+    };
+    (shared) => {
+        Some(|| $crate::codegen::shared_dict!())
+    };
+    ($dict:literal) => {
+        Some(|| $crate::codegen::dict_file!($dict))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_checksum {
+    ($path:tt $(joined $sep:literal)?) => {
+        None
+    };
+    ($path:tt $(joined $sep:literal)? $hash:ident) => {
+        Some((
+            $crate::__flate_hash!($hash),
+            $crate::codegen::file_digest!($path $(joined $sep)? $hash),
+        ))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_signature {
+    ($path:tt $(joined $sep:literal)?) => {
+        None
+    };
+    ($path:tt $(joined $sep:literal)? signed $(by $key_env:literal)?) => {
+        Some($crate::codegen::file_signature!($path $(joined $sep)? $($key_env)?))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_hash {
+    (sha256) => {
+        $crate::HashAlgorithm::Sha256
+    };
+    (xxh3) => {
+        $crate::HashAlgorithm::Xxh3
+    };
+}
+
+// Declares the constant named by `checksum $hash as $digest`, if any.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flate_digest {
+    ([$($meta:tt)*] [$($vis:tt)*] $path:tt $(joined $sep:literal)? checksum $hash:ident as $digest:ident) => {
+        $($meta)*
+        $($vis)* const $digest: &[u8] = $crate::codegen::file_digest!($path $(joined $sep)? $hash);
+    };
+    ([$($meta:tt)*] [$($vis:tt)*] $path:tt $(joined $sep:literal)? $(checksum $hash:ident)? $(signed $(by $key_env:literal)?)?) => {};
+}
+
+/// Decompresses `bytes` according to the header they start with.
+///
+/// This accepts the output of [`build::compress`] and of `deflate_file!`,
+/// except for chunked data and data compressed with a dictionary,
+/// so that build scripts can compress generated artifacts to be embedded with `include_bytes!`.
+///
+/// At most the size recorded in the header is decompressed,
+/// so altered data cannot make this allocate more than the original contents.
+///
+/// # Panics
+/// If `bytes` do not start with a valid header, or are not valid compressed data,
+/// or decompress to more than the size recorded in the header.
+/// With the `verify-checksum` feature, also if the decompressed data do not match
+/// the checksum in the header.
+/// `name` identifies the data in the panic message.
+/// Use [`try_decode`] to handle these errors instead.
+pub fn decode(name: &str, bytes: &[u8]) -> Vec<u8> {
+    let Some((header, _)) = Header::parse(bytes) else {
+        #[cfg(feature = "log")]
+        log::error!("Compiled buffer for {:?} has an invalid header", name);
+        panic!("Compiled buffer for {:?} has an invalid header", name)
+    };
+    let start = std::time::Instant::now();
+    match try_decode(bytes) {
+        Ok(ret) => {
+            hook::call(name, bytes.len(), ret.len(), start.elapsed());
+            ret
+        }
+        Err(err) => {
+            #[cfg(feature = "log")]
+            log::error!(
+                "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                header.method,
+                name,
+                err
+            );
+            panic!(
+                "Compiled `{:?}` buffer for {:?} was corrupted: {:?}",
+                header.method, name, err
+            )
+        }
+    }
+}
+
+/// Decompresses `bytes` like [`decode`], but returns an error instead of panicking
+/// if they are invalid.
+pub fn try_decode(bytes: &[u8]) -> Result<Vec<u8>, FlateCompressionError> {
+    let (header, data) = Header::parse(bytes).ok_or(FlateCompressionError::InvalidHeader)?;
+    let ret = header.decompress(data)?;
+    #[cfg(feature = "verify-checksum")]
+    header.verify(&ret)?;
+    Ok(ret)
+}
+
+/// Decompresses `bytes` like [`try_decode`], but writes the contents into `writer`
+/// as they are decompressed instead of holding them in memory.
+///
+/// Like [`try_decode`], at most the size recorded in the header is ever written,
+/// and the checksum in the header is verified as the contents are written.
+/// Since the contents are streamed, `writer` may have received part of them
+/// when an error is returned.
+///
+/// Like [`try_decode`], this only supports data compressed into a single stream.
+/// Data compressed with a dictionary or split into blocks,
+/// as by `with zstd dict`, [`flate_delta!`] or the chunked algorithms,
+/// fail to decompress; use [`Flate::decode_to_writer`] on their statics instead.
+///
+/// # Errors
+/// If `bytes` do not start with a valid header or are not valid compressed data,
+/// if the contents are longer or shorter than the size in the header
+/// or do not match its checksum, or if writing fails.
+pub fn decode_to_writer<W: Write>(
+    bytes: &[u8],
+    writer: &mut W,
+) -> Result<(), FlateCompressionError> {
+    let (header, data) = Header::parse(bytes).ok_or(FlateCompressionError::InvalidHeader)?;
+    copy_checked(&header, header.decoder(data)?, writer)
+}
+
+/// Copies the contents read from `reader` into `writer`,
+/// failing as soon as they exceed the size in `header`,
+/// or at the end if they are shorter or do not match its checksum.
+pub(crate) fn copy_checked<W: Write>(
+    header: &Header,
+    reader: impl Read,
+    writer: &mut W,
+) -> Result<(), FlateCompressionError> {
+    let mut checked = CheckedWriter {
+        writer,
+        limit: header.len,
+        written: 0,
+        exceeded: false,
+        hasher: crc32fast::Hasher::new(),
+    };
+    // read one more byte than expected to tell whether there are more
+    let mut reader = reader.take(header.len.saturating_add(1));
+    match io::copy(&mut reader, &mut checked) {
         Ok(_) => {}
-        Err(err) => panic!("Compiled `{:?}` buffer was corrupted: {:?}", algo, err),
+        Err(_) if checked.exceeded => {
+            return Err(FlateCompressionError::TooLarge { limit: header.len })
+        }
+        Err(err) => return Err(err.into()),
     }
 
-    ret
+    if checked.written < header.len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "the decompressed data are {} bytes instead of {}",
+                checked.written, header.len
+            ),
+        )
+        .into());
+    }
+    let actual = checked.hasher.finalize();
+    if actual != header.crc32 {
+        return Err(FlateCompressionError::ChecksumMismatch {
+            expected: header.crc32,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Forwards the contents to `writer` up to `limit` bytes, computing their CRC-32.
+struct CheckedWriter<'a, W: Write> {
+    writer: &'a mut W,
+    limit: u64,
+    written: u64,
+    exceeded: bool,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: Write> Write for CheckedWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written + buf.len() as u64 > self.limit {
+            self.exceeded = true;
+            return Err(io::Error::other(
+                "the decompressed data exceed the expected size",
+            ));
+        }
+        let written = self.writer.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Decompresses `bytes` like [`try_decode`],
+/// and also returns an error if the contents are not valid UTF-8.
+pub fn try_decode_string(bytes: &[u8]) -> Result<String, FlateCompressionError> {
+    String::from_utf8(try_decode(bytes)?)
+        .map_err(|err| FlateCompressionError::InvalidUtf8(err.utf8_error()))
 }
 
 #[doc(hidden)]
-#[allow(private_interfaces)]
-pub fn decode_string(bytes: &[u8], algo: Option<CompressionMethodTy>) -> String {
+pub fn decode_string(name: &str, bytes: &[u8]) -> String {
     // We should have checked for utf8 correctness in encode_utf8_file!
-    String::from_utf8(decode(bytes, algo))
-        .expect("flate_str has malformed UTF-8 despite checked at compile time")
+    match String::from_utf8(decode(name, bytes)) {
+        Ok(string) => string,
+        Err(_) => panic!(
+            "{:?} has malformed UTF-8 despite checked at compile time",
+            name
+        ),
+    }
 }
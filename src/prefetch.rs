@@ -0,0 +1,133 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::Flate;
+
+type Job = Box<dyn Fn() + Send + Sync>;
+
+/// Decompresses a list of [`Flate`] statics on background threads, in order of priority.
+///
+/// This is intended to warm up assets while the main thread keeps working,
+/// e.g. so that the assets of the next screen are ready by the time the UI needs them.
+///
+/// # Examples
+/// ```ignore
+/// let handle = Prefetch::new()
+///     .add(&FONT, 10)
+///     .add(&BACKGROUND, 5)
+///     .add(&CREDITS, 0)
+///     .start();
+/// // keep rendering...
+/// handle.join();
+/// ```
+pub struct Prefetch {
+    jobs: Vec<(u32, Job)>,
+    threads: usize,
+}
+
+impl Prefetch {
+    /// Creates an empty list that is decompressed with as many threads as
+    /// [`std::thread::available_parallelism`].
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    /// Sets the number of threads, which is at least one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Adds a static to the list.
+    ///
+    /// Statics with a higher `priority` start decompressing first,
+    /// and statics with the same priority start in the order they were added.
+    pub fn add<T: Send + Sync>(mut self, flate: &'static Flate<T>, priority: u32) -> Self {
+        self.jobs
+            .push((priority, Box::new(move || flate.preload())));
+        self
+    }
+
+    /// Starts decompressing the statics on background threads.
+    ///
+    /// Statics that were already decompressed are skipped.
+    /// Dropping the returned handle does not stop the threads.
+    pub fn start(mut self) -> PrefetchHandle {
+        self.jobs.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let jobs: Arc<[Job]> = self.jobs.into_iter().map(|(_, job)| job).collect();
+        let next = Arc::new(AtomicUsize::new(0));
+
+        let threads = (0..self.threads.min(jobs.len()))
+            .map(|_| {
+                let jobs = Arc::clone(&jobs);
+                let next = Arc::clone(&next);
+                thread::spawn(move || {
+                    while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        job();
+                    }
+                })
+            })
+            .collect();
+        PrefetchHandle { threads }
+    }
+}
+
+impl Default for Prefetch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Prefetch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Prefetch")
+            .field("len", &self.jobs.len())
+            .field("threads", &self.threads)
+            .finish()
+    }
+}
+
+/// The threads started by [`Prefetch::start`].
+#[derive(Debug)]
+pub struct PrefetchHandle {
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl PrefetchHandle {
+    /// Tells whether all statics have been decompressed.
+    pub fn is_finished(&self) -> bool {
+        self.threads.iter().all(|thread| thread.is_finished())
+    }
+
+    /// Waits until all statics have been decompressed.
+    ///
+    /// # Panics
+    /// If the contents of a static could not be loaded.
+    pub fn join(self) {
+        for thread in self.threads {
+            if let Err(err) = thread.join() {
+                std::panic::resume_unwind(err);
+            }
+        }
+    }
+}
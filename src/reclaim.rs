@@ -0,0 +1,199 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::Asset;
+
+/// The contents of a static in memory that the OS may reclaim while they are not used,
+/// for [`Flate::with_reclaimable`](crate::Flate::with_reclaimable).
+pub(crate) struct Reclaimable {
+    mapping: Mutex<Option<Mapping>>,
+}
+
+impl Reclaimable {
+    pub(crate) const fn new() -> Self {
+        Self {
+            mapping: Mutex::new(None),
+        }
+    }
+
+    /// Calls `f` with the contents of `asset`, decompressing them again if they were reclaimed.
+    pub(crate) fn with<R>(&self, asset: &Asset, f: impl FnOnce(&[u8]) -> R) -> R {
+        let data = {
+            let mut mapping = self.lock();
+            let mapping = match &mut *mapping {
+                Some(mapping) => mapping,
+                None => {
+                    let len = asset.contents_len();
+                    match Mapping::new(len) {
+                        Ok(new) => mapping.insert(new),
+                        Err(err) => {
+                            panic!("Cannot map {} bytes for {:?}: {}", len, asset.name, err)
+                        }
+                    }
+                }
+            };
+            if mapping.users == 0 && !mapping.pin() {
+                mapping.fill(|buf| {
+                    asset.load_into_slice(buf);
+                });
+            }
+            mapping.users += 1;
+            mapping.data()
+        };
+
+        let _unpin = Unpin(self);
+        // SAFETY: the pages are not written or reclaimed until the last user unpins them.
+        f(unsafe { slice::from_raw_parts(data.0, data.1) })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Mapping>> {
+        self.mapping.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Unpins the mapping when the user is done with it, even if the user panics.
+struct Unpin<'a>(&'a Reclaimable);
+
+impl Drop for Unpin<'_> {
+    fn drop(&mut self) {
+        if let Some(mapping) = &mut *self.0.lock() {
+            mapping.users -= 1;
+            if mapping.users == 0 {
+                mapping.unpin();
+            }
+        }
+    }
+}
+
+/// An anonymous mapping that holds the contents.
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+    /// The length of the mapping, i.e. `len` rounded up to whole pages.
+    map_len: usize,
+    page: usize,
+    /// For each page, the offset and value of a nonzero byte in it, if any.
+    ///
+    /// Reclaimed pages read as zero, so this tells whether the page was reclaimed.
+    /// If a page has no such byte, its contents are the same either way.
+    markers: Box<[Option<(usize, u8)>]>,
+    /// The number of callers that are using the contents.
+    users: usize,
+}
+
+// SAFETY: the mapping is only accessed through the mutex, or while it is pinned.
+unsafe impl Send for Mapping {}
+
+impl Mapping {
+    fn new(len: usize) -> io::Result<Self> {
+        // SAFETY: sysconf has no preconditions.
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let map_len = len.div_ceil(page).max(1) * page;
+        // SAFETY: this creates a new mapping that does not alias anything.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            ptr: ptr.cast(),
+            len,
+            map_len,
+            page,
+            markers: Box::new([]),
+            users: 0,
+        })
+    }
+
+    fn data(&self) -> (*const u8, usize) {
+        (self.ptr, self.len)
+    }
+
+    /// Writes the contents with `load` and records the markers of the pages.
+    fn fill(&mut self, load: impl FnOnce(&mut [u8])) {
+        // SAFETY: the mapping is not pinned by any user, so nothing borrows it.
+        let buf = unsafe { slice::from_raw_parts_mut(self.ptr, self.len) };
+        load(buf);
+        self.markers = buf
+            .chunks(self.page)
+            .map(|page| {
+                let offset = page.iter().position(|&byte| byte != 0)?;
+                Some((offset, page[offset]))
+            })
+            .collect();
+    }
+
+    /// Writes to every page so that the OS no longer reclaims them,
+    /// and returns whether none of the pages had been reclaimed before.
+    ///
+    /// Each page is written with an atomic swap of its marker, which also reads the marker,
+    /// so a page cannot be reclaimed between checking and pinning it.
+    fn pin(&self) -> bool {
+        let mut intact = self.markers.len() == self.len.div_ceil(self.page);
+        for page in 0..self.map_len / self.page {
+            let (offset, value) = self.markers.get(page).copied().flatten().unwrap_or((0, 0));
+            // SAFETY: the offset is within the mapping, and nothing borrows it while unpinned.
+            let byte = unsafe { AtomicU8::from_ptr(self.ptr.add(page * self.page + offset)) };
+            if byte.swap(value, Ordering::Relaxed) != value {
+                intact = false;
+            }
+        }
+        intact
+    }
+
+    /// Allows the OS to reclaim the pages until they are pinned again.
+    fn unpin(&self) {
+        // SAFETY: the range is the mapping, and the contents are checked before they are used.
+        unsafe {
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                target_os = "ios",
+                target_os = "freebsd"
+            ))]
+            if libc::madvise(self.ptr.cast(), self.map_len, libc::MADV_FREE) == 0 {
+                return;
+            }
+            // MADV_FREE is not available before Linux 4.5,
+            // so the pages are discarded right away instead
+            libc::madvise(self.ptr.cast(), self.map_len, libc::MADV_DONTNEED);
+        }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created in `new` and is not borrowed anymore.
+        unsafe {
+            libc::munmap(self.ptr.cast(), self.map_len);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::{CompressionMethod, Flate};
+
+/// A static that registered itself before `main`.
+trait Registered: Sync {
+    fn ident(&self) -> Option<&'static str>;
+    fn name(&self) -> &'static str;
+    fn compressed_len(&self) -> usize;
+    fn decompressed_len(&self) -> usize;
+    fn algorithm(&self) -> CompressionMethod;
+    fn is_initialized(&self) -> bool;
+    fn preload(&self);
+}
+
+impl<T: Send + Sync> Registered for Flate<T> {
+    fn ident(&self) -> Option<&'static str> {
+        self.asset.ident
+    }
+
+    fn name(&self) -> &'static str {
+        self.asset.name
+    }
+
+    fn compressed_len(&self) -> usize {
+        Flate::compressed_len(self)
+    }
+
+    fn decompressed_len(&self) -> usize {
+        Flate::decompressed_len(self)
+    }
+
+    fn algorithm(&self) -> CompressionMethod {
+        Flate::algorithm(self)
+    }
+
+    fn is_initialized(&self) -> bool {
+        Flate::is_initialized(self)
+    }
+
+    fn preload(&self) {
+        Flate::preload(self)
+    }
+}
+
+#[doc(hidden)]
+pub struct __Registration(&'static dyn Registered);
+
+impl __Registration {
+    #[doc(hidden)]
+    pub const fn __new<T: Send + Sync>(flate: &'static Flate<T>) -> Self {
+        Self(flate)
+    }
+}
+
+inventory::collect!(__Registration);
+
+/// A [`flate!`](crate::flate) static, as listed by [`assets`].
+#[derive(Clone, Copy)]
+pub struct AssetInfo {
+    flate: &'static dyn Registered,
+}
+
+impl AssetInfo {
+    /// The identifier of the static.
+    pub fn ident(&self) -> &'static str {
+        self.flate.ident().unwrap_or_default()
+    }
+
+    /// The name of the asset, i.e. its path unless the `strip-paths` feature is enabled.
+    pub fn name(&self) -> &'static str {
+        self.flate.name()
+    }
+
+    /// See [`Flate::compressed_len`].
+    pub fn compressed_len(&self) -> usize {
+        self.flate.compressed_len()
+    }
+
+    /// See [`Flate::decompressed_len`].
+    pub fn decompressed_len(&self) -> usize {
+        self.flate.decompressed_len()
+    }
+
+    /// See [`Flate::algorithm`].
+    pub fn algorithm(&self) -> CompressionMethod {
+        self.flate.algorithm()
+    }
+
+    /// See [`Flate::is_initialized`].
+    pub fn is_initialized(&self) -> bool {
+        self.flate.is_initialized()
+    }
+
+    /// See [`Flate::preload`].
+    pub fn preload(&self) {
+        self.flate.preload()
+    }
+}
+
+impl fmt::Debug for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AssetInfo")
+            .field("ident", &self.ident())
+            .field("name", &self.name())
+            .field("compressed_len", &self.compressed_len())
+            .field("decompressed_len", &self.decompressed_len())
+            .field("algorithm", &self.algorithm())
+            .field("initialized", &self.is_initialized())
+            .finish()
+    }
+}
+
+/// Returns every [`flate!`](crate::flate) static in the program,
+/// including those of dependencies, in no particular order.
+///
+/// This requires the `registry` feature,
+/// which registers each static through the [`inventory`] crate.
+pub fn assets() -> impl Iterator<Item = AssetInfo> {
+    inventory::iter::<__Registration>
+        .into_iter()
+        .map(|registration| AssetInfo {
+            flate: registration.0,
+        })
+}
+
+/// Decompresses every [`flate!`](crate::flate) static in the program
+/// that was not decompressed yet, e.g. to move the cost of the first accesses to startup.
+///
+/// This requires the `registry` feature.
+pub fn init_all() {
+    for asset in assets() {
+        asset.preload();
+    }
+}
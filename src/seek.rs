@@ -0,0 +1,115 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+
+use crate::{Contents, Flate};
+
+/// The number of bytes read at once from statics that are not chunked.
+const WINDOW: usize = 64 * 1024;
+
+/// A reader over the contents of a [`Flate`] static that can seek,
+/// returned by [`Flate::seekable`].
+///
+/// Each read only decompresses the part of the contents around the current position
+/// with [`Flate::read_range`], so for statics declared `with zstd_seekable` or as chunked,
+/// only the frames or blocks that are read are decompressed,
+/// and the static is not initialized.
+/// The last part that was read is kept, so small consecutive reads do not decompress it again.
+pub struct Seekable<T: 'static> {
+    flate: &'static Flate<T>,
+    len: u64,
+    pos: u64,
+    window: usize,
+    /// The range of the contents in `buf`.
+    range: Range<u64>,
+    buf: Vec<u8>,
+}
+
+impl<T: Contents> Seekable<T> {
+    pub(crate) fn new(flate: &'static Flate<T>, len: usize, block_size: Option<usize>) -> Self {
+        Self {
+            flate,
+            len: len as u64,
+            pos: 0,
+            window: block_size.unwrap_or(WINDOW),
+            range: 0..0,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the contents.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Tells whether the contents are empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T: Contents> Read for Seekable<T> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len || out.is_empty() {
+            return Ok(0);
+        }
+
+        if !self.range.contains(&self.pos) {
+            // align the part to the blocks so that each block is decompressed once
+            let start = self.pos - self.pos % self.window as u64;
+            let end = (start + self.window.max(out.len()) as u64).min(self.len);
+            self.buf = self.flate.read_range(start as usize..end as usize);
+            self.range = start..end;
+        }
+
+        let offset = (self.pos - self.range.start) as usize;
+        let n = out.len().min(self.buf.len() - offset);
+        out[..n].copy_from_slice(&self.buf[offset..offset + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<T: Contents> Seek for Seekable<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Seekable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Seekable")
+            .field("len", &self.len)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
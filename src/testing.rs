@@ -0,0 +1,97 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for verifying embedded data in tests.
+//!
+//! ```ignore
+//! use include_flate::{flate, testing::assert_matches_source};
+//!
+//! flate!(static DATA: [u8] from "assets/data.bin");
+//!
+//! #[test]
+//! fn data_is_up_to_date() {
+//!     assert_matches_source!(DATA, "assets/data.bin");
+//! }
+//! ```
+
+use std::path::Path;
+
+use include_flate_compress::{compress_bytes, decompress_bytes, CompressionMethod};
+
+#[doc(inline)]
+pub use crate::assert_matches_source;
+
+/// Asserts that the contents of a `flate!` static are identical to a file.
+///
+/// The path is relative to `CARGO_MANIFEST_DIR` of the calling crate,
+/// consistent with the path passed to `flate!`.
+#[macro_export]
+macro_rules! assert_matches_source {
+    ($name:expr, $path:literal) => {
+        $crate::testing::assert_matches_file(
+            &*$name,
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Asserts that `actual` is identical to the contents of the file at `path`.
+///
+/// # Panics
+/// - If the file cannot be read
+/// - If the contents differ
+#[track_caller]
+pub fn assert_matches_file<T: AsRef<[u8]> + ?Sized, P: AsRef<Path>>(actual: &T, path: P) {
+    let path = path.as_ref();
+    let expected =
+        std::fs::read(path).unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+    assert_same_bytes(actual.as_ref(), &expected, &path.display().to_string());
+}
+
+/// Asserts that `data` is unchanged after compressing and decompressing it with `method`.
+///
+/// # Panics
+/// - If compression or decompression fails
+/// - If the decompressed data differ from `data`
+#[track_caller]
+pub fn assert_roundtrip(data: &[u8], method: CompressionMethod) {
+    let compressed = compress_bytes(data, method)
+        .unwrap_or_else(|err| panic!("cannot compress with `{:?}`: {}", method, err));
+    let decompressed = decompress_bytes(&compressed, method)
+        .unwrap_or_else(|err| panic!("cannot decompress with `{:?}`: {}", method, err));
+
+    assert_same_bytes(&decompressed, data, &format!("`{:?}` roundtrip", method));
+}
+
+/// Compares two buffers without dumping their whole contents on mismatch.
+#[track_caller]
+fn assert_same_bytes(actual: &[u8], expected: &[u8], what: &str) {
+    if actual == expected {
+        return;
+    }
+
+    match actual.iter().zip(expected).position(|(a, b)| a != b) {
+        Some(offset) => panic!(
+            "data differ from {} at offset {} ({:#04x} != {:#04x})",
+            what, offset, actual[offset], expected[offset]
+        ),
+        None => panic!(
+            "data differ from {} in length ({} != {} bytes)",
+            what,
+            actual.len(),
+            expected.len()
+        ),
+    }
+}
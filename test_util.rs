@@ -18,12 +18,15 @@ use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::str::from_utf8;
 
-use include_flate_compress::{apply_compression, apply_decompression, CompressionMethod};
+use include_flate_compress::{
+    apply_compression, apply_decompression, CompressionLevel, CompressionMethod,
+    CompressionOptions,
+};
 
 pub fn get_file_path<P: AsRef<Path>>(relative_from: Option<&Path>, path: P) -> PathBuf {
     let cargo_manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     let default_base_path = Path::new(&cargo_manifest_dir);
-    let base_path = relative_from.unwrap_or_else(|| default_base_path);
+    let base_path = relative_from.unwrap_or(default_base_path);
     base_path.join("assets").join(path)
 }
 
@@ -34,7 +37,7 @@ pub fn read_file<P: AsRef<Path>>(name: P) -> Vec<u8> {
     vec
 }
 
-pub fn verify_compression<P: AsRef<Path>>(name: P, data: &[u8], method: CompressionMethod) {
+pub fn verify_compression<P: AsRef<Path>>(name: P, data: &[u8], method: CompressionOptions) {
     let path = get_file_path(None, &name);
     let mut file = File::open(&path).unwrap();
     let mut file_buffer = Vec::new();
@@ -51,15 +54,41 @@ pub fn verify_compression<P: AsRef<Path>>(name: P, data: &[u8], method: Compress
     {
         let mut compressed_cursor = std::io::Cursor::new(&mut compressed_buffer);
         let mut decompressed_cursor = std::io::Cursor::new(&mut decompressed_buffer);
-        apply_decompression(&mut compressed_cursor, &mut decompressed_cursor, method).unwrap();
+        apply_decompression(
+            &mut compressed_cursor,
+            &mut decompressed_cursor,
+            method.method,
+        )
+        .unwrap();
         decompressed_cursor.seek(SeekFrom::Start(0)).unwrap(); // Reset cursor position
     }
     assert_ne!(compressed_buffer.as_slice(), decompressed_buffer.as_slice());
 }
 
+/// The DEFLATE and zstd methods, as far as they are enabled.
+pub fn methods() -> Vec<CompressionMethod> {
+    vec![
+        #[cfg(feature = "deflate")]
+        CompressionMethod::Deflate,
+        CompressionMethod::Zstd,
+    ]
+}
+
 pub fn verify<P: AsRef<Path>>(name: P, data: &[u8]) {
-    verify_compression(&name, data, CompressionMethod::Deflate);
-    verify_compression(&name, data, CompressionMethod::Zstd);
+    for method in methods() {
+        for level in [
+            CompressionLevel::Fast,
+            CompressionLevel::Balanced,
+            CompressionLevel::Max,
+        ] {
+            let options = CompressionOptions {
+                method,
+                level,
+                ..CompressionOptions::default()
+            };
+            verify_compression(&name, data, options);
+        }
+    }
     assert_eq!(read_file(&name), data);
 }
 
@@ -18,12 +18,14 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: [u8] from "assets/009f.dat");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: [u8] from "assets/009f.dat" with deflate);
 flate!(pub static DATA3: [u8] from "assets/009f.dat" with zstd);
 
 #[test]
 fn test() {
     verify("009f.dat", &DATA1);
+    #[cfg(feature = "deflate")]
     verify("009f.dat", &DATA2);
     verify("009f.dat", &DATA3);
 }
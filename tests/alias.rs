@@ -0,0 +1,45 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::{flate, flate_alias};
+
+mod assets {
+    use include_flate::flate;
+
+    flate!(pub static DATA: [u8] from "assets/ff.dat");
+}
+
+flate!(static TEXT: str from "assets/chinese.txt");
+
+flate_alias!(pub static LEGACY_DATA = assets::DATA);
+flate_alias!(static LEGACY_TEXT = self::TEXT);
+
+mod legacy {
+    use include_flate::flate_alias;
+
+    flate_alias!(pub(crate) static OLD_DATA = crate::assets::DATA);
+}
+
+#[test]
+fn test() {
+    verify("ff.dat", &LEGACY_DATA);
+    verify_str("chinese.txt", &LEGACY_TEXT);
+
+    assert!(std::ptr::eq(&LEGACY_DATA, &assets::DATA));
+    assert!(std::ptr::eq(&legacy::OLD_DATA, &assets::DATA));
+    assert!(format!("{:?}", TEXT).contains("initialized: true"));
+}
@@ -0,0 +1,68 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls the future on the current thread, counting the polls.
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    let mut polls = 0;
+    loop {
+        polls += 1;
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return (output, polls);
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn test_get_async() {
+    let (data, _) = block_on(DATA.get_async());
+    verify("random.dat", data);
+    assert!(format!("{:?}", DATA).contains("initialized: true"));
+
+    let (again, polls) = block_on(DATA.get_async());
+    assert_eq!(again.as_ptr(), data.as_ptr());
+    assert_eq!(polls, 1);
+}
+
+#[test]
+fn test_await() {
+    let (first, second) = block_on(async { (TEXT.get_async().await, TEXT.get_async().await) }).0;
+    verify_str("chinese.txt", first);
+    assert_eq!(first.as_ptr(), second.as_ptr());
+}
@@ -18,12 +18,14 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: str from "assets/base64.txt");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: str from "assets/base64.txt" with deflate);
 flate!(pub static DATA3: str from "assets/base64.txt" with zstd);
 
 #[test]
 fn test() {
     verify_str("base64.txt", &DATA1);
+    #[cfg(feature = "deflate")]
     verify_str("base64.txt", &DATA2);
     verify_str("base64.txt", &DATA3);
 }
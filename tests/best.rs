@@ -0,0 +1,76 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::codegen::deflate_file;
+use include_flate::{flate, inflate};
+use include_flate_compress::{Header, HEADER_SIZE};
+
+flate!(pub static RANDOM: [u8] from "assets/random.dat" with best);
+flate!(pub static ZERO: [u8] from "assets/zero.dat" with best);
+flate!(pub static CHINESE: str from "assets/chinese.txt" with best);
+
+#[test]
+fn test() {
+    verify("random.dat", &RANDOM);
+    verify("zero.dat", &ZERO);
+    verify_str("chinese.txt", &CHINESE);
+    assert_eq!(
+        inflate!("assets/base64.txt" with best),
+        read_file("base64.txt")
+    );
+
+    let lines: Vec<_> = CHINESE.lines_streaming().collect();
+    assert_eq!(lines, CHINESE.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_smallest() {
+    // random data does not compress, so it is stored as is
+    let random: &[u8] = deflate_file!("assets/random.dat" best);
+    assert_eq!(method(random), CompressionMethod::Store);
+    assert_eq!(RANDOM.compressed(), read_file("random.dat"));
+
+    let zero: &[u8] = deflate_file!("assets/zero.dat" best);
+    #[cfg(feature = "deflate")]
+    {
+        let deflate: &[u8] = deflate_file!("assets/zero.dat" deflate);
+        assert!(zero.len() < deflate.len());
+    }
+    assert_ne!(method(zero), CompressionMethod::Store);
+}
+
+flate!(pub static FAST_RANDOM: [u8] from "assets/random.dat" with fastest_decode);
+flate!(pub static FAST_CHINESE: str from "assets/chinese.txt" with fastest_decode);
+
+#[test]
+fn test_fastest_decode() {
+    verify("random.dat", &FAST_RANDOM);
+    verify_str("chinese.txt", &FAST_CHINESE);
+
+    let fast: &[u8] = deflate_file!("assets/chinese.txt" fastest_decode);
+    let best: &[u8] = deflate_file!("assets/chinese.txt" best);
+    assert!((fast.len() - HEADER_SIZE) * 100 <= (best.len() - HEADER_SIZE) * 110);
+
+    // random data do not compress, so storing them is both the smallest and the fastest
+    let random: &[u8] = deflate_file!("assets/random.dat" fastest_decode);
+    assert_eq!(method(random), CompressionMethod::Store);
+}
+
+/// Returns the method recorded in the header of `data`.
+fn method(data: &[u8]) -> CompressionMethod {
+    Header::parse(data).unwrap().0.method
+}
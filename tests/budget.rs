@@ -0,0 +1,65 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "cache-budget")]
+
+include!("../test_util.rs");
+
+use std::sync::Arc;
+
+use include_flate::{cache_budget, cached_bytes, flate, set_cache_budget, Contents, Flate};
+
+flate!(static RANDOM: [u8] from "assets/random.dat");
+flate!(static CHINESE: str from "assets/chinese.txt");
+flate!(static EMOJI: str from "assets/emoji.txt");
+
+fn is_cached<T: Contents>(flate: &'static Flate<T>, kept: &Arc<T>) -> bool {
+    Arc::ptr_eq(kept, &flate.shared())
+}
+
+// the budget is global, so everything is checked in a single test
+#[test]
+fn test_budget() {
+    let random = read_file("random.dat").len();
+    let chinese = read_file("chinese.txt").len();
+    let emoji = read_file("emoji.txt").len();
+    assert_eq!(cache_budget(), None);
+
+    let kept_random = RANDOM.shared();
+    let kept_chinese = CHINESE.shared();
+    verify("random.dat", &kept_random);
+    assert_eq!(cached_bytes(), random + chinese);
+
+    // CHINESE was used more recently, so RANDOM is evicted first
+    set_cache_budget(Some(chinese + emoji));
+    assert_eq!(cache_budget(), Some(chinese + emoji));
+    assert_eq!(cached_bytes(), chinese);
+
+    let guard = EMOJI.load();
+    verify_str("emoji.txt", &guard);
+    assert_eq!(cached_bytes(), chinese + emoji);
+
+    // contents used by a guard are not evicted
+    set_cache_budget(Some(0));
+    assert_eq!(cached_bytes(), emoji);
+    drop(guard);
+    assert_eq!(cached_bytes(), 0);
+
+    set_cache_budget(None);
+    assert!(!is_cached(&RANDOM, &kept_random));
+    assert!(!is_cached(&CHINESE, &kept_chinese));
+    assert!(is_cached(&CHINESE, &CHINESE.shared()));
+    assert_eq!(cached_bytes(), random + chinese);
+}
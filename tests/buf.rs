@@ -0,0 +1,108 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate_buf;
+
+flate_buf!(pub static RANDOM: [u8] from "assets/random.dat");
+#[cfg(feature = "deflate")]
+flate_buf!(pub static CHINESE: str from "assets/chinese.txt" with deflate);
+flate_buf!(static ZERO: [u8] from "assets/zero.dat");
+#[cfg(feature = "deflate")]
+flate_buf!(static CHUNKED: [u8] from "assets/base64.txt" with deflate_chunked);
+#[cfg(feature = "zstd")]
+flate_buf!(static EMOJI: str from "assets/emoji.txt" with zstd);
+#[cfg(feature = "zstd")]
+flate_buf!(static SEEKABLE: [u8] from "assets/base64.txt" with zstd_seekable);
+
+#[test]
+fn test() {
+    assert!(format!("{:?}", RANDOM).contains("initialized: false"));
+    let random: &'static [u8] = &RANDOM;
+    assert_eq!(random, read_file("random.dat"));
+    assert!(format!("{:?}", RANDOM).contains("initialized: true"));
+
+    assert_eq!(&*ZERO, read_file("zero.dat"));
+    #[cfg(feature = "deflate")]
+    {
+        let chinese: &'static str = &CHINESE;
+        verify_str("chinese.txt", chinese);
+        assert_eq!(&*CHUNKED, read_file("base64.txt"));
+    }
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    verify_str("emoji.txt", &EMOJI);
+    assert_eq!(&*SEEKABLE, read_file("base64.txt"));
+}
+
+/// Returns the names and sizes of the sections in the ELF file `elf`.
+#[cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+fn sections(elf: &[u8]) -> Vec<(String, usize)> {
+    let u16_at = |at: usize| u16::from_le_bytes(elf[at..at + 2].try_into().unwrap()) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(elf[at..at + 4].try_into().unwrap()) as usize;
+    let u64_at = |at: usize| u64::from_le_bytes(elf[at..at + 8].try_into().unwrap()) as usize;
+
+    let (offset, size, count, names) = (u64_at(0x28), u16_at(0x3a), u16_at(0x3c), u16_at(0x3e));
+    let header = |index: usize| offset + index * size;
+    let names = u64_at(header(names) + 24);
+    (0..count)
+        .map(|index| {
+            let header = header(index);
+            let start = names + u32_at(header);
+            let end = start + elf[start..].iter().position(|&byte| byte == 0).unwrap();
+            let name = String::from_utf8_lossy(&elf[start..end]).into_owned();
+            (name, u64_at(header + 32))
+        })
+        .collect()
+}
+
+// the buffers are uninitialized statics, so they are placed in `.bss` rather than `.data`
+#[cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+#[test]
+fn test_data_size() {
+    let elf = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let sections = sections(&elf);
+    let size = |prefix: &str| -> usize {
+        sections
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .map(|(_, size)| size)
+            .sum()
+    };
+    #[allow(unused_mut)]
+    let mut buffers = RANDOM.len() + ZERO.len();
+    #[cfg(feature = "deflate")]
+    {
+        buffers += CHUNKED.len();
+    }
+    assert!(
+        size(".data") < buffers / 3,
+        "{} bytes of data",
+        size(".data")
+    );
+    assert!(size(".bss") >= buffers, "{} bytes of bss", size(".bss"));
+}
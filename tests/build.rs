@@ -0,0 +1,153 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::build::{compress, Header};
+use include_flate::codegen::deflate_file;
+use include_flate::{decode, try_decode, try_decode_string, FlateCompressionError};
+use include_flate_compress::compress_bytes;
+
+#[test]
+fn test_matches_macro() {
+    let data = read_file("chinese.txt");
+
+    #[cfg(feature = "deflate")]
+    assert_eq!(
+        compress(&data, CompressionMethod::Deflate).unwrap(),
+        deflate_file!("assets/chinese.txt" deflate)
+    );
+    #[cfg(feature = "zstd")]
+    assert_eq!(
+        compress(&data, CompressionMethod::Zstd).unwrap(),
+        deflate_file!("assets/chinese.txt" zstd)
+    );
+    assert_eq!(
+        compress(&data, CompressionMethod::default()).unwrap(),
+        deflate_file!("assets/chinese.txt")
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    let data = read_file("base64.txt");
+    let method = CompressionMethod::default();
+    let compressed = compress(&data, method).unwrap();
+    let decoded = decode("base64.txt", &compressed);
+    assert_eq!(decoded, data);
+    // allocated once with the size in the header
+    assert_eq!(decoded.capacity(), data.len());
+}
+
+#[test]
+fn test_header() {
+    let data = read_file("chinese.txt");
+    let compressed: &[u8] = deflate_file!("assets/chinese.txt");
+    let (header, _) = Header::parse(compressed).unwrap();
+    assert_eq!(header.method, CompressionMethod::default());
+    assert_eq!(header.len, data.len() as u64);
+    assert_eq!(decode("chinese.txt", compressed), data);
+
+    let stored: &[u8] = deflate_file!("assets/chinese.txt" none);
+    assert_eq!(Header::parse(stored).unwrap().1, data);
+}
+
+#[test]
+#[should_panic(expected = "invalid header")]
+fn test_missing_header() {
+    let data = read_file("base64.txt");
+    let compressed = compress_bytes(&data, CompressionMethod::default()).unwrap();
+    decode("base64.txt", &compressed);
+}
+
+#[test]
+#[should_panic(expected = "TooLarge { limit: 1024 }")]
+fn test_bounded() {
+    let data = vec![0; 1 << 20];
+    let method = CompressionMethod::default();
+    let header = Header {
+        len: 1024,
+        ..Header::new(method, &data)
+    };
+    let compressed = header.prepend(&compress_bytes(&data, method).unwrap());
+    decode("zero.dat", &compressed);
+}
+
+#[test]
+fn test_try_decode() {
+    let data = read_file("chinese.txt");
+    let method = CompressionMethod::default();
+    let compressed = compress(&data, method).unwrap();
+    assert_eq!(try_decode(&compressed).unwrap(), data);
+    assert_eq!(try_decode_string(&compressed).unwrap().as_bytes(), data);
+
+    let raw = compress_bytes(&data, method).unwrap();
+    assert!(matches!(
+        try_decode(&raw),
+        Err(FlateCompressionError::InvalidHeader)
+    ));
+
+    let header = Header {
+        len: 16,
+        ..Header::new(method, &data)
+    };
+    let truncated = header.prepend(&raw);
+    assert!(matches!(
+        try_decode(&truncated),
+        Err(FlateCompressionError::TooLarge { limit: 16 })
+    ));
+
+    let binary = compress(&[0xff; 16], method).unwrap();
+    assert!(matches!(
+        try_decode_string(&binary),
+        Err(FlateCompressionError::InvalidUtf8(_))
+    ));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_train_dictionary_from_dir() {
+    use include_flate::build::train_dictionary_from_dir;
+    use include_flate_compress::{compress_with_dict, decompress_with_dict};
+
+    let dir = std::env::temp_dir().join(format!("include-flate-dict-{}", std::process::id()));
+    let sample = |i: usize| {
+        format!(
+            r#"{{"id": {}, "name": "user-{}", "email": "user-{}@example.com", "active": {}}}"#,
+            i,
+            i * 7,
+            i * 13,
+            i.is_multiple_of(2)
+        )
+    };
+    for i in 0..400 {
+        let sub = dir.join(format!("{}", i % 4));
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(sub.join(format!("{}.json", i)), sample(i)).unwrap();
+    }
+
+    let dict = train_dictionary_from_dir(&dir, 4096).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(!dict.is_empty() && dict.len() <= 4096);
+
+    let data = sample(1000);
+    let with_dict = compress_with_dict(data.as_bytes(), &dict, CompressionMethod::Zstd).unwrap();
+    let without = compress(data.as_bytes(), CompressionMethod::Zstd).unwrap();
+    assert!(with_dict.len() < without.len());
+    assert_eq!(
+        decompress_with_dict(&with_dict, &dict).unwrap(),
+        data.as_bytes()
+    );
+}
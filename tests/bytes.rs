@@ -0,0 +1,41 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "bytes")]
+
+include!("../test_util.rs");
+
+use bytes::Bytes;
+use include_flate::{flate, Flate};
+
+flate!(pub static DATA: [u8] in Bytes from "assets/random.dat");
+flate!(pub static TEXT: [u8] in Bytes from "assets/chinese.txt" with zstd);
+
+#[test]
+fn test_bytes() {
+    let _: &Flate<Bytes> = &DATA;
+    verify("random.dat", &DATA);
+    verify("chinese.txt", &TEXT);
+}
+
+#[test]
+fn test_zero_copy() {
+    let shared = DATA.clone();
+    assert_eq!(shared.as_ptr(), DATA.as_ptr());
+
+    let slice = Bytes::slice(&DATA, 16..32);
+    assert_eq!(slice.as_ptr(), DATA[16..].as_ptr());
+    assert_eq!(slice, read_file("random.dat")[16..32]);
+}
@@ -0,0 +1,50 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "verify-checksum")]
+
+include!("../test_util.rs");
+
+use include_flate::build::{compress, Header, HEADER_SIZE};
+use include_flate::{decode, flate, flate_buf};
+
+flate!(pub static DATA: [u8] from "assets/random.dat");
+flate!(pub static TEXT: str from "assets/chinese.txt" with deflate_chunked);
+flate_buf!(pub static BUF: [u8] from "assets/base64.txt");
+
+#[test]
+fn test() {
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+    assert_eq!(&BUF[..], read_file("base64.txt"));
+}
+
+#[test]
+fn test_header() {
+    let data = read_file("chinese.txt");
+    let compressed = compress(&data, CompressionMethod::default()).unwrap();
+    let (header, _) = Header::parse(&compressed).unwrap();
+    assert_eq!(header.verify(&data).map_err(|err| err.to_string()), Ok(()));
+    assert!(header.verify(&data[1..]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "ChecksumMismatch")]
+fn test_corrupted() {
+    let data = read_file("base64.txt");
+    let mut stored = compress(&data, CompressionMethod::Store).unwrap();
+    stored[HEADER_SIZE] ^= 1;
+    decode("base64.txt", &stored);
+}
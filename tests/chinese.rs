@@ -18,12 +18,20 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: str from "assets/chinese.txt");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: str from "assets/chinese.txt" with deflate);
 flate!(pub static DATA3: str from "assets/chinese.txt" with zstd);
 
 #[test]
 fn test() {
     verify_str("chinese.txt", &DATA1);
+    #[cfg(feature = "deflate")]
     verify_str("chinese.txt", &DATA2);
     verify_str("chinese.txt", &DATA3);
 }
+
+#[test]
+fn test_lines_streaming() {
+    let lines: Vec<String> = DATA3.lines_streaming().collect();
+    assert_eq!(lines, DATA1.lines().collect::<Vec<_>>());
+}
@@ -0,0 +1,119 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "deflate")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+use include_flate_compress::{
+    compress_bytes, compress_chunked, decompress_block, decompress_chunked, ChunkIndex,
+    DEFAULT_BLOCK_SIZE,
+};
+
+flate!(static RANDOM: [u8] from "assets/random.dat" with deflate_chunked);
+flate!(static BASE64: str from "assets/base64.txt" with deflate_chunked);
+#[cfg(feature = "zstd")]
+flate!(static CHINESE: str from "assets/chinese.txt" with zstd_chunked);
+
+#[test]
+#[cfg_attr(
+    all(feature = "debug-passthrough", debug_assertions),
+    ignore = "the data are not compressed"
+)]
+fn test_block() {
+    let expected = read_file("random.dat");
+    assert_eq!(
+        RANDOM.block_count(),
+        expected.len().div_ceil(DEFAULT_BLOCK_SIZE)
+    );
+
+    for n in [0, 3, RANDOM.block_count() - 1] {
+        let start = n * DEFAULT_BLOCK_SIZE;
+        let end = (start + DEFAULT_BLOCK_SIZE).min(expected.len());
+        assert_eq!(RANDOM.block(n), &expected[start..end]);
+    }
+    let range = DEFAULT_BLOCK_SIZE - 10..DEFAULT_BLOCK_SIZE * 2 + 10;
+    assert_eq!(RANDOM.read_range(range.clone()), &expected[range]);
+    assert!(format!("{:?}", RANDOM).contains("initialized: false"));
+
+    verify("random.dat", &RANDOM);
+    assert_eq!(RANDOM.capacity(), expected.len());
+    assert_eq!(
+        RANDOM.block(1),
+        &expected[DEFAULT_BLOCK_SIZE..DEFAULT_BLOCK_SIZE * 2]
+    );
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_block_out_of_bounds() {
+    RANDOM.block(RANDOM.block_count());
+}
+
+#[test]
+#[cfg_attr(
+    all(feature = "debug-passthrough", debug_assertions),
+    ignore = "the data are not compressed"
+)]
+fn test_str() {
+    verify_str("base64.txt", &BASE64);
+    assert_eq!(
+        BASE64.block_count(),
+        read_file("base64.txt").len().div_ceil(DEFAULT_BLOCK_SIZE)
+    );
+    assert_eq!(
+        BASE64.lines_streaming().collect::<Vec<_>>().join("\n"),
+        *BASE64
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let expected = read_file("chinese.txt");
+    assert_eq!(CHINESE.read_range(5..50), &expected[5..50]);
+    verify_str("chinese.txt", &CHINESE);
+}
+
+#[test]
+fn test_format() {
+    let data = read_file("base64.txt");
+    let compressed = compress_chunked(&data, CompressionMethod::Deflate, 1000).unwrap();
+
+    let index = ChunkIndex::parse(&compressed).unwrap();
+    assert_eq!(index.block_count(), data.len().div_ceil(1000));
+    assert_eq!(index.block_size(), 1000);
+    assert_eq!(index.decompressed_len(), data.len());
+    assert_eq!(index.block_range(2), 2000..3000);
+
+    assert_eq!(
+        decompress_block(&compressed, CompressionMethod::Deflate, 2).unwrap(),
+        &data[2000..3000]
+    );
+    assert_eq!(
+        decompress_chunked(&compressed, CompressionMethod::Deflate).unwrap(),
+        data
+    );
+
+    let empty = compress_chunked(&[], CompressionMethod::Deflate, 1000).unwrap();
+    assert_eq!(ChunkIndex::parse(&empty).unwrap().block_count(), 0);
+    assert!(decompress_chunked(&empty, CompressionMethod::Deflate)
+        .unwrap()
+        .is_empty());
+
+    let plain = compress_bytes(&data, CompressionMethod::Deflate).unwrap();
+    assert!(ChunkIndex::parse(&plain).is_none());
+}
@@ -0,0 +1,73 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::build::Header;
+use include_flate::codegen::deflate_file;
+use include_flate::flate_chunks;
+use include_flate_compress::ChunkIndex;
+
+flate_chunks!(pub static ATLAS: [u8] from "assets/random.dat" with zstd_chunked of 4096);
+#[cfg(feature = "deflate")]
+flate_chunks!(static TEXT: [u8] from "assets/chinese.txt" with deflate_chunked of 1000);
+
+#[test]
+fn test_chunks() {
+    let expected = read_file("random.dat");
+    assert_eq!(ATLAS.chunk_size(), 4096);
+    assert_eq!(ATLAS.len(), expected.len().div_ceil(4096));
+    assert_eq!(
+        format!("{:?}", ATLAS),
+        "FlateChunks { chunk_size: 4096, loaded: 0 }"
+    );
+
+    assert_eq!(ATLAS.get_chunk(3), &expected[3 * 4096..4 * 4096]);
+    assert_eq!(ATLAS.get_chunk(0), &expected[..4096]);
+    // chunks are decompressed once
+    assert_eq!(ATLAS.get_chunk(3).as_ptr(), ATLAS.get_chunk(3).as_ptr());
+    assert_eq!(
+        format!("{:?}", ATLAS),
+        "FlateChunks { chunk_size: 4096, loaded: 2 }"
+    );
+
+    #[cfg(feature = "deflate")]
+    {
+        let expected = read_file("chinese.txt");
+        let chunks: Vec<u8> = (0..TEXT.len())
+            .flat_map(|i| TEXT.get_chunk(i).to_vec())
+            .collect();
+        assert_eq!(chunks, expected);
+        assert_eq!(TEXT.get_chunk(TEXT.len() - 1).len(), expected.len() % 1000);
+    }
+}
+
+#[test]
+fn test_block_size() {
+    let compressed: &[u8] = deflate_file!("assets/chinese.txt" zstd_chunked of 1KiB);
+    let index = ChunkIndex::parse(Header::parse(compressed).unwrap().1).unwrap();
+    assert_eq!(index.block_size(), 1024);
+    assert_eq!(
+        index.block_count(),
+        read_file("chinese.txt").len().div_ceil(1024)
+    );
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_out_of_bounds() {
+    TEXT.get_chunk(TEXT.len());
+}
@@ -0,0 +1,55 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::{flate, flate_buf, inflate};
+
+flate!(pub static TEXT: str from ["assets/chinese.txt", "assets/emoji.txt"] joined "\n");
+#[cfg(feature = "deflate")]
+flate!(pub static BYTES: [u8] from ["assets/ff.dat", "assets/zero.dat",] with deflate);
+flate!(pub static SINGLE: str from ["assets/emoji.txt"] joined "\n");
+flate_buf!(static BUF: str from ["assets/emoji.txt", "assets/chinese.txt"] joined "--");
+
+fn concat(names: &[&str], separator: &str) -> Vec<u8> {
+    names
+        .iter()
+        .map(read_file)
+        .collect::<Vec<_>>()
+        .join(separator.as_bytes())
+}
+
+#[test]
+fn test() {
+    assert_eq!(TEXT.as_bytes(), concat(&["chinese.txt", "emoji.txt"], "\n"));
+    #[cfg(feature = "deflate")]
+    assert_eq!(*BYTES, concat(&["ff.dat", "zero.dat"], ""));
+    verify_str("emoji.txt", &SINGLE);
+    assert_eq!(BUF.as_bytes(), concat(&["emoji.txt", "chinese.txt"], "--"));
+
+    let data = inflate!(["assets/emoji.txt", "assets/emoji.txt"] joined " ");
+    assert_eq!(data, concat(&["emoji.txt", "emoji.txt"], " "));
+}
+
+#[cfg(not(feature = "strip-paths"))]
+#[test]
+fn test_name() {
+    use include_flate::codegen::asset_name;
+
+    assert_eq!(
+        asset_name!(["assets/chinese.txt", "assets/emoji.txt"]),
+        "assets/chinese.txt+assets/emoji.txt"
+    );
+}
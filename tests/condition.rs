@@ -0,0 +1,132 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::borrow::Cow;
+
+use include_flate::build::Header;
+use include_flate::codegen::deflate_file;
+use include_flate::{flate, flate_module, inflate, Flate};
+
+flate!(static ZERO: [u8] from "assets/zero.dat" if saves_at_least(4KiB));
+flate!(static RANDOM: [u8] from "assets/random.dat" with zstd if saves_at_least(1));
+flate!(static CHINESE: str from "assets/chinese.txt" if saves_at_least(1000000000));
+flate!(static BEST: str from "assets/chinese.txt" with best if saves_at_least(1));
+flate!(static LARGE: [u8] from "assets/zero.dat" with zstd if larger_than(1024));
+flate!(static SMALL: str from "assets/emoji.txt" with zstd if larger_than(1000000));
+flate!(static COMBINED: [u8] from "assets/zero.dat"
+    if less_than_original and compression_ratio_more_than(99.5%) or larger_than(1000000000));
+flate!(static NEGATED: [u8] from "assets/random.dat" with zstd if not(less_than_original));
+flate!(static RELEASE: str from "assets/chinese.txt" if cfg(not(debug_assertions)));
+flate!(static CFG_OR_SIZE: [u8] from "assets/zero.dat" if cfg(any()) or larger_than(1KiB));
+flate!(static OWNED: str in String from "assets/emoji.txt" with zstd if larger_than(1000000));
+
+flate_module! {
+    mod assets {
+        static EN: str from "assets/locale-en.json" with zstd if saves_at_least(100);
+        static FF: [u8] from "assets/ff.dat" if saves_at_least(100);
+    }
+}
+
+#[test]
+fn test() {
+    verify("zero.dat", &ZERO);
+    verify("random.dat", &RANDOM);
+    verify_str("chinese.txt", &CHINESE);
+    verify_str("chinese.txt", &BEST);
+    verify("zero.dat", &LARGE);
+    verify_str("emoji.txt", &SMALL);
+    verify("zero.dat", &COMBINED);
+    verify("random.dat", &NEGATED);
+    verify_str("chinese.txt", &RELEASE);
+    verify("zero.dat", &CFG_OR_SIZE);
+    verify_str("emoji.txt", &OWNED);
+    verify_str("locale-en.json", &assets::EN);
+    verify("ff.dat", &assets::FF);
+    assert_eq!(
+        inflate!("assets/ff.dat" with zstd if saves_at_least(1)),
+        read_file("ff.dat")
+    );
+}
+
+#[test]
+fn test_method() {
+    // the method is recorded in front of the data
+    let zero: &[u8] = deflate_file!("assets/zero.dat" if saves_at_least(4096));
+    assert_ne!(method(zero), CompressionMethod::Store);
+    let random: &[u8] = deflate_file!("assets/random.dat" zstd if saves_at_least(1));
+    assert_eq!(method(random), CompressionMethod::Store);
+
+    // the statics are not compressed at all in debug builds with `debug-passthrough`
+    if !cfg!(all(feature = "debug-passthrough", debug_assertions)) {
+        assert_eq!(RANDOM.compressed(), read_file("random.dat"));
+        assert_eq!(CHINESE.compressed(), read_file("chinese.txt"));
+        assert!(LARGE.compressed().len() < read_file("zero.dat").len());
+        assert_eq!(SMALL.compressed(), read_file("emoji.txt"));
+        assert!(COMBINED.compressed().len() < read_file("zero.dat").len());
+        assert!(CFG_OR_SIZE.compressed().len() < read_file("zero.dat").len());
+    }
+
+    let ratio: &[u8] = deflate_file!("assets/ff.dat" if compression_ratio_more_than(100%));
+    assert_eq!(method(ratio), CompressionMethod::Store);
+    let grouped: &[u8] =
+        deflate_file!("assets/zero.dat" if not(larger_than(1) and less_than_original));
+    assert_eq!(method(grouped), CompressionMethod::Store);
+
+    // zero.dat is 1 MiB
+    let larger: &[u8] = deflate_file!("assets/zero.dat" if larger_than(1.5 MB));
+    assert_eq!(method(larger), CompressionMethod::Store);
+    let smaller: &[u8] = deflate_file!("assets/zero.dat" if larger_than(1023KiB));
+    assert_ne!(method(smaller), CompressionMethod::Store);
+    let equal: &[u8] = deflate_file!("assets/zero.dat" if larger_than(1MiB));
+    assert_eq!(method(equal), CompressionMethod::Store);
+
+    // the compiler decides whether the data are compressed
+    let release: &[u8] = deflate_file!("assets/zero.dat" if cfg(not(debug_assertions)));
+    assert_eq!(
+        method(release) == CompressionMethod::Store,
+        cfg!(debug_assertions)
+    );
+    let linux: &[u8] =
+        deflate_file!("assets/zero.dat" if cfg(target_os = "linux") and larger_than(1KiB));
+    assert_eq!(
+        method(linux) == CompressionMethod::Store,
+        !cfg!(target_os = "linux")
+    );
+}
+
+#[test]
+fn test_storage() {
+    let _: &Flate<Cow<'static, [u8]>> = &ZERO;
+    let _: &Flate<Cow<'static, str>> = &CHINESE;
+    let _: &Flate<String> = &OWNED;
+
+    // files that are embedded as is are borrowed from the binary
+    if !cfg!(all(feature = "debug-from-disk", debug_assertions)) {
+        assert!(matches!(*RANDOM, Cow::Borrowed(_)));
+        assert!(matches!(*SMALL, Cow::Borrowed(_)));
+        assert_eq!(CHINESE.as_ptr(), CHINESE.compressed().as_ptr());
+        assert_eq!(matches!(*RELEASE, Cow::Borrowed(_)), cfg!(debug_assertions));
+    }
+    if !cfg!(debug_assertions) {
+        assert!(matches!(*ZERO, Cow::Owned(_)));
+    }
+}
+
+/// Returns the method recorded in the header of `data`.
+fn method(data: &[u8]) -> CompressionMethod {
+    Header::parse(data).unwrap().0.method
+}
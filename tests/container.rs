@@ -0,0 +1,59 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "deflate")]
+
+include!("../test_util.rs");
+
+use include_flate::{flate, inflate};
+
+flate!(pub static GZIP: [u8] from "assets/random.dat" with gzip);
+flate!(pub static ZLIB: [u8] from "assets/random.dat" with zlib);
+flate!(pub static GZIP_STR: str from "assets/chinese.txt" with gzip);
+flate!(pub static ZLIB_STR: str from "assets/chinese.txt" with zlib);
+
+#[test]
+fn test() {
+    verify("random.dat", &GZIP);
+    verify("random.dat", &ZLIB);
+    verify_str("chinese.txt", &GZIP_STR);
+    verify_str("chinese.txt", &ZLIB_STR);
+    assert_eq!(
+        inflate!("assets/base64.txt" with gzip),
+        read_file("base64.txt")
+    );
+}
+
+#[test]
+#[cfg_attr(
+    all(feature = "debug-passthrough", debug_assertions),
+    ignore = "the data are not compressed"
+)]
+fn test_headers() {
+    // the gzip magic number and the DEFLATE compression method
+    assert_eq!(GZIP_STR.compressed()[..3], [0x1f, 0x8b, 8]);
+
+    // the DEFLATE compression method and the header checksum
+    let header = &ZLIB_STR.compressed()[..2];
+    assert_eq!(header[0] & 0x0f, 8);
+    assert_eq!(u16::from_be_bytes([header[0], header[1]]) % 31, 0);
+}
+
+#[test]
+fn test_compression() {
+    for method in [CompressionMethod::Gzip, CompressionMethod::Zlib] {
+        verify_compression("random.dat", &GZIP, method.into());
+    }
+}
@@ -0,0 +1,55 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+use include_flate_compress::compress_bytes;
+
+flate!(static USED: [u8] from "assets/base64.txt");
+
+flate!(#[allow(dead_code)] static UNUSED: [u8] from "assets/random.dat");
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+#[cfg_attr(
+    all(feature = "debug-passthrough", debug_assertions),
+    ignore = "the data are not compressed"
+)]
+#[cfg_attr(
+    all(
+        feature = "registry",
+        not(all(feature = "debug-passthrough", debug_assertions))
+    ),
+    ignore = "the registry keeps every static in the binary"
+)]
+fn test() {
+    verify("base64.txt", &USED);
+
+    let binary = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+
+    let used = compress_bytes(&read_file("base64.txt"), CompressionMethod::default()).unwrap();
+    assert!(contains(&binary, &used[..64]));
+
+    let unused = compress_bytes(&read_file("random.dat"), CompressionMethod::default()).unwrap();
+    for offset in [0, unused.len() / 2, unused.len() - 64] {
+        assert!(!contains(&binary, &unused[offset..offset + 64]));
+    }
+}
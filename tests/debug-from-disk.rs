@@ -0,0 +1,56 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `debug-passthrough` would skip decompressing the list of files in debug builds
+#![cfg(all(feature = "debug-from-disk", not(feature = "debug-passthrough")))]
+
+include!("../test_util.rs");
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use include_flate::{flate, set_decode_hook};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from ["assets/chinese.txt", "assets/emoji.txt"] joined "\n");
+
+/// The number of bytes decompressed from the embedded data.
+static DECOMPRESSED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+fn record(_: &str, _: usize, decompressed: usize, _: Duration) {
+    DECOMPRESSED.lock().unwrap().push(decompressed);
+}
+
+// the hook is global, so everything is checked in a single test
+#[test]
+fn test_debug_from_disk() {
+    set_decode_hook(Some(record));
+    verify("random.dat", &DATA);
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8(read_file("chinese.txt")).unwrap(),
+        String::from_utf8(read_file("emoji.txt")).unwrap()
+    );
+    assert_eq!(*TEXT, text);
+    set_decode_hook(None);
+
+    // a list of files does not exist on disk, so it is always decompressed
+    let decompressed = DECOMPRESSED.lock().unwrap();
+    if cfg!(debug_assertions) {
+        assert_eq!(*decompressed, [text.len()]);
+    } else {
+        assert_eq!(*decompressed, [read_file("random.dat").len(), text.len()]);
+    }
+}
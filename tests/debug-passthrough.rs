@@ -0,0 +1,63 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "debug-passthrough", feature = "zstd"))]
+
+include!("../test_util.rs");
+
+use include_flate::{flate, flate_buf, flate_delta, inflate};
+
+flate!(static RANDOM: [u8] from "assets/random.dat" with zstd);
+flate!(static CHINESE: str from "assets/chinese.txt" with deflate_chunked);
+flate!(static JOINED: str from ["assets/ascii-printable.txt", "assets/chinese.txt"] joined "\n");
+flate!(static BEST: [u8] from "assets/zero.dat" with best if larger_than(1KiB));
+flate_delta!(static DELTA: str from "assets/chinese.txt" against CHINESE = "assets/chinese.txt");
+flate_buf!(static BUF: [u8] from "assets/ff.dat" with zstd dict shared);
+
+#[test]
+fn test() {
+    verify("random.dat", &RANDOM);
+    verify_str("chinese.txt", &CHINESE);
+    let joined = read_file("ascii-printable.txt")
+        .into_iter()
+        .chain(*b"\n")
+        .chain(read_file("chinese.txt"))
+        .collect::<Vec<_>>();
+    assert_eq!(JOINED.as_bytes(), joined);
+    verify("zero.dat", &BEST);
+    verify_str("chinese.txt", &DELTA);
+    assert_eq!(&*BUF, read_file("ff.dat"));
+    assert_eq!(
+        inflate!("assets/base64.txt" with zstd),
+        read_file("base64.txt")
+    );
+}
+
+#[test]
+fn test_compressed() {
+    // the files are embedded as is in debug builds
+    assert_eq!(
+        RANDOM.compressed() == read_file("random.dat"),
+        cfg!(debug_assertions)
+    );
+    assert_eq!(
+        BEST.compressed() == read_file("zero.dat"),
+        cfg!(debug_assertions)
+    );
+    assert_eq!(
+        CHINESE.compressed() == read_file("chinese.txt"),
+        cfg!(debug_assertions)
+    );
+}
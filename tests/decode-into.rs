@@ -0,0 +1,69 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/random.dat" with zstd);
+flate!(pub static TEXT: str from "assets/chinese.txt");
+flate!(pub static CHUNKED: [u8] from "assets/base64.txt" with zstd_chunked);
+#[cfg(feature = "deflate")]
+flate!(pub static EMOJI: str from "assets/emoji.txt" with deflate);
+
+#[test]
+fn test_vec() {
+    let mut buf = Vec::with_capacity(2 << 20);
+    let ptr = buf.as_ptr();
+
+    let len = DATA.decode_into(&mut buf);
+    assert_eq!(len, buf.len());
+    verify("random.dat", &buf);
+
+    TEXT.decode_into(&mut buf);
+    verify("chinese.txt", &buf);
+
+    CHUNKED.decode_into(&mut buf);
+    verify("base64.txt", &buf);
+
+    // the buffer is reused
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(buf.capacity(), 2 << 20);
+
+    // initialized statics are copied
+    verify_str("chinese.txt", &TEXT);
+    TEXT.decode_into(&mut buf);
+    verify("chinese.txt", &buf);
+}
+
+#[test]
+fn test_slice() {
+    let mut buf = vec![0; 1 << 20];
+    #[cfg(feature = "deflate")]
+    {
+        let len = EMOJI.decode_into_slice(&mut buf);
+        verify("emoji.txt", &buf[..len]);
+    }
+
+    let len = CHUNKED.decode_into_slice(&mut buf);
+    verify("base64.txt", &buf[..len]);
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+#[should_panic(expected = "too short")]
+fn test_short() {
+    EMOJI.decode_into_slice(&mut [0; 4]);
+}
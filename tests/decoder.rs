@@ -0,0 +1,33 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::io::BufRead;
+
+use include_flate_compress::compress_bytes;
+
+#[test]
+fn test_lines() {
+    let data = read_file("chinese.txt");
+    let expected: Vec<_> = from_utf8(&data).unwrap().lines().collect();
+
+    for method in methods() {
+        let compressed = compress_bytes(&data, method).unwrap();
+        let decoder = method.decoder(compressed.as_slice()).unwrap();
+        let lines: Vec<_> = decoder.lines().map(Result::unwrap).collect();
+        assert_eq!(lines, expected);
+    }
+}
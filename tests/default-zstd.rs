@@ -0,0 +1,39 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "default-zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::build::Header;
+use include_flate::codegen::deflate_file;
+use include_flate::decode;
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/base64.txt");
+
+#[test]
+fn test() {
+    assert_eq!(CompressionMethod::default(), CompressionMethod::Zstd);
+
+    let unannotated: &[u8] = deflate_file!("assets/base64.txt");
+    assert_eq!(
+        Header::parse(unannotated).unwrap().0.method,
+        CompressionMethod::Zstd
+    );
+    assert_eq!(decode("base64.txt", unannotated), read_file("base64.txt"));
+
+    verify("base64.txt", &DATA);
+}
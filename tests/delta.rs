@@ -0,0 +1,42 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::codegen::{deflate_file, delta_file};
+use include_flate::{flate, flate_delta};
+
+flate!(static EN: str from "assets/locale-en.json" with zstd);
+flate_delta!(static FR: str from "assets/locale-fr.json" against EN = "assets/locale-en.json");
+flate_delta!(static FR_BYTES: [u8] from "assets/locale-fr.json" against EN = "assets/locale-en.json");
+
+#[test]
+fn test() {
+    verify_str("locale-fr.json", &FR);
+    verify("locale-fr.json", &FR_BYTES);
+    verify_str("locale-en.json", &EN);
+
+    let lines: Vec<_> = FR.lines_streaming().collect();
+    assert_eq!(lines, FR.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_smaller() {
+    let delta: &[u8] = delta_file!("assets/locale-fr.json" "assets/locale-en.json");
+    let standalone: &[u8] = deflate_file!("assets/locale-fr.json" zstd);
+    assert!(delta.len() * 2 < standalone.len());
+}
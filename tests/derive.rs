@@ -0,0 +1,48 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::{Flate, FlateAssets, FlateBytes, FlateStr};
+
+#[derive(FlateAssets, Clone, Copy)]
+struct Assets {
+    #[flate("assets/ff.dat")]
+    ff: FlateBytes,
+    #[cfg_attr(feature = "deflate", flate("assets/chinese.txt" with deflate))]
+    #[cfg_attr(not(feature = "deflate"), flate("assets/chinese.txt"))]
+    chinese: FlateStr,
+    #[flate("assets/emoji.txt")]
+    emoji: &'static Flate<String>,
+    #[cfg_attr(feature = "deflate", flate("assets/random.dat" with zstd on "x86_64-*", deflate))]
+    #[cfg_attr(not(feature = "deflate"), flate("assets/random.dat" with zstd))]
+    random: FlateBytes,
+}
+
+#[test]
+fn test() {
+    let assets = Assets::assets();
+    assert!(format!("{:?}", assets.ff).contains("initialized: false"));
+
+    verify("ff.dat", assets.ff);
+    verify_str("chinese.txt", assets.chinese);
+    verify_str("emoji.txt", assets.emoji);
+    verify("random.dat", assets.random);
+
+    // every value shares the same statics
+    let again = Assets::assets();
+    assert!(std::ptr::eq(assets.ff, again.ff));
+    assert!(format!("{:?}", again.ff).contains("initialized: true"));
+}
@@ -0,0 +1,46 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::codegen::deflate_file;
+use include_flate::{flate, flate_buf, inflate};
+
+flate!(static FR: str from "assets/locale-fr.json" with zstd dict "assets/locale-en.json");
+flate!(static FR_BYTES: [u8] from "assets/locale-fr.json" with zstd(long) dict "assets/locale-en.json");
+flate_buf!(static FR_BUF: str from "assets/locale-fr.json" with zstd dict "assets/locale-en.json");
+
+#[test]
+fn test() {
+    verify_str("locale-fr.json", &FR);
+    verify("locale-fr.json", &FR_BYTES);
+    verify_str("locale-fr.json", &FR_BUF);
+    assert_eq!(
+        inflate!("assets/locale-fr.json" with zstd dict "assets/locale-en.json"),
+        read_file("locale-fr.json")
+    );
+
+    let lines: Vec<_> = FR.lines_streaming().collect();
+    assert_eq!(lines, FR.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_smaller() {
+    let dict: &[u8] = deflate_file!("assets/locale-fr.json" zstd dict "assets/locale-en.json");
+    let standalone: &[u8] = deflate_file!("assets/locale-fr.json" zstd);
+    assert!(dict.len() * 2 < standalone.len());
+}
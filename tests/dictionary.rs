@@ -0,0 +1,40 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use include_flate_compress::{
+    compress_bytes, compress_with_dict, decompress_with_dict, train_dictionary, CompressionMethod,
+};
+
+#[test]
+fn test() {
+    let samples: Vec<String> = (0..1000)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "name": "item-{}", "tags": ["asset", "flate"]}}"#,
+                i,
+                i * 7
+            )
+        })
+        .collect();
+    let dict = train_dictionary(&samples, 4096).unwrap();
+
+    let data = br#"{"id": 12345, "name": "item-54321", "tags": ["asset", "flate"]}"#;
+    let compressed = compress_with_dict(data, &dict, CompressionMethod::Zstd).unwrap();
+    assert!(compressed.len() < compress_bytes(data, CompressionMethod::Zstd).unwrap().len());
+    assert_eq!(decompress_with_dict(&compressed, &dict).unwrap(), data);
+
+    #[cfg(feature = "deflate")]
+    assert!(compress_with_dict(data, &dict, CompressionMethod::Deflate).is_err());
+}
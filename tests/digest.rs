@@ -0,0 +1,93 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "sha256", feature = "xxh3"))]
+
+include!("../test_util.rs");
+
+use include_flate::{
+    flate, flate_buf, flate_group, inflate, Asset, Flate, FlateAssets, FlateStr, HashAlgorithm,
+};
+
+flate!(pub static BASE64: [u8] from "assets/base64.txt" checksum sha256 as BASE64_SHA256);
+flate!(pub static CHINESE: str from "assets/chinese.txt" with zstd checksum xxh3 as CHINESE_XXH3);
+flate!(static CHUNKED: [u8] from "assets/random.dat" with deflate_chunked checksum sha256);
+flate_group!(#[cfg(not(miri))] {
+    static FF: [u8] from "assets/ff.dat" with zstd on "x86_64-*", deflate checksum xxh3 as FF_XXH3;
+});
+flate_buf!(static BUF: str from "assets/emoji.txt" with zstd checksum sha256 as BUF_SHA256);
+
+#[derive(FlateAssets)]
+struct Assets {
+    #[flate("assets/config.toml" with zstd checksum sha256)]
+    config: FlateStr,
+}
+
+#[test]
+fn test() {
+    verify("base64.txt", &BASE64);
+    verify_str("chinese.txt", &CHINESE);
+    verify("random.dat", &CHUNKED);
+    verify_str("emoji.txt", &BUF);
+    verify("ff.dat", &FF);
+    verify_str("config.toml", Assets::assets().config);
+    verify(
+        "base64.txt",
+        &inflate!("assets/base64.txt" with zstd checksum xxh3),
+    );
+}
+
+#[test]
+fn test_digest() {
+    // from `sha256sum assets/base64.txt`
+    assert_eq!(
+        hex(BASE64_SHA256),
+        "9e85b85188a32cd8887d0ab796bd0625380faa16c27ad2557b6feb8abcc5ca88"
+    );
+    assert_eq!(
+        CHINESE_XXH3,
+        HashAlgorithm::Xxh3.digest(&read_file("chinese.txt"))
+    );
+    assert_eq!(FF_XXH3, HashAlgorithm::Xxh3.digest(&read_file("ff.dat")));
+    assert_eq!(
+        BUF_SHA256,
+        HashAlgorithm::Sha256.digest(&read_file("emoji.txt"))
+    );
+}
+
+static CORRUPTED: Flate<Vec<u8>> = Flate::new(
+    Asset {
+        ident: Some("CORRUPTED"),
+        name: "base64.txt",
+        source: None,
+        path: None,
+        checksum: Some((HashAlgorithm::Sha256, &[0; 32])),
+        signature: None,
+        bytes: include_flate::codegen::deflate_file!("assets/base64.txt"),
+        chunked: false,
+        base: None,
+    },
+    Asset::load_bytes,
+);
+
+#[test]
+#[should_panic(expected = "DigestMismatch(Sha256)")]
+fn test_mismatch() {
+    let _ = CORRUPTED.len();
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,39 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "ctor")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(#[eager] static DATA: [u8] from "assets/random.dat");
+flate!(
+    /// Loaded before `main`.
+    #[eager]
+    #[allow(dead_code)]
+    pub static TEXT: str from "assets/chinese.txt" with zstd
+);
+flate!(static LAZY: [u8] from "assets/ff.dat");
+
+#[test]
+fn test_eager() {
+    assert!(format!("{:?}", DATA).contains("initialized: true"));
+    assert!(format!("{:?}", TEXT).contains("initialized: true"));
+    assert!(format!("{:?}", LAZY).contains("initialized: false"));
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+    verify("ff.dat", &LAZY);
+}
@@ -18,12 +18,14 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: str from "assets/emoji.txt");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: str from "assets/emoji.txt" with deflate);
 flate!(pub static DATA3: str from "assets/emoji.txt" with zstd);
 
 #[test]
 fn test() {
     verify_str("emoji.txt", &DATA1);
+    #[cfg(feature = "deflate")]
     verify_str("emoji.txt", &DATA2);
     verify_str("emoji.txt", &DATA3);
 }
@@ -0,0 +1,34 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate_compress::{compress_bytes, compression_ratio, estimate_compressed_size};
+
+#[test]
+fn test() {
+    for method in methods() {
+        let data = read_file("base64.txt");
+        let compressed = compress_bytes(&data, method).unwrap().len() as u64;
+        let estimate = estimate_compressed_size(&mut data.as_slice(), method, None).unwrap();
+        assert_eq!(estimate, Some(compressed));
+        assert!(compression_ratio(data.len() as u64, compressed) < 10.0);
+
+        let data = read_file("random.dat");
+        let limit = Some(data.len() as u64 / 2);
+        let estimate = estimate_compressed_size(&mut data.as_slice(), method, limit).unwrap();
+        assert_eq!(estimate, None);
+    }
+}
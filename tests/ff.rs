@@ -18,12 +18,14 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: [u8] from "assets/ff.dat");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: [u8] from "assets/ff.dat" with deflate);
 flate!(pub static DATA3: [u8] from "assets/ff.dat" with zstd);
 
 #[test]
 fn test() {
     verify("ff.dat", &DATA1);
+    #[cfg(feature = "deflate")]
     verify("ff.dat", &DATA2);
     verify("ff.dat", &DATA3);
 }
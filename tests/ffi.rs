@@ -0,0 +1,43 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(#[ffi] static DATA: [u8] from "assets/random.dat");
+flate!(
+    /// Exported to C.
+    #[ffi]
+    pub static TEXT: str from "assets/chinese.txt" with zstd
+);
+
+extern "C" {
+    fn DATA_ptr() -> *const u8;
+    fn DATA_len() -> usize;
+    fn TEXT_ptr() -> *const u8;
+    fn TEXT_len() -> usize;
+}
+
+#[test]
+fn test_ffi() {
+    let data = unsafe { std::slice::from_raw_parts(DATA_ptr(), DATA_len()) };
+    verify("random.dat", data);
+    assert_eq!(data.as_ptr(), DATA.as_ptr());
+
+    let text = unsafe { std::slice::from_raw_parts(TEXT_ptr(), TEXT_len()) };
+    verify_str("chinese.txt", std::str::from_utf8(text).unwrap());
+    assert_eq!(text, TEXT.as_bytes());
+}
@@ -0,0 +1,36 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate_group;
+
+flate_group!(#[cfg(all())] {
+    pub static DATA: [u8] from "assets/ff.dat";
+    #[allow(dead_code)]
+    pub(crate) static TEXT: str from "assets/emoji.txt" with zstd;
+});
+
+// The files do not exist, so this would not compile if the group was not stripped.
+flate_group!(#[cfg(any())] #[allow(dead_code)] {
+    static MISSING: [u8] from "assets/missing.dat";
+    static MISSING_TEXT: str from "assets/missing.txt";
+});
+
+#[test]
+fn test() {
+    verify("ff.dat", &DATA);
+    verify_str("emoji.txt", &TEXT);
+}
@@ -0,0 +1,60 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+
+#[test]
+fn test_guard() {
+    let first = DATA.load();
+    let second = DATA.load();
+    verify("random.dat", &first);
+    assert_eq!(first.as_ptr(), second.as_ptr());
+    assert!(format!("{:?}", DATA).contains("initialized: false"));
+
+    // keeps the contents alive, so that new contents cannot reuse the address
+    let kept = DATA.shared();
+    assert_eq!(kept.as_ptr(), first.as_ptr());
+    drop(first);
+    assert!(Arc::ptr_eq(&kept, &DATA.shared()));
+    drop(second);
+    assert!(!Arc::ptr_eq(&kept, &DATA.shared()));
+}
+
+#[test]
+fn test_grace_period() {
+    let grace = Duration::from_millis(500);
+    TEXT.set_grace_period(grace);
+
+    let guard = TEXT.load();
+    verify_str("chinese.txt", &guard);
+    let kept = TEXT.shared();
+    drop(guard);
+    assert!(Arc::ptr_eq(&kept, &TEXT.shared()));
+
+    let guard = TEXT.load();
+    assert_eq!(guard.as_ptr(), kept.as_ptr());
+    drop(guard);
+    thread::sleep(grace * 4);
+    assert!(!Arc::ptr_eq(&kept, &TEXT.shared()));
+}
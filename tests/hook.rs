@@ -0,0 +1,69 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use include_flate::build::compress;
+use include_flate::{decode, flate, set_decode_hook};
+
+flate!(static DATA: [u8] from "assets/random.dat" with zstd);
+
+static CALLS: Mutex<Vec<(String, usize, usize)>> = Mutex::new(Vec::new());
+
+fn record(name: &str, compressed: usize, decompressed: usize, _: Duration) {
+    CALLS
+        .lock()
+        .unwrap()
+        .push((name.to_string(), compressed, decompressed));
+}
+
+// the hook is global, so everything is checked in a single test
+#[test]
+fn test_hook() {
+    set_decode_hook(Some(record));
+
+    verify("random.dat", &DATA);
+    let data = read_file("chinese.txt");
+    let compressed = compress(&data, CompressionMethod::default()).unwrap();
+    decode("chinese.txt", &compressed);
+
+    set_decode_hook(None);
+    decode("chinese.txt", &compressed);
+
+    let mut calls = CALLS.lock().unwrap();
+    assert_eq!(
+        calls.pop(),
+        Some(("chinese.txt".to_string(), compressed.len(), data.len()))
+    );
+
+    // the static is not decompressed in debug builds with these features
+    if cfg!(all(
+        any(feature = "debug-from-disk", feature = "debug-passthrough"),
+        debug_assertions
+    )) {
+        assert!(calls.is_empty());
+        return;
+    }
+    let (name, compressed, decompressed) = calls.pop().unwrap();
+    if !cfg!(feature = "strip-paths") {
+        assert_eq!(name, "assets/random.dat");
+    }
+    assert_eq!(compressed, DATA.compressed().len());
+    assert_eq!(decompressed, read_file("random.dat").len());
+    assert!(calls.is_empty());
+}
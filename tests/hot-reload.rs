@@ -0,0 +1,55 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "hot-reload", feature = "runtime-override"))]
+
+include!("../test_util.rs");
+
+use std::time::{Duration, SystemTime};
+
+use include_flate::flate;
+
+flate!(pub static LIVE: str from "assets/chinese.txt");
+flate!(pub static EMBEDDED: [u8] from "assets/random.dat");
+
+fn write(path: &Path, contents: &str, modified: SystemTime) {
+    std::fs::write(path, contents).unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[test]
+fn test() {
+    let path = std::env::temp_dir().join(format!("include-flate-reload-{}", std::process::id()));
+    let start = SystemTime::now();
+    write(&path, "before", start);
+    std::env::set_var("INCLUDE_FLATE_OVERRIDE_LIVE", &path);
+
+    let before: &str = &LIVE;
+    assert_eq!(before, "before");
+    assert_eq!(*LIVE, "before");
+
+    write(&path, "after", start + Duration::from_secs(1));
+    assert_eq!(*LIVE, "after");
+    // earlier borrows remain valid
+    assert_eq!(before, "before");
+
+    std::fs::remove_file(path).unwrap();
+    verify("random.dat", &EMBEDDED);
+}
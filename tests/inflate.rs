@@ -0,0 +1,26 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::inflate;
+
+#[test]
+fn test() {
+    verify("base64.txt", &inflate!("assets/base64.txt"));
+    #[cfg(feature = "deflate")]
+    verify("base64.txt", &inflate!("assets/base64.txt" with deflate));
+    verify("base64.txt", &inflate!("assets/base64.txt" with zstd));
+}
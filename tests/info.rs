@@ -0,0 +1,43 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/base64.txt" with zstd);
+flate!(static STORED: str from "assets/chinese.txt" with none);
+
+#[test]
+fn test_info() {
+    assert!(!DATA.is_initialized());
+    assert_eq!(DATA.decompressed_len(), read_file("base64.txt").len());
+    assert_eq!(DATA.compressed_len(), DATA.compressed().len());
+    assert!(!DATA.is_initialized());
+
+    // the statics are not compressed at all in debug builds with `debug-passthrough`
+    if !cfg!(all(feature = "debug-passthrough", debug_assertions)) {
+        assert_eq!(DATA.algorithm(), CompressionMethod::Zstd);
+        assert!(DATA.compressed_len() < DATA.decompressed_len());
+    }
+
+    verify("base64.txt", &DATA);
+    assert!(DATA.is_initialized());
+
+    assert_eq!(STORED.algorithm(), CompressionMethod::Store);
+    assert_eq!(STORED.compressed_len(), STORED.decompressed_len());
+}
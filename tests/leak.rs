@@ -0,0 +1,44 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/random.dat" with zstd);
+flate!(pub static TEXT: str in Box from "assets/chinese.txt");
+
+struct Holder;
+
+impl Holder {
+    flate!(const HELP: str from "assets/ascii-printable.txt");
+}
+
+fn spawn_with(text: &'static str) -> usize {
+    std::thread::spawn(move || text.len()).join().unwrap()
+}
+
+#[test]
+fn test_leak() {
+    let data: &'static [u8] = DATA.leak();
+    verify("random.dat", data);
+    assert_eq!(data.as_ptr(), DATA.leak().as_ptr());
+
+    verify_str("chinese.txt", TEXT.leak());
+    assert_eq!(spawn_with(TEXT.leak()), read_file("chinese.txt").len());
+
+    let help: &'static str = Holder::HELP.leak();
+    verify_str("ascii-printable.txt", help);
+}
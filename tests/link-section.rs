@@ -0,0 +1,77 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+
+include!("../test_util.rs");
+
+use include_flate::{flate, flate_group};
+
+flate!(#[link_section = "flate_assets"] static DATA: [u8] from "assets/random.dat");
+flate!(
+    /// Placed in a section.
+    #[link_section = "flate_assets"]
+    #[allow(dead_code)]
+    pub static TEXT: str from "assets/chinese.txt" with zstd
+);
+flate_group!(#[allow(dead_code)] {
+    /// Placed in a section after the group attributes.
+    #[allow(unused)]
+    #[link_section = "flate_assets"]
+    static GROUPED: [u8] from "assets/emoji.txt" with zstd;
+});
+
+/// Returns the contents of the section named `name` in the ELF file `elf`.
+fn section<'a>(elf: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    let u16_at = |at: usize| u16::from_le_bytes(elf[at..at + 2].try_into().unwrap()) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(elf[at..at + 4].try_into().unwrap()) as usize;
+    let u64_at = |at: usize| u64::from_le_bytes(elf[at..at + 8].try_into().unwrap()) as usize;
+
+    let (offset, size, count, names) = (u64_at(0x28), u16_at(0x3a), u16_at(0x3c), u16_at(0x3e));
+    let header = |index: usize| offset + index * size;
+    let names = u64_at(header(names) + 24);
+    (0..count).find_map(|index| {
+        let header = header(index);
+        let start = names + u32_at(header);
+        let end = start + elf[start..].iter().position(|&byte| byte == 0)?;
+        (&elf[start..end] == name.as_bytes()).then(|| {
+            let data = u64_at(header + 24);
+            &elf[data..data + u64_at(header + 32)]
+        })
+    })
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+fn test_link_section() {
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+    verify("emoji.txt", &GROUPED);
+
+    let elf = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let section = section(&elf, "flate_assets").expect("the section is in the binary");
+    assert!(contains(section, DATA.compressed()));
+    assert!(contains(section, TEXT.compressed()));
+    assert!(contains(section, GROUPED.compressed()));
+}
@@ -0,0 +1,97 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "log")]
+
+include!("../test_util.rs");
+
+use std::panic::catch_unwind;
+use std::sync::Mutex;
+
+use include_flate::flate;
+use include_flate_compress::Header;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+
+/// Records the messages logged by include-flate.
+struct Logger(Mutex<Vec<(Level, String)>>);
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with("include_flate")
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            let message = record.args().to_string();
+            self.0.lock().unwrap().push((record.level(), message));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger(Mutex::new(Vec::new()));
+
+fn take() -> Vec<(Level, String)> {
+    std::mem::take(&mut *LOGGER.0.lock().unwrap())
+}
+
+#[test]
+fn test_log() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let _: &[u8] = &DATA;
+    let records = take();
+    assert_eq!(records.len(), 1);
+    // the file is read from disk in debug builds with `debug-from-disk`
+    if cfg!(all(feature = "debug-from-disk", debug_assertions)) {
+        assert_eq!(records[0].0, Level::Info);
+        assert!(records[0].1.starts_with("Read DATA ("));
+        assert!(records[0]
+            .1
+            .ends_with(&format!(": {} bytes", read_file("random.dat").len())));
+    } else {
+        assert_eq!(records[0].0, Level::Debug);
+        assert!(records[0].1.starts_with("Decompressed DATA ("));
+        assert!(records[0].1.ends_with(&format!(
+            ": {} -> {} bytes",
+            DATA.compressed_len(),
+            read_file("random.dat").len()
+        )));
+    }
+
+    // a deflate block of the reserved type
+    let corrupted = Header::new(CompressionMethod::Deflate, b"abc").prepend(&[0xff; 8]);
+    assert!(catch_unwind(|| include_flate::decode("corrupted", &corrupted)).is_err());
+    assert!(catch_unwind(|| include_flate::decode("invalid", b"not a header")).is_err());
+    let records = take();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].0, Level::Error);
+    assert!(records[0]
+        .1
+        .starts_with("Compiled `Deflate` buffer for \"corrupted\" was corrupted: "));
+    assert_eq!(
+        records[1],
+        (
+            Level::Error,
+            "Compiled buffer for \"invalid\" has an invalid header".to_string()
+        )
+    );
+
+    verify("random.dat", &DATA);
+}
@@ -0,0 +1,146 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// the metrics are labelled with the path of the asset
+#![cfg(all(feature = "metrics", not(feature = "strip-paths")))]
+
+include!("../test_util.rs");
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use include_flate::flate;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata,
+    SharedString, Unit,
+};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+
+/// The values recorded for a metric, in order.
+#[derive(Default)]
+struct Values(Mutex<Vec<f64>>);
+
+impl CounterFn for Values {
+    fn increment(&self, value: u64) {
+        self.0.lock().unwrap().push(value as f64);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.lock().unwrap().push(value as f64);
+    }
+}
+
+impl GaugeFn for Values {
+    fn increment(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.0.lock().unwrap().push(-value);
+    }
+
+    fn set(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+impl HistogramFn for Values {
+    fn record(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+/// Records every metric by its name and the value of its `asset` label.
+#[derive(Default)]
+struct Recorder(Mutex<HashMap<(String, String), Arc<Values>>>);
+
+impl Recorder {
+    fn values(&self, key: &Key) -> Arc<Values> {
+        let asset = key
+            .labels()
+            .find(|label| label.key() == "asset")
+            .expect("every metric is labelled with the asset")
+            .value()
+            .to_string();
+        let mut metrics = self.0.lock().unwrap();
+        metrics
+            .entry((key.name().to_string(), asset))
+            .or_default()
+            .clone()
+    }
+
+    fn get(&self, name: &str, asset: &str) -> Vec<f64> {
+        let metrics = self.0.lock().unwrap();
+        let values = &metrics[&(name.to_string(), asset.to_string())];
+        let values = values.0.lock().unwrap().clone();
+        values
+    }
+}
+
+impl metrics::Recorder for Recorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.values(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.values(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.values(key))
+    }
+}
+
+#[test]
+fn test_metrics() {
+    let recorder = Recorder::default();
+    metrics::with_local_recorder(&recorder, || {
+        for _ in 0..3 {
+            let _: &[u8] = &DATA;
+        }
+        let _: &str = &TEXT;
+    });
+
+    for (name, accesses) in [("random.dat", 3.0), ("chinese.txt", 1.0)] {
+        let asset = &format!("assets/{}", name);
+
+        // the contents are only decompressed on the first access
+        let durations = recorder.get("include_flate_decompress_seconds", asset);
+        assert_eq!(durations.len(), 1);
+        assert!(durations[0] >= 0.0);
+        assert_eq!(
+            recorder.get("include_flate_decompressed_bytes", asset),
+            [read_file(name).len() as f64]
+        );
+        assert_eq!(
+            recorder
+                .get("include_flate_accesses_total", asset)
+                .iter()
+                .sum::<f64>(),
+            accesses
+        );
+    }
+
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+}
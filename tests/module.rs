@@ -0,0 +1,67 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate_module;
+
+flate_module! {
+    /// Assets exported by this crate.
+    pub mod assets {
+        static DATA: [u8] from "assets/ff.dat";
+        /// Some emojis.
+        static TEXT: str from "assets/emoji.txt" with zstd;
+    }
+}
+
+#[test]
+fn test() {
+    verify("ff.dat", &assets::DATA);
+    verify_str("emoji.txt", &assets::TEXT);
+
+    let names: Vec<_> = assets::ASSETS.iter().map(|entry| entry.ident()).collect();
+    assert_eq!(names, ["DATA", "TEXT"]);
+
+    let data = assets::get("DATA").unwrap();
+    if !cfg!(feature = "strip-paths") {
+        assert_eq!(data.name(), "assets/ff.dat");
+    }
+    assert!(!data.is_str());
+    assert_eq!(data.as_str(), None);
+    verify("ff.dat", data.bytes());
+
+    let text = assets::get("TEXT").unwrap();
+    assert!(text.is_str());
+    verify_str("emoji.txt", text.as_str().unwrap());
+
+    assert!(assets::get("MISSING").is_none());
+}
+
+flate_module! {
+    mod preloaded {
+        static DATA: [u8] from "assets/random.dat";
+        static TEXT: str from "assets/chinese.txt" with zstd;
+    }
+}
+
+#[test]
+fn test_preload() {
+    assert!(format!("{:?}", preloaded::DATA).contains("initialized: false"));
+    preloaded::preload();
+    assert!(format!("{:?}", preloaded::DATA).contains("initialized: true"));
+    assert!(format!("{:?}", preloaded::TEXT).contains("initialized: true"));
+    verify("random.dat", &preloaded::DATA);
+    verify_str("chinese.txt", &preloaded::TEXT);
+}
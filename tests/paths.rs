@@ -0,0 +1,39 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static BACKSLASH: [u8] from "assets\\ff.dat");
+flate!(pub static DOT: [u8] from "./assets/ff.dat");
+flate!(pub static MIXED: str from "assets/.\\chinese.txt");
+
+#[test]
+fn test() {
+    verify("ff.dat", &BACKSLASH);
+    verify("ff.dat", &DOT);
+    verify_str("chinese.txt", &MIXED);
+}
+
+#[cfg(feature = "strip-paths")]
+#[test]
+fn test_opaque_id() {
+    use include_flate::codegen::asset_name;
+
+    assert_eq!(asset_name!("assets\\ff.dat"), asset_name!("assets/ff.dat"));
+    assert_eq!(asset_name!("./assets/ff.dat"), asset_name!("assets/ff.dat"));
+    assert_ne!(asset_name!("assets/ff.dat"), asset_name!("assets/zero.dat"));
+}
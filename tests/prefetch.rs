@@ -0,0 +1,73 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::{flate, Prefetch};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+flate!(static ZERO: [u8] from "assets/zero.dat");
+flate!(static LATER: [u8] from "assets/ff.dat");
+flate!(static FIRST: [u8] from "assets/base64.txt");
+flate!(static SECOND: str from "assets/emoji.txt");
+
+fn is_initialized(flate: &impl std::fmt::Debug) -> bool {
+    format!("{:?}", flate).contains("initialized: true")
+}
+
+#[test]
+fn test_prefetch() {
+    let handle = Prefetch::new()
+        .add(&DATA, 1)
+        .add(&TEXT, 10)
+        .add(&ZERO, 0)
+        .start();
+    handle.join();
+    assert!(is_initialized(&DATA));
+    assert!(is_initialized(&TEXT));
+    assert!(is_initialized(&ZERO));
+    assert!(!is_initialized(&LATER));
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+    verify("zero.dat", &ZERO);
+
+    let handle = Prefetch::new().start();
+    assert!(handle.is_finished());
+    handle.join();
+}
+
+#[test]
+fn test_priority() {
+    // a single thread decompresses the statics one at a time in order of priority
+    let handle = Prefetch::new()
+        .threads(1)
+        .add(&SECOND, 1)
+        .add(&FIRST, 2)
+        .start();
+    loop {
+        let second = is_initialized(&SECOND);
+        let first = is_initialized(&FIRST);
+        assert!(first || !second);
+        if first {
+            break;
+        }
+        std::thread::yield_now();
+    }
+    handle.join();
+    assert!(is_initialized(&SECOND));
+    verify("base64.txt", &FIRST);
+    verify_str("emoji.txt", &SECOND);
+}
@@ -0,0 +1,56 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::sync::Arc;
+
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt");
+
+#[test]
+fn test_purge() {
+    assert!(!DATA.purge_shared());
+
+    let first = DATA.shared();
+    assert!(Arc::ptr_eq(&first, &DATA.shared()));
+    assert!(format!("{:?}", DATA).contains("initialized: false"));
+    verify("random.dat", &first);
+
+    assert!(DATA.purge_shared());
+    assert!(!DATA.purge_shared());
+    let second = DATA.shared();
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_deref() {
+    // the contents borrowed by dereferencing are not cached by `shared`
+    verify_str("chinese.txt", &TEXT);
+    assert!(!TEXT.purge_shared());
+
+    let shared = TEXT.shared();
+    assert_eq!(*shared, *TEXT);
+    let weak = Arc::downgrade(&shared);
+    assert!(TEXT.purge_shared());
+    assert!(weak.upgrade().is_some());
+    // the memory is released along with the last `Arc`
+    drop(shared);
+    assert!(weak.upgrade().is_none());
+    verify_str("chinese.txt", &TEXT);
+}
@@ -18,12 +18,14 @@ include!("../test_util.rs");
 use include_flate::flate;
 
 flate!(pub static DATA1: [u8] from "assets/random.dat");
+#[cfg(feature = "deflate")]
 flate!(pub static DATA2: [u8] from "assets/random.dat" with deflate);
 flate!(pub static DATA3: [u8] from "assets/random.dat" with zstd);
 
 #[test]
 fn test() {
     verify("random.dat", &DATA1);
+    #[cfg(feature = "deflate")]
     verify("random.dat", &DATA2);
     verify("random.dat", &DATA3);
 }
@@ -0,0 +1,50 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/random.dat" with zstd);
+#[cfg(feature = "deflate")]
+flate!(pub static TEXT: str from "assets/chinese.txt" with deflate);
+flate!(pub static CHUNKED: [u8] in Box from "assets/base64.txt" with zstd_chunked);
+
+#[test]
+fn test_reader() {
+    let mut reader = DATA.reader();
+    let mut head = [0; 100];
+    reader.read_exact(&mut head).unwrap();
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    verify("random.dat", &[&head[..], &rest].concat());
+
+    let mut data = Vec::new();
+    CHUNKED.reader().read_to_end(&mut data).unwrap();
+    verify("base64.txt", &data);
+
+    // reading does not initialize the static
+    assert!(format!("{:?}", DATA).contains("initialized: false"));
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn test_initialized() {
+    use std::io::BufRead;
+
+    verify_str("chinese.txt", &TEXT);
+    let lines: Vec<_> = TEXT.reader().lines().map(Result::unwrap).collect();
+    assert_eq!(lines, TEXT.lines().collect::<Vec<_>>());
+}
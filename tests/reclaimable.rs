@@ -0,0 +1,52 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(unix, feature = "reclaimable"))]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+flate!(static SMALL: [u8] from "assets/009f.dat" with none);
+flate!(static RECLAIMED: str from "assets/chinese.txt");
+
+#[test]
+fn test_reclaimable() {
+    let ptr = DATA.with_reclaimable(|data| {
+        verify("random.dat", data);
+        data.as_ptr()
+    });
+    let nested = DATA.with_reclaimable(|data| {
+        DATA.with_reclaimable(|inner| assert_eq!(inner.as_ptr(), data.as_ptr()));
+        data.as_ptr()
+    });
+    assert_eq!(ptr, nested);
+    assert!(format!("{:?}", DATA).contains("initialized: false"));
+
+    TEXT.with_reclaimable(|text| verify("chinese.txt", text));
+    SMALL.with_reclaimable(|data| verify("009f.dat", data));
+}
+
+#[test]
+fn test_reclaimed() {
+    let (ptr, len) = RECLAIMED.with_reclaimable(|text| (text.as_ptr() as usize, text.len()));
+    // simulates the OS reclaiming the pages
+    unsafe {
+        assert_eq!(libc::madvise(ptr as *mut _, len, libc::MADV_DONTNEED), 0);
+    }
+    RECLAIMED.with_reclaimable(|text| verify("chinese.txt", text));
+}
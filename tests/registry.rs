@@ -0,0 +1,51 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "registry")]
+
+include!("../test_util.rs");
+
+use include_flate::{assets, flate, init_all};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt" with zstd);
+
+struct Holder;
+
+impl Holder {
+    flate!(const HELP: str from "assets/ascii-printable.txt");
+}
+
+#[test]
+fn test_registry() {
+    let mut idents: Vec<_> = assets().map(|asset| asset.ident()).collect();
+    idents.sort();
+    assert_eq!(idents, ["DATA", "HELP", "TEXT"]);
+
+    let text = assets().find(|asset| asset.ident() == "TEXT").unwrap();
+    if !cfg!(feature = "strip-paths") {
+        assert_eq!(text.name(), "assets/chinese.txt");
+    }
+    assert_eq!(text.decompressed_len(), read_file("chinese.txt").len());
+    assert_eq!(text.compressed_len(), TEXT.compressed_len());
+    assert_eq!(text.algorithm(), TEXT.algorithm());
+    assert!(format!("{:?}", text).contains("TEXT"));
+
+    init_all();
+    assert!(assets().all(|asset| asset.is_initialized()));
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+    verify_str("ascii-printable.txt", Holder::HELP);
+}
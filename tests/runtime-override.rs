@@ -0,0 +1,70 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-override")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/ff.dat");
+
+#[test]
+fn test() {
+    std::env::set_var(
+        "INCLUDE_FLATE_OVERRIDE_DATA",
+        get_file_path(None, "zero.dat"),
+    );
+    verify("zero.dat", &DATA);
+}
+
+flate!(
+    #[cfg(feature = "signed")]
+    pub static SIGNED: [u8] from "assets/base64.txt" signed by "INCLUDE_FLATE_TEST_SIGNING_KEY"
+);
+
+#[test]
+#[cfg(feature = "signed")]
+fn test_signed() {
+    // other contents would not match the signature
+    std::env::set_var(
+        "INCLUDE_FLATE_OVERRIDE_SIGNED",
+        get_file_path(None, "zero.dat"),
+    );
+    verify("base64.txt", &SIGNED);
+}
+
+flate!(pub static TEXT: str from "assets/chinese.txt");
+flate!(pub static KEPT: [u8] from "assets/random.dat");
+flate!(pub static JOINED: str from ["assets/chinese.txt"] joined "\n");
+
+#[test]
+fn test_dir() {
+    let dir = std::env::temp_dir().join(format!("include-flate-override-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    std::fs::copy(
+        get_file_path(None, "emoji.txt"),
+        dir.join("assets").join("chinese.txt"),
+    )
+    .unwrap();
+
+    std::env::set_var("INCLUDE_FLATE_OVERRIDE_DIR", &dir);
+    verify_str("emoji.txt", &TEXT);
+    verify("random.dat", &KEPT);
+    // a list of files has no single path in the directory
+    verify_str("chinese.txt", &JOINED);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
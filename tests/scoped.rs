@@ -0,0 +1,43 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+struct Assets;
+
+impl Assets {
+    flate!(pub const DATA: [u8] from "assets/ff.dat");
+    flate!(pub const TEXT: str from "assets/emoji.txt" with zstd);
+}
+
+#[test]
+fn test_fn_body() {
+    flate!(static DATA1: [u8] from "assets/ff.dat");
+    flate!(static DATA2: [u8] from "assets/zero.dat");
+    flate!(static TEXT: str from "assets/emoji.txt");
+
+    verify("ff.dat", &DATA1);
+    verify("zero.dat", &DATA2);
+    verify_str("emoji.txt", &TEXT);
+}
+
+#[test]
+fn test_impl_block() {
+    verify("ff.dat", Assets::DATA);
+    verify_str("emoji.txt", Assets::TEXT);
+    assert!(std::ptr::eq(Assets::DATA, Assets::DATA));
+}
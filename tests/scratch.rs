@@ -0,0 +1,46 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate_compress::{compress_bytes, decompress_bytes_with, DecompressScratch};
+
+#[test]
+fn test_reuse() {
+    let mut scratch = DecompressScratch::new();
+    for method in [
+        #[cfg(feature = "deflate")]
+        CompressionMethod::Deflate,
+        #[cfg(feature = "zstd")]
+        CompressionMethod::Zstd,
+    ] {
+        for name in ["base64.txt", "chinese.txt", "zero.dat"] {
+            let data = read_file(name);
+            let compressed = compress_bytes(&data, method).unwrap();
+            assert_eq!(
+                decompress_bytes_with(&compressed, method, &mut scratch).unwrap(),
+                data
+            );
+
+            // a truncated stream must not affect the next call
+            let truncated = &compressed[..compressed.len() / 2];
+            assert!(decompress_bytes_with(truncated, method, &mut scratch).is_err());
+            assert_eq!(
+                decompress_bytes_with(&compressed, method, &mut scratch).unwrap(),
+                data
+            );
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+use include_flate_compress::{
+    compress_bytes, compress_seekable, decompress_bytes, decompress_seekable_range, SeekTable,
+    DEFAULT_FRAME_SIZE,
+};
+
+flate!(static RANDOM: [u8] from "assets/random.dat" with zstd_seekable);
+flate!(static CHINESE: str from "assets/chinese.txt" with zstd_seekable);
+flate!(static DATABASE: [u8] from "assets/random.dat" with zstd_seekable);
+flate!(static BLOCKS: [u8] from "assets/base64.txt" with zstd_chunked);
+flate!(static ATLAS: [u8] from "assets/random.dat" with zstd_seekable);
+
+#[test]
+fn test_read_range() {
+    let expected = read_file("random.dat");
+    for range in [
+        0..0,
+        0..10,
+        DEFAULT_FRAME_SIZE - 10..DEFAULT_FRAME_SIZE + 10,
+        100..DEFAULT_FRAME_SIZE * 3 + 7,
+        expected.len() - 10..expected.len(),
+    ] {
+        assert_eq!(RANDOM.read_range(range.clone()), &expected[range]);
+    }
+    assert!(format!("{:?}", RANDOM).contains("initialized: false"));
+
+    verify("random.dat", &RANDOM);
+    assert_eq!(RANDOM.read_range(5..20), &expected[5..20]);
+}
+
+#[test]
+fn test_slice() {
+    let expected = read_file("random.dat");
+    let len = expected.len();
+    assert_eq!(ATLAS.slice(10..20), &expected[10..20]);
+    assert_eq!(ATLAS.slice(..=9), &expected[..=9]);
+    assert_eq!(ATLAS.slice(len - 100..), &expected[len - 100..]);
+    assert!(format!("{:?}", ATLAS).contains("initialized: false"));
+
+    assert_eq!(ATLAS.slice(..).len(), len);
+    verify("random.dat", &ATLAS);
+    assert_eq!(
+        ATLAS.slice(DEFAULT_FRAME_SIZE..),
+        &expected[DEFAULT_FRAME_SIZE..]
+    );
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_slice_included_max() {
+    ATLAS.slice(10..=usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_slice_excluded_max() {
+    use std::ops::Bound;
+
+    ATLAS.slice((Bound::Excluded(usize::MAX), Bound::Unbounded));
+}
+
+#[test]
+fn test_seek() {
+    for (flate, name) in [(&DATABASE, "random.dat"), (&BLOCKS, "base64.txt")] {
+        let expected = read_file(name);
+        let len = expected.len();
+        let mut reader = flate.seekable();
+        assert_eq!(reader.len(), len as u64);
+
+        let mut buf = [0; 100];
+        reader
+            .seek(SeekFrom::Start(DEFAULT_FRAME_SIZE as u64 - 50))
+            .unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, expected[DEFAULT_FRAME_SIZE - 50..][..100]);
+
+        reader.seek(SeekFrom::Current(-20)).unwrap();
+        reader.read_exact(&mut buf[..20]).unwrap();
+        assert_eq!(buf[..20], expected[DEFAULT_FRAME_SIZE + 30..][..20]);
+
+        assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), len as u64 - 10);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, expected[len - 10..]);
+
+        assert!(reader.seek(SeekFrom::Current(-(len as i64) - 1)).is_err());
+    }
+    assert!(format!("{:?}", DATABASE).contains("initialized: false"));
+    assert!(format!("{:?}", BLOCKS).contains("initialized: false"));
+}
+
+#[test]
+fn test_str() {
+    let expected = read_file("chinese.txt");
+    assert_eq!(CHINESE.read_range(3..30), &expected[3..30]);
+    verify_str("chinese.txt", &CHINESE);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_out_of_bounds() {
+    let len = read_file("random.dat").len();
+    RANDOM.read_range(len - 1..len + 1);
+}
+
+#[test]
+fn test_format() {
+    let data = read_file("base64.txt");
+    let compressed = compress_seekable(&data, CompressionMethod::Zstd, 1000).unwrap();
+
+    let table = SeekTable::parse(&compressed).unwrap();
+    assert_eq!(table.decompressed_len(), data.len());
+    assert_eq!(table.frame_count(), data.len().div_ceil(1000));
+
+    // the seek table is a skippable frame, so the data are still valid zstd
+    assert_eq!(
+        decompress_bytes(&compressed, CompressionMethod::Zstd).unwrap(),
+        data
+    );
+    assert_eq!(
+        decompress_seekable_range(&compressed, 999..2001).unwrap(),
+        &data[999..2001]
+    );
+
+    let empty = compress_seekable(&[], CompressionMethod::Zstd, 1000).unwrap();
+    assert!(decompress_bytes(&empty, CompressionMethod::Zstd)
+        .unwrap()
+        .is_empty());
+
+    let plain = compress_bytes(&data, CompressionMethod::Zstd).unwrap();
+    assert!(SeekTable::parse(&plain).is_none());
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn test_deflate_unsupported() {
+    use include_flate_compress::FlateCompressionError;
+
+    assert!(matches!(
+        compress_seekable(b"data", CompressionMethod::Deflate, 1000),
+        Err(FlateCompressionError::SeekableUnsupported(
+            CompressionMethod::Deflate
+        ))
+    ));
+}
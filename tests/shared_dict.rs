@@ -0,0 +1,49 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::codegen::{deflate_file, shared_dict};
+use include_flate::{flate, inflate};
+
+flate!(static EN: str from "assets/locale-en.json" with zstd dict shared);
+flate!(static FR: str from "assets/locale-fr.json" with zstd dict shared);
+flate!(static TOML: [u8] from "assets/config.toml" with zstd dict shared);
+
+#[test]
+fn test() {
+    verify_str("locale-en.json", &EN);
+    verify_str("locale-fr.json", &FR);
+    verify("config.toml", &TOML);
+    assert_eq!(
+        inflate!("assets/config.yaml" with zstd dict shared),
+        read_file("config.yaml")
+    );
+}
+
+#[test]
+fn test_shared() {
+    // the dictionary is trained once per crate
+    let first: &[u8] = shared_dict!();
+    let second: &[u8] = shared_dict!();
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+
+    let shared: &[u8] = deflate_file!("assets/config.toml" zstd dict shared);
+    let standalone: &[u8] = deflate_file!("assets/config.toml" zstd);
+    assert!(shared.len() < standalone.len());
+}
@@ -0,0 +1,81 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "signed")]
+
+include!("../test_util.rs");
+
+use include_flate::{flate, inflate, Asset, Flate, FlateAssets, FlateBytes};
+
+// the verifying key of test 1 in RFC 8032, whose secret key is set in `.cargo/config.toml`
+const KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+flate!(pub static CONFIG: str from "assets/config.toml" signed by "INCLUDE_FLATE_TEST_SIGNING_KEY");
+flate!(static CHUNKED: [u8] from "assets/random.dat" with zstd_chunked signed by "INCLUDE_FLATE_TEST_SIGNING_KEY");
+flate!(static UNSIGNED: [u8] from "assets/ff.dat");
+
+#[derive(FlateAssets)]
+struct Assets {
+    #[flate("assets/base64.txt" with zstd signed by "INCLUDE_FLATE_TEST_SIGNING_KEY")]
+    base64: FlateBytes,
+}
+
+#[test]
+fn test() {
+    verify_str("config.toml", &CONFIG);
+    verify("random.dat", &CHUNKED);
+    verify("base64.txt", Assets::assets().base64);
+    verify(
+        "emoji.txt",
+        &inflate!("assets/emoji.txt" signed by "INCLUDE_FLATE_TEST_SIGNING_KEY"),
+    );
+}
+
+#[test]
+fn test_verifying_key() {
+    assert_eq!(
+        CONFIG.verifying_key().map(|key| hex(key)).as_deref(),
+        Some(KEY)
+    );
+    assert_eq!(UNSIGNED.verifying_key(), None);
+}
+
+static TAMPERED: Flate<Vec<u8>> = Flate::new(
+    Asset {
+        ident: Some("TAMPERED"),
+        name: "base64.txt",
+        source: None,
+        path: None,
+        checksum: None,
+        // the signature of another file
+        signature: Some(include_flate::codegen::file_signature!(
+            "assets/ff.dat" "INCLUDE_FLATE_TEST_SIGNING_KEY"
+        )),
+        bytes: include_flate::codegen::deflate_file!("assets/base64.txt"),
+        chunked: false,
+        base: None,
+    },
+    Asset::load_bytes,
+);
+
+#[test]
+#[should_panic(expected = "InvalidSignature")]
+fn test_tampered() {
+    let _ = TAMPERED.len();
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
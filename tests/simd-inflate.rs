@@ -0,0 +1,69 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "simd-inflate")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+use include_flate_compress::{compress_bytes, decompress_bytes};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+flate!(static TEXT: str from "assets/chinese.txt");
+
+/// Decompresses `data` with `libflate`, which is used when the SIMD path is unavailable.
+fn inflate_scalar(data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    libflate::deflate::Decoder::new(data)
+        .read_to_end(&mut output)
+        .unwrap();
+    output
+}
+
+// On CPUs without the required vector instructions, both sides use the scalar decoder.
+#[test]
+fn test_simd_inflate() {
+    for name in [
+        "random.dat",
+        "chinese.txt",
+        "base64.txt",
+        "zero.dat",
+        "ff.dat",
+        "009f.dat",
+    ] {
+        let original = read_file(name);
+        for level in [
+            CompressionLevel::Fast,
+            CompressionLevel::Balanced,
+            CompressionLevel::Max,
+        ] {
+            let compressed = compress_bytes(
+                &original,
+                CompressionOptions {
+                    method: CompressionMethod::Deflate,
+                    level,
+                    ..CompressionOptions::default()
+                },
+            )
+            .unwrap();
+            let simd = decompress_bytes(&compressed, CompressionMethod::Deflate).unwrap();
+            assert_eq!(simd, inflate_scalar(&compressed), "{} at {:?}", name, level);
+            assert_eq!(simd, original, "{} at {:?}", name, level);
+        }
+    }
+
+    verify("random.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+}
@@ -0,0 +1,40 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate_compress::{apply_compression_with_stats, apply_decompression_with_stats};
+
+#[test]
+fn test() {
+    let data = read_file("base64.txt");
+    for method in methods() {
+        let mut compressed = Vec::new();
+        let stats =
+            apply_compression_with_stats(&mut data.as_slice(), &mut compressed, method).unwrap();
+        assert_eq!(stats.bytes_in, data.len() as u64);
+        assert_eq!(stats.bytes_out, compressed.len() as u64);
+        assert!(stats.ratio() < 10.0);
+
+        let mut decompressed = Vec::new();
+        let stats =
+            apply_decompression_with_stats(&mut compressed.as_slice(), &mut decompressed, method)
+                .unwrap();
+        assert_eq!(decompressed, data);
+        assert_eq!(stats.bytes_in, compressed.len() as u64);
+        assert_eq!(stats.bytes_out, data.len() as u64);
+        assert!(stats.ratio() < 10.0);
+    }
+}
@@ -0,0 +1,119 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use include_flate::{flate, flate_module, Asset, Flate, FlateCompressionError};
+
+flate!(pub static BOXED: [u8] in Box from "assets/random.dat");
+flate!(pub static BOXED_STR: str in Box from "assets/chinese.txt" with zstd);
+flate!(pub static SHARED: [u8] in Arc from "assets/random.dat" with zstd);
+flate!(pub static SHARED_STR: str in Arc from "assets/chinese.txt");
+flate!(pub static STORED: [u8] in Cow from "assets/random.dat" with zstd if saves_at_least(1));
+flate!(pub static STORED_STR: str in Cow from "assets/emoji.txt" with none);
+flate!(pub static COMPRESSED: [u8] in Cow from "assets/zero.dat" with zstd);
+flate!(pub static CHECKED: str in Result from "assets/chinese.txt" with zstd);
+
+static CORRUPTED: Flate<Result<Vec<u8>, FlateCompressionError>> = Flate::new(
+    Asset {
+        ident: Some("CORRUPTED"),
+        name: "corrupted.dat",
+        source: None,
+        path: None,
+        checksum: None,
+        signature: None,
+        bytes: b"not compressed",
+        chunked: false,
+        base: None,
+    },
+    Asset::try_load_bytes,
+);
+
+struct Holder;
+
+impl Holder {
+    flate!(const TEXT: str in Box from "assets/emoji.txt");
+}
+
+flate_module! {
+    mod assets {
+        static DATA: [u8] in Box from "assets/base64.txt";
+        static TEXT: str in Box from "assets/ascii-printable.txt";
+    }
+}
+
+#[test]
+fn test_box() {
+    let _: &Flate<Box<[u8]>> = &BOXED;
+    let _: &Flate<Box<str>> = &BOXED_STR;
+    verify("random.dat", &BOXED);
+    verify_str("chinese.txt", &BOXED_STR);
+    verify_str("emoji.txt", Holder::TEXT);
+    assert_eq!(
+        BOXED_STR.lines_streaming().count(),
+        BOXED_STR.lines().count()
+    );
+}
+
+#[test]
+fn test_arc() {
+    let _: &Flate<Arc<str>> = &SHARED_STR;
+    verify_str("chinese.txt", &SHARED_STR);
+
+    let shared = Arc::clone(&SHARED);
+    assert!(Arc::ptr_eq(&shared, &SHARED));
+    let len = std::thread::spawn(move || shared.len()).join().unwrap();
+    assert_eq!(len, read_file("random.dat").len());
+    verify("random.dat", &SHARED);
+}
+
+#[test]
+fn test_cow() {
+    verify("random.dat", &STORED);
+    verify_str("emoji.txt", &STORED_STR);
+    verify("zero.dat", &COMPRESSED);
+    assert!(matches!(*COMPRESSED, Cow::Owned(_)));
+
+    // the stored bytes are borrowed from the binary unless the file is read from disk
+    if !cfg!(all(feature = "debug-from-disk", debug_assertions)) {
+        assert!(matches!(*STORED, Cow::Borrowed(_)));
+        assert_eq!(STORED.as_ptr(), STORED.compressed().as_ptr());
+        assert!(matches!(*STORED_STR, Cow::Borrowed(_)));
+    }
+}
+
+#[test]
+fn test_result() {
+    match &*CHECKED {
+        Ok(text) => verify_str("chinese.txt", text),
+        Err(err) => panic!("{}", err),
+    }
+    assert!(matches!(
+        *CORRUPTED,
+        Err(FlateCompressionError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn test_module() {
+    verify("base64.txt", &assets::DATA);
+    assert_eq!(
+        assets::get("TEXT").unwrap().as_str().unwrap(),
+        &**assets::TEXT
+    );
+}
@@ -0,0 +1,40 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use include_flate::{flate, inflate};
+
+flate!(pub static RANDOM: [u8] from "assets/random.dat" with none);
+flate!(pub static CHINESE: str from "assets/chinese.txt" with none);
+
+#[test]
+fn test() {
+    verify("random.dat", &RANDOM);
+    verify_str("chinese.txt", &CHINESE);
+    assert_eq!(
+        inflate!("assets/base64.txt" with none),
+        read_file("base64.txt")
+    );
+
+    let lines: Vec<_> = CHINESE.lines_streaming().collect();
+    assert_eq!(lines, CHINESE.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_uncompressed() {
+    assert_eq!(RANDOM.compressed(), read_file("random.dat"));
+    assert_eq!(CHINESE.compressed(), read_file("chinese.txt"));
+}
@@ -0,0 +1,84 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "strip-paths")]
+
+include!("../test_util.rs");
+
+use std::panic::catch_unwind;
+
+use include_flate::codegen::asset_name;
+use include_flate::{flate, flate_module};
+
+flate!(static DATA: [u8] from "assets/009f.dat");
+
+flate_module! {
+    mod exported {
+        static TEXT: str from "assets/emoji.txt";
+    }
+}
+
+#[test]
+fn test_panic_message() {
+    let err = catch_unwind(|| DATA.slice(..=usize::MAX)).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(
+        message.contains(asset_name!("assets/009f.dat")),
+        "{}",
+        message
+    );
+    assert!(!message.contains("009f.dat"), "{}", message);
+    verify("009f.dat", &DATA);
+}
+
+#[test]
+fn test_export_name() {
+    let text = exported::get("TEXT").unwrap();
+    assert_eq!(text.name(), asset_name!("assets/emoji.txt"));
+    assert!(text.name().starts_with("asset-"));
+    verify_str("emoji.txt", text.as_str().unwrap());
+}
+
+#[cfg(feature = "registry")]
+#[test]
+fn test_registry_name() {
+    let data = include_flate::assets()
+        .find(|asset| asset.ident() == "DATA")
+        .unwrap();
+    assert_eq!(data.name(), asset_name!("assets/009f.dat"));
+}
+
+// `debug-from-disk` embeds the absolute path of the file in debug builds only,
+// and `runtime-override` embeds the path to find the file in the override directory
+#[cfg(all(
+    any(not(feature = "debug-from-disk"), not(debug_assertions)),
+    not(feature = "runtime-override")
+))]
+#[test]
+fn test_binary() {
+    let binary = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let contains = |needle: &[u8]| binary.windows(needle.len()).any(|window| window == needle);
+    for id in [
+        asset_name!("assets/009f.dat"),
+        asset_name!("assets/emoji.txt"),
+    ] {
+        assert!(contains(id.as_bytes()), "{} is not in the binary", id);
+    }
+    // the paths are joined at runtime, so that they are not literals in this test
+    for name in ["009f.dat", "emoji.txt"] {
+        let path = ["assets", name].join("/");
+        assert!(!contains(path.as_bytes()), "{} is in the binary", path);
+    }
+}
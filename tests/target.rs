@@ -0,0 +1,71 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "deflate", feature = "zstd"))]
+
+include!("../test_util.rs");
+
+use include_flate::build::Header;
+use include_flate::codegen::deflate_file;
+use include_flate::flate;
+
+flate!(static BY_ARCH: [u8] from "assets/random.dat" with zstd on "x86_64-*", deflate on "wasm32-*");
+flate!(static BY_OS: str from "assets/chinese.txt" with zstd(long) on "*-linux-*", deflate);
+flate!(static FALLBACK: [u8] from "assets/zero.dat" with none on "wasm32-*");
+flate!(static CONDITIONAL: [u8] from "assets/zero.dat" with zstd on "*", deflate
+    if larger_than(1MiB));
+
+#[test]
+fn test() {
+    verify("random.dat", &BY_ARCH);
+    verify_str("chinese.txt", &BY_OS);
+    verify("zero.dat", &FALLBACK);
+    verify("zero.dat", &CONDITIONAL);
+}
+
+#[test]
+fn test_method() {
+    let by_arch: &[u8] =
+        deflate_file!("assets/zero.dat" zstd on "x86_64-*", deflate on "wasm32-*", none);
+    let expected = if cfg!(target_arch = "x86_64") {
+        CompressionMethod::Zstd
+    } else if cfg!(target_arch = "wasm32") {
+        CompressionMethod::Deflate
+    } else {
+        CompressionMethod::Store
+    };
+    assert_eq!(method(by_arch), expected);
+
+    // targets that no pattern matches use the default algorithm
+    let by_os: &[u8] = deflate_file!("assets/zero.dat" none on "*-windows-msvc");
+    let expected = if cfg!(all(target_os = "windows", target_env = "msvc")) {
+        CompressionMethod::Store
+    } else {
+        CompressionMethod::DEFAULT
+    };
+    assert_eq!(method(by_os), expected);
+
+    // the first pattern that matches wins
+    let first: &[u8] = deflate_file!("assets/zero.dat" deflate on "*", zstd on "*");
+    assert_eq!(method(first), CompressionMethod::Deflate);
+
+    let stored: &[u8] = deflate_file!("assets/zero.dat" zstd on "*" if larger_than(1MiB));
+    assert_eq!(method(stored), CompressionMethod::Store);
+}
+
+/// Returns the method recorded in the header of `data`.
+fn method(data: &[u8]) -> CompressionMethod {
+    Header::parse(data).unwrap().0.method
+}
@@ -0,0 +1,38 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "test-override")]
+
+include!("../test_util.rs");
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/ff.dat");
+flate!(pub static TEXT: str from "assets/chinese.txt");
+
+#[test]
+fn test_override() {
+    DATA.set_for_test(read_file("zero.dat"));
+    verify("zero.dat", &DATA);
+
+    TEXT.set_for_test("fixture");
+    assert_eq!(TEXT.as_str(), "fixture");
+}
+
+#[test]
+fn test_original() {
+    verify("ff.dat", &DATA);
+    verify_str("chinese.txt", &TEXT);
+}
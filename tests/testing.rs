@@ -0,0 +1,35 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use include_flate::testing::{assert_matches_source, assert_roundtrip};
+use include_flate::{flate, CompressionMethod};
+
+flate!(pub static DATA: [u8] from "assets/random.dat" with zstd);
+flate!(pub static TEXT: str from "assets/emoji.txt");
+
+#[test]
+fn test() {
+    assert_matches_source!(DATA, "assets/random.dat");
+    assert_matches_source!(TEXT, "assets/emoji.txt");
+    #[cfg(feature = "deflate")]
+    assert_roundtrip(&DATA, CompressionMethod::Deflate);
+    assert_roundtrip(TEXT.as_bytes(), CompressionMethod::Zstd);
+}
+
+#[test]
+#[should_panic(expected = "data differ from")]
+fn test_mismatch() {
+    assert_matches_source!(DATA, "assets/zero.dat");
+}
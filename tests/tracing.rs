@@ -0,0 +1,111 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "tracing")]
+
+include!("../test_util.rs");
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use include_flate::flate;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+flate!(static DATA: [u8] from "assets/random.dat");
+
+/// The fields of a span or an event, formatted with `Debug` except for strings.
+#[derive(Default, Clone)]
+struct Fields(HashMap<String, String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+/// Records the fields of every span and event.
+#[derive(Default)]
+struct Recorder {
+    spans: Mutex<Vec<(&'static str, Fields)>>,
+    events: Mutex<Vec<Fields>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata().name(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut spans[span.into_u64() as usize - 1].1);
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_tracing() {
+    let recorder = Arc::new(Recorder::default());
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let _: &[u8] = &DATA;
+    });
+    let len = read_file("random.dat").len().to_string();
+
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(spans.len(), 1);
+    let (name, span) = &spans[0];
+    assert_eq!(*name, "include_flate::load");
+    if !cfg!(feature = "strip-paths") {
+        assert_eq!(span.0["asset"], "assets/random.dat");
+    }
+    assert_eq!(span.0["method"], format!("{:?}", DATA.algorithm()));
+    assert_eq!(span.0["compressed_size"], DATA.compressed_len().to_string());
+    assert_eq!(span.0["decompressed_size"], len);
+    assert!(span.0.contains_key("duration"));
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0["decompressed_size"], len);
+    assert_eq!(events[0].0["duration"], span.0["duration"]);
+    assert!(events[0].0["message"].starts_with("Loaded "));
+
+    verify("random.dat", &DATA);
+}
@@ -0,0 +1,50 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::borrow::Borrow;
+
+use include_flate::flate;
+
+flate!(pub static DATA: [u8] from "assets/ff.dat");
+flate!(pub static TEXT: str from "assets/ascii-printable.txt");
+
+fn as_bytes<T: AsRef<[u8]> + ?Sized>(value: &T) -> &[u8] {
+    value.as_ref()
+}
+
+fn borrow_str<T: Borrow<str> + ?Sized>(value: &T) -> &str {
+    value.borrow()
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        format!("{:?}", DATA),
+        "Flate { initialized: false, len: None }"
+    );
+
+    verify("ff.dat", as_bytes(&DATA));
+    verify_str("ascii-printable.txt", borrow_str(&TEXT));
+    assert_eq!(as_bytes(&TEXT), TEXT.as_bytes());
+    assert_eq!(&DATA[1..4], &[0xff; 3]);
+    assert_eq!(TEXT[..5].as_bytes(), &read_file("ascii-printable.txt")[..5]);
+
+    assert_eq!(
+        format!("{:?}", DATA),
+        "Flate { initialized: true, len: Some(1048576) }"
+    );
+}
@@ -0,0 +1,46 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+#[allow(unused_imports)]
+use include_flate::{flate, flate_group};
+
+#[cfg(feature = "validate-json")]
+flate!(static JSON: str from "assets/locale-en.json" with deflate validate json);
+#[cfg(feature = "validate-toml")]
+flate!(static TOML: str from "assets/config.toml" validate toml);
+#[cfg(feature = "validate-yaml")]
+flate_group!(#[cfg(all())] {
+    static YAML: str from "assets/config.yaml" validate yaml;
+});
+
+#[cfg(feature = "validate-json")]
+#[test]
+fn test_json() {
+    verify_str("locale-en.json", &JSON);
+}
+
+#[cfg(feature = "validate-toml")]
+#[test]
+fn test_toml() {
+    verify_str("config.toml", &TOML);
+}
+
+#[cfg(feature = "validate-yaml")]
+#[test]
+fn test_yaml() {
+    verify_str("config.yaml", &YAML);
+}
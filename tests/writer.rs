@@ -0,0 +1,165 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+include!("../test_util.rs");
+
+use std::io::{self, Write};
+
+use include_flate::build::compress;
+use include_flate::{decode_to_writer, flate, Asset, Flate, FlateCompressionError};
+
+flate!(pub static DATA: [u8] from "assets/random.dat" with zstd);
+#[cfg(feature = "deflate")]
+flate!(pub static TEXT: str from "assets/chinese.txt" with deflate_chunked);
+
+/// Records the size of each write instead of the data.
+#[derive(Default)]
+struct Sizes(Vec<usize>);
+
+impl Write for Sizes {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.push(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_flate() {
+    let mut out = Vec::new();
+    assert_eq!(
+        DATA.decode_to_writer(&mut out).unwrap(),
+        read_file("random.dat").len() as u64
+    );
+    verify("random.dat", &out);
+
+    #[cfg(feature = "deflate")]
+    {
+        let mut out = Vec::new();
+        TEXT.decode_to_writer(&mut out).unwrap();
+        verify("chinese.txt", &out);
+    }
+
+    // the contents are streamed in pieces
+    let mut sizes = Sizes::default();
+    DATA.decode_to_writer(&mut sizes).unwrap();
+    assert!(sizes.0.len() > 1);
+    assert_eq!(sizes.0.iter().sum::<usize>(), read_file("random.dat").len());
+}
+
+#[test]
+fn test_bytes() {
+    let data = read_file("chinese.txt");
+    let compressed = compress(&data, CompressionMethod::default()).unwrap();
+    let mut out = Vec::new();
+    decode_to_writer(&compressed, &mut out).unwrap();
+    assert_eq!(out, data);
+
+    assert!(matches!(
+        decode_to_writer(&data, &mut out),
+        Err(FlateCompressionError::InvalidHeader)
+    ));
+}
+
+/// Returns `compressed` with `len` as the size in the header.
+fn with_len(compressed: &[u8], len: u64) -> Vec<u8> {
+    let mut bytes = compressed.to_vec();
+    // the size is a little-endian `u64` after the magic number and the method
+    bytes[4..12].copy_from_slice(&len.to_le_bytes());
+    bytes
+}
+
+/// Returns a static whose embedded data are `bytes`.
+fn embedded(bytes: Vec<u8>) -> &'static Flate<Vec<u8>> {
+    let asset = Asset {
+        ident: None,
+        name: "tampered.txt",
+        source: None,
+        path: None,
+        checksum: None,
+        signature: None,
+        bytes: bytes.leak(),
+        chunked: false,
+        base: None,
+    };
+    Box::leak(Box::new(Flate::new(asset, Asset::load_bytes)))
+}
+
+#[test]
+fn test_bounded() {
+    let data = read_file("chinese.txt");
+    let compressed = compress(&data, CompressionMethod::default()).unwrap();
+    let with_len = |len: u64| with_len(&compressed, len);
+
+    let mut out = Vec::new();
+    let limit = data.len() as u64 - 1;
+    assert!(matches!(
+        decode_to_writer(&with_len(limit), &mut out),
+        Err(FlateCompressionError::TooLarge { limit: actual }) if actual == limit
+    ));
+    assert!(out.len() as u64 <= limit);
+
+    let mut out = Vec::new();
+    match decode_to_writer(&with_len(data.len() as u64 + 1), &mut out) {
+        Err(FlateCompressionError::IoError(err)) => {
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof)
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // the CRC-32 follows the size
+    let mut corrupted = compressed.clone();
+    corrupted[12] ^= 1;
+    assert!(matches!(
+        decode_to_writer(&corrupted, &mut Vec::new()),
+        Err(FlateCompressionError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_flate_bounded() {
+    let data = read_file("chinese.txt");
+    let compressed = compress(&data, CompressionMethod::default()).unwrap();
+
+    let limit = data.len() as u64 - 1;
+    let short = embedded(with_len(&compressed, limit));
+    let mut out = Vec::new();
+    let err = short.decode_to_writer(&mut out).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(out.len() as u64 <= limit);
+    let err = short.reader().read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let long = embedded(with_len(&compressed, data.len() as u64 + 1));
+    let err = long.decode_to_writer(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    let err = long.reader().read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    // the CRC-32 follows the size
+    let mut corrupted = compressed.clone();
+    corrupted[12] ^= 1;
+    let err = embedded(corrupted)
+        .decode_to_writer(&mut Vec::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let mut out = Vec::new();
+    embedded(compressed).decode_to_writer(&mut out).unwrap();
+    assert_eq!(out, data);
+}
@@ -0,0 +1,90 @@
+// include-flate
+// Copyright (C) SOFe
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "zstd")]
+
+include!("../test_util.rs");
+
+use include_flate::build::compress;
+use include_flate::codegen::deflate_file;
+use include_flate::{decode, flate, inflate};
+use include_flate_compress::{decompress_bytes, CompressionExtras, Header};
+use std::io;
+
+flate!(pub static LONG: [u8] from "assets/random.dat" with zstd(long));
+flate!(pub static WINDOW: str from "assets/chinese.txt" with zstd(long = 30, block_size = 1KiB));
+flate!(pub static SEEKABLE: str from "assets/chinese.txt" with zstd_seekable(window_log = 16));
+
+#[test]
+fn test() {
+    verify("random.dat", &LONG);
+    verify_str("chinese.txt", &WINDOW);
+    verify_str("chinese.txt", &SEEKABLE);
+    assert_eq!(SEEKABLE.read_range(3..9), read_file("chinese.txt")[3..9]);
+    assert_eq!(
+        inflate!("assets/base64.txt" with zstd(window_log = 20)),
+        read_file("base64.txt")
+    );
+}
+
+#[test]
+fn test_large_window() {
+    let mut extras = CompressionExtras::default();
+    extras.zstd_long = true;
+    extras.zstd_window_log = Some(30);
+    let options = CompressionOptions {
+        method: CompressionMethod::Zstd,
+        extras,
+        ..CompressionOptions::default()
+    };
+
+    let data = read_file("random.dat").repeat(4);
+    let compressed = compress(&data, options).unwrap();
+    assert_eq!(decode("random.dat", &compressed), data);
+
+    // only the header raises the window limit of the decoder
+    let (header, body) = Header::parse(&compressed).unwrap();
+    assert_eq!(header.zstd_window_log, Some(30));
+    assert!(decompress_bytes(body, CompressionMethod::Zstd).is_err());
+    assert!(CompressionMethod::Zstd
+        .decoder(body)
+        .and_then(|mut decoder| Ok(io::copy(&mut decoder, &mut io::sink())?))
+        .is_err());
+    assert_eq!(header.decompress(body).unwrap(), data);
+}
+
+#[test]
+fn test_header_window() {
+    let long: &[u8] = deflate_file!("assets/chinese.txt" zstd(long));
+    assert_eq!(Header::parse(long).unwrap().0.zstd_window_log, None);
+    let window: &[u8] = deflate_file!("assets/chinese.txt" zstd(long = 30));
+    let (header, body) = Header::parse(window).unwrap();
+    assert_eq!(header.zstd_window_log, Some(30));
+    assert_eq!(header.decompress(body).unwrap(), read_file("chinese.txt"));
+}
+
+#[cfg(feature = "zstd-multithread")]
+mod multithread {
+    use super::*;
+
+    flate!(pub static WORKERS: [u8] from "assets/random.dat" with zstd(workers = 2));
+    flate!(pub static SINGLE: str from "assets/chinese.txt" with zstd(long, workers = 0));
+
+    #[test]
+    fn test() {
+        verify("random.dat", &WORKERS);
+        verify_str("chinese.txt", &SINGLE);
+    }
+}